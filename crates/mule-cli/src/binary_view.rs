@@ -0,0 +1,148 @@
+use std::{any::Any, ops::Range, path::Path};
+
+use ratatui::{buffer::Buffer, layout::Rect, widgets::ListState};
+
+use crate::{
+    InteractiveCommand,
+    hash::HashAlgorithm,
+    hex::{GroupBytes, HexState},
+    patch::Patches,
+    theme::Theme,
+};
+
+/// Byte order of the multi-byte fields of a file, independent of the format.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+/// A parsed binary together with the state of its interactive view. The
+/// `FormatParser` of a format creates it, the app draws the binary and
+/// forwards the commands to it through this trait only.
+///
+/// The methods about hex views and disassemblies default to "not available"
+/// for views without them.
+pub trait BinaryView: Any + Send {
+    /// Name of the format, e.g. "Mach-O"
+    fn type_name(&self) -> &'static str;
+
+    /// A one line summary of the binary, e.g. "Mach-O, arm64, executable".
+    fn summary(&self) -> String;
+
+    /// Byte order of the multi-byte fields of the file, None if unknown.
+    fn byte_order(&self) -> Option<ByteOrder>;
+
+    /// Appends the header fields and the lists of the file pane to the plain
+    /// text report of `--dump`.
+    fn dump(&self, data: &[u8], out: &mut String);
+
+    fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        data: &[u8],
+        patches: &Patches,
+        theme: &Theme,
+    );
+
+    /// Returns the message for the status bar, None if the view has nothing
+    /// to report or does not support the command, e.g. a `Toggle` of
+    /// another format.
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String>;
+
+    /// Called with the file the binary was read from, e.g. a GB ROM looks
+    /// for its save next to it.
+    fn set_path(&mut self, _path: &Path) {}
+
+    /// File byte range of a section, bank or similar by its name.
+    fn named_region(&self, _name: &str) -> Option<Range<usize>> {
+        None
+    }
+
+    /// The structure the file offset lies in, e.g. "__TEXT,__text +0x1C".
+    fn describe_offset(&self, _offset: usize) -> Option<String> {
+        None
+    }
+
+    /// The algorithm of `:hash` without an argument.
+    fn default_hash(&self) -> HashAlgorithm {
+        HashAlgorithm::Sha256
+    }
+
+    /// Position of the focused pane in the focus cycle of the view, saved in
+    /// the session and restored with `set_focus_index`.
+    fn focus_index(&self) -> usize {
+        0
+    }
+
+    fn set_focus_index(&mut self, _ix: usize) {}
+
+    fn set_hex_group_bytes(&mut self, _group_bytes: GroupBytes) {}
+
+    fn set_hex_byte_colors(&mut self, _byte_colors: bool) {}
+
+    fn clear_disassemblies(&mut self) {}
+
+    /// Parses the binary again after its bytes were edited, for views that
+    /// show parsed fields of the edited bytes, e.g. the GB header.
+    fn reparse(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// True if the focused pane is a header that is edited with `:header`
+    /// instead of in a hex view.
+    fn header_focused(&self) -> bool {
+        false
+    }
+
+    /// Keeps the scroll positions of the hex views after a terminal resize.
+    fn relayout(&mut self) {}
+
+    fn focused_hex(&mut self) -> Option<&mut HexState> {
+        None
+    }
+
+    /// File byte range of the selected region (segment, section, bank), if any.
+    fn selected_region(&self) -> Option<Range<usize>> {
+        None
+    }
+
+    /// File offset of the first byte of the data in the focused hex view.
+    fn focused_hex_start(&self) -> Option<usize> {
+        None
+    }
+
+    /// File offset of the first byte shown in the focused hex view.
+    fn shown_offset(&self) -> Option<usize> {
+        None
+    }
+
+    /// File offset of the byte at the hex cursor, or of the first shown byte
+    /// while not editing.
+    fn inspected_offset(&self) -> Option<usize> {
+        None
+    }
+
+    /// The lines and list state of the shown disassembly, if any.
+    fn shown_disassembly(&mut self) -> Option<(Vec<&str>, &mut ListState)> {
+        None
+    }
+
+    /// File offset of the target of the jump selected in a disassembly.
+    fn selected_jump_target(&self) -> Option<usize> {
+        None
+    }
+
+    /// Shows the file `offset` in a hex view, if the view supports it.
+    fn show_offset(&mut self, _offset: usize) -> bool {
+        false
+    }
+}
+
+impl dyn BinaryView {
+    /// True if the view is a `T`, e.g. for the commands of a single format.
+    pub fn is<T: BinaryView>(&self) -> bool {
+        (self as &dyn Any).is::<T>()
+    }
+}
@@ -0,0 +1,698 @@
+use std::ops::RangeInclusive;
+
+use ratatui::widgets::ListState;
+
+use crate::{
+    Mule, ViewToggle, parse_offset, recent::RecentPicker, session::Session,
+    view_entropy::EntropyState, view_minimap::MinimapState,
+};
+
+/// What happens after a command ran.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CommandOutcome {
+    Continue,
+    Quit,
+}
+
+/// Why `:q` and the quit key refuse to quit.
+pub const UNSAVED_EDITS_ERROR: &str = "unsaved edits, write them with :w or quit with :q!";
+
+type Handler = fn(&mut Mule, &[String]) -> Result<CommandOutcome, String>;
+
+/// A command of the command mode, typed as `:<name> <args>`.
+pub struct Command {
+    /// The name and its aliases.
+    pub names: &'static [&'static str],
+    /// The arguments as shown in the help, empty if there are none.
+    pub args: &'static str,
+    /// The allowed number of arguments, checked before the handler runs.
+    arity: RangeInclusive<usize>,
+    pub description: &'static str,
+    handler: Handler,
+}
+
+impl Command {
+    /// The names and the arguments, e.g. `:reload / :e`.
+    pub fn usage(&self) -> String {
+        let names: Vec<String> = self.names.iter().map(|n| format!(":{}", n)).collect();
+        let mut usage = names.join(" / ");
+        if !self.args.is_empty() {
+            usage.push(' ');
+            usage.push_str(self.args);
+        }
+        usage
+    }
+}
+
+pub static COMMANDS: [Command; 35] = [
+    Command {
+        names: &["o"],
+        args: "<path>",
+        arity: 1..=1,
+        description: "open a binary in a new tab, Tab completes, quote paths with spaces",
+        handler: |mule, args| {
+            mule.start_open(&args[0])?;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["q"],
+        args: "",
+        arity: 0..=0,
+        description: "quit, refused while there are unsaved edits",
+        handler: |mule, _| {
+            if mule.has_unsaved_edits() {
+                return Err(UNSAVED_EDITS_ERROR.to_string());
+            }
+            Ok(CommandOutcome::Quit)
+        },
+    },
+    Command {
+        names: &["q!"],
+        args: "",
+        arity: 0..=0,
+        description: "quit and discard unsaved edits",
+        handler: |_, _| Ok(CommandOutcome::Quit),
+    },
+    Command {
+        names: &["i"],
+        args: "",
+        arity: 0..=0,
+        description: "return to the interactive mode",
+        // nothing to do, every command returns to the interactive mode
+        handler: |_, _| Ok(CommandOutcome::Continue),
+    },
+    Command {
+        names: &["goto"],
+        args: "<offset>",
+        arity: 1..=1,
+        description: "show the decimal or 0x file offset in a hex view",
+        handler: |mule, args| {
+            mule.goto_offset(parse_offset(&args[0])?);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["at"],
+        args: "<offset>",
+        arity: 1..=1,
+        description: "show the section, segment or bank a decimal or 0x offset lies in",
+        handler: |mule, args| {
+            mule.describe_offset(parse_offset(&args[0])?);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["find"],
+        args: "<hexbytes>",
+        arity: 1..=usize::MAX,
+        description: "search the binary for a byte pattern",
+        handler: |mule, args| {
+            mule.find(&args.concat());
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["bn"],
+        args: "",
+        arity: 0..=0,
+        description: "switch to the next opened binary",
+        handler: |mule, _| {
+            mule.switch_binary(1);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["bp"],
+        args: "",
+        arity: 0..=0,
+        description: "switch to the previous opened binary",
+        handler: |mule, _| {
+            mule.switch_binary(-1);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["bd"],
+        args: "",
+        arity: 0..=0,
+        description: "close the current binary, refused while it has unsaved edits",
+        handler: |mule, _| {
+            if mule.active_has_unsaved_edits() {
+                return Err("unsaved edits, write them with :w or close with :bd!".to_string());
+            }
+            mule.close_binary();
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["bd!"],
+        args: "",
+        arity: 0..=0,
+        description: "close the current binary and discard its unsaved edits",
+        handler: |mule, _| {
+            mule.close_binary();
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["reload", "e"],
+        args: "",
+        arity: 0..=0,
+        description: "read the current binary again from disk, refused while it has unsaved edits",
+        handler: |mule, _| {
+            if mule.active_has_unsaved_edits() {
+                return Err("unsaved edits, write them with :w or reload with :e!".to_string());
+            }
+            mule.reload();
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["reload!", "e!"],
+        args: "",
+        arity: 0..=0,
+        description: "read the current binary again from disk and discard its unsaved edits",
+        handler: |mule, _| {
+            mule.reload();
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["w"],
+        args: "[path]",
+        arity: 0..=1,
+        description: "write the edits back, or the selected region to a path (.hex: Intel HEX)",
+        handler: |mule, args| {
+            match args.first() {
+                None => mule.write_patches(),
+                Some(path) => mule.write_region(path, false),
+            }
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["w!"],
+        args: "<path>",
+        arity: 1..=1,
+        description: "write the selected region to a path, overwrites the file",
+        handler: |mule, args| {
+            mule.write_region(&args[0], true);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["export-view"],
+        args: "<path>",
+        arity: 1..=1,
+        description: "write the shown view with its colors to an .html or ANSI text file",
+        handler: |mule, args| {
+            mule.export_view(&args[0]);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["hash"],
+        args: "[algorithm]",
+        arity: 0..=1,
+        description: "show the crc32, md5 or sha256 of the binary",
+        handler: |mule, args| {
+            mule.hash(args.first().map_or("", String::as_str));
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["entropy"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the entropy pane",
+        handler: |mule, _| {
+            mule.entropy = match mule.entropy {
+                Some(_) => None,
+                None => Some(EntropyState::new()),
+            };
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["tiles"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the GB bank between hex and 2bpp tiles",
+        handler: |mule, _| {
+            mule.toggle_view(
+                ViewToggle::Tiles,
+                "tiles are only available for GameBoy ROMs",
+            );
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["header"],
+        args: "<field> <value>",
+        arity: 2..=usize::MAX,
+        description: "set the GB title, licensee, cartridge, rom or ram header field",
+        handler: |mule, args| {
+            mule.edit_gb_header(&args[0], &args[1..].join(" "))?;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["sav"],
+        args: "",
+        arity: 0..=0,
+        description: "open the battery save next to the GB ROM as hex",
+        handler: |mule, _| {
+            mule.open_save()?;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["memmap"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the Mach-O segments drawn by their vm address and size",
+        handler: |mule, _| {
+            mule.toggle_view(
+                ViewToggle::MemoryMap,
+                "the memory map is only available for Mach-O binaries",
+            );
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["dylibs"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the Mach-O rpaths and dylibs in load order",
+        handler: |mule, _| {
+            mule.toggle_view(
+                ViewToggle::Dylibs,
+                "the dylibs are only available for Mach-O binaries",
+            );
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["gaps"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the unused space between the sections of a Mach-O segment",
+        handler: |mule, _| {
+            mule.toggle_view(
+                ViewToggle::SectionGaps,
+                "the section gaps are only available for Mach-O binaries",
+            );
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["minimap"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the file overview, click it to jump to an offset",
+        handler: |mule, _| {
+            mule.minimap = match mule.minimap {
+                Some(_) => None,
+                None => Some(MinimapState::new()),
+            };
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["inspector"],
+        args: "",
+        arity: 0..=0,
+        description: "toggle the integer/float interpretation of the bytes at the cursor",
+        handler: |mule, _| {
+            mule.show_inspector = !mule.show_inspector;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["restore"],
+        args: "",
+        arity: 0..=0,
+        description: "reopen the binaries of the last session",
+        handler: |mule, _| match Session::load()? {
+            Some(session) => {
+                mule.restore_session(session);
+                Ok(CommandOutcome::Continue)
+            }
+            None => Err("no session to restore".to_string()),
+        },
+    },
+    Command {
+        names: &["mark"],
+        args: "<name>",
+        arity: 1..=1,
+        description: "bookmark the offset shown in the focused hex view",
+        handler: |mule, args| {
+            mule.mark(&args[0]);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["marks"],
+        args: "",
+        arity: 0..=0,
+        description: "list the bookmarks of the current binary",
+        handler: |mule, _| {
+            mule.show_marks = true;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["recent"],
+        args: "",
+        arity: 0..=0,
+        description: "pick one of the recently opened files",
+        handler: |mule, _| {
+            mule.recent_picker = Some(RecentPicker::new(&mule.recent));
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["jump"],
+        args: "<name>",
+        arity: 1..=1,
+        description: "show a bookmarked offset",
+        handler: |mule, args| {
+            mule.jump(&args[0]);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["diff"],
+        args: "[<tab> <tab>]",
+        arity: 0..=2,
+        description: "compare two binaries, default: this and the next tab",
+        handler: |mule, args| {
+            mule.diff(args);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["strings"],
+        args: "[len] [name]",
+        arity: 0..=2,
+        description: "list strings, optionally in a section or bank<N>",
+        handler: |mule, args| {
+            mule.strings(args);
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["palette"],
+        args: "",
+        arity: 0..=0,
+        description: "pick a command from a filterable list",
+        handler: |mule, _| {
+            mule.open_palette();
+            Ok(CommandOutcome::Continue)
+        },
+    },
+    Command {
+        names: &["help"],
+        args: "",
+        arity: 0..=0,
+        description: "show this help",
+        handler: |mule, _| {
+            mule.show_help = true;
+            Ok(CommandOutcome::Continue)
+        },
+    },
+];
+
+/// Runs the command typed as `:<name> <args>`. Fails for unknown commands,
+/// a wrong number of arguments and with the error of the command itself.
+pub fn dispatch(mule: &mut Mule, input: &str) -> Result<CommandOutcome, String> {
+    let mut tokens = tokenize(input)?.into_iter();
+    let name = tokens.next().unwrap_or_default();
+    let command = name
+        .strip_prefix(':')
+        .and_then(|name| COMMANDS.iter().find(|c| c.names.contains(&name)))
+        .ok_or(format!("unknown command: {}", input))?;
+    let args: Vec<String> = tokens.collect();
+    if !command.arity.contains(&args.len()) {
+        return Err(format!("usage: {}", command.usage()));
+    }
+    (command.handler)(mule, &args)
+}
+
+/// Splits the input at whitespace, an argument in double quotes can contain
+/// whitespace.
+fn tokenize(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut token = String::new();
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err(format!("unterminated quote in {}", input)),
+                }
+            }
+        } else {
+            token.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// The commands whose usage or description matches the typed query. Commands
+/// matching with their name come first.
+pub struct Palette {
+    pub query: String,
+    pub matches: Vec<&'static Command>,
+    pub list_state: ListState,
+}
+
+impl Palette {
+    pub fn new() -> Palette {
+        let mut palette = Palette {
+            query: String::new(),
+            matches: Vec::new(),
+            list_state: ListState::default(),
+        };
+        palette.filter();
+        palette
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.filter();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.filter();
+    }
+
+    pub fn select_next(&mut self) {
+        self.list_state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.list_state.select_previous();
+    }
+
+    pub fn selected(&self) -> Option<&'static Command> {
+        self.list_state
+            .selected()
+            .and_then(|ix| self.matches.get(ix).copied())
+    }
+
+    fn filter(&mut self) {
+        let query = self.query.to_lowercase();
+        let mut scored: Vec<(usize, &'static Command)> = COMMANDS
+            .iter()
+            .filter_map(|c| match_score(&query, c).map(|score| (score, c)))
+            .collect();
+        // stable, keeps the order of the registry for equal scores
+        scored.sort_by_key(|(score, _)| *score);
+        self.matches = scored.into_iter().map(|(_, c)| c).collect();
+        self.list_state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+}
+
+// Lower is better: a name starting with the query, a name containing it,
+// the query's chars in order in the usage and at last in the description.
+fn match_score(query: &str, command: &Command) -> Option<usize> {
+    let query = query.strip_prefix(':').unwrap_or(query);
+    if command.names.iter().any(|n| n.starts_with(query)) {
+        Some(0)
+    } else if command.names.iter().any(|n| n.contains(query)) {
+        Some(1)
+    } else if is_subsequence(query, &command.usage()) {
+        Some(2)
+    } else if is_subsequence(query, &command.description.to_lowercase()) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+fn is_subsequence(query: &str, text: &str) -> bool {
+    let mut chars = text.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        jumps::Location, keymap::Action, open::OpenedBinary, view_macho::MachoView,
+        view_raw::RawView,
+    };
+
+    fn mule_with_data(data: Vec<u8>) -> Mule {
+        let mut mule = Mule::new();
+        let opened = OpenedBinary {
+            view: Box::new(RawView::new()),
+            data,
+            slice: None,
+            compressed: false,
+            parse_error: None,
+        };
+        mule.add_opened("data.bin", Path::new("data.bin"), vec![opened]);
+        mule
+    }
+
+    #[test]
+    fn open_without_path() {
+        let mut mule = mule_with_data(vec![1, 2, 3]);
+
+        mule.input = ":o".to_string();
+        assert!(!mule.exec_command());
+        assert_eq!(mule.status, "usage: :o <path>");
+        assert!(mule.status_is_error);
+        assert_eq!(mule.project_state.binaries.len(), 1);
+        assert_eq!(mule.project_state.active, 0);
+        assert_eq!(mule.project_state.binaries[0].data, [1, 2, 3]);
+    }
+
+    #[test]
+    fn close_with_unsaved_edits() {
+        let mut mule = mule_with_data(vec![1, 2, 3]);
+        let binary = &mut mule.project_state.binaries[0];
+        binary.patches.set(&mut binary.data, 0, 0xFF);
+
+        mule.input = ":bd".to_string();
+        assert!(!mule.exec_command());
+        assert!(mule.status_is_error);
+        assert_eq!(mule.project_state.binaries.len(), 1);
+
+        mule.input = ":e".to_string();
+        assert!(!mule.exec_command());
+        assert!(mule.status.starts_with("unsaved edits"));
+        assert_eq!(mule.project_state.binaries[0].data, [0xFF, 2, 3]);
+
+        mule.input = ":bd!".to_string();
+        assert!(!mule.exec_command());
+        assert!(mule.project_state.binaries.is_empty());
+    }
+
+    #[test]
+    fn quit_key_with_unsaved_edits() {
+        let mut mule = mule_with_data(vec![1, 2, 3]);
+        let binary = &mut mule.project_state.binaries[0];
+        binary.patches.set(&mut binary.data, 0, 0xFF);
+
+        assert!(!mule.handle_action(Action::Quit));
+        assert!(mule.status_is_error);
+        assert_eq!(mule.status, UNSAVED_EDITS_ERROR);
+
+        mule.project_state.binaries[0].patches.saved();
+        assert!(mule.handle_action(Action::Quit));
+    }
+
+    #[test]
+    fn unknown_command() {
+        let mut mule = Mule::new();
+        assert_eq!(
+            dispatch(&mut mule, ":frobnicate 1"),
+            Err("unknown command: :frobnicate 1".to_string())
+        );
+    }
+
+    #[test]
+    fn too_many_arguments() {
+        let mut mule = Mule::new();
+        assert_eq!(dispatch(&mut mule, ":q foo"), Err("usage: :q".to_string()));
+        assert_eq!(dispatch(&mut mule, ":q"), Ok(CommandOutcome::Quit));
+    }
+
+    #[test]
+    fn usage_on_wrong_arity() {
+        let mut mule = Mule::new();
+        assert_eq!(
+            dispatch(&mut mule, ":o a.bin b.bin"),
+            Err("usage: :o <path>".to_string())
+        );
+    }
+
+    #[test]
+    fn goto_is_a_jump_only_when_shown() {
+        let start = Location {
+            focus: 0,
+            offset: None,
+        };
+        let data = mule_macho::test_util::macho_bytes(&[]);
+        let opened = OpenedBinary {
+            view: Box::new(MachoView::new(mule_macho::load(&data).unwrap())),
+            data,
+            slice: None,
+            compressed: false,
+            parse_error: None,
+        };
+        let mut mule = Mule::new();
+        mule.add_opened("a.out", Path::new("a.out"), vec![opened]);
+
+        // the header is in no section of the Mach-O view
+        mule.input = ":goto 0x4".to_string();
+        assert!(!mule.exec_command());
+        assert_eq!(mule.status, "0x4 is not shown in a hex view");
+        assert!(mule.project_state.binaries[0].jumps.back(start).is_none());
+
+        let mut mule = mule_with_data(vec![0; 64]);
+        mule.input = ":goto 0x20".to_string();
+        assert!(!mule.exec_command());
+        assert_eq!(mule.status, "0x20");
+        assert!(!mule.status_is_error);
+        assert!(mule.project_state.binaries[0].jumps.back(start).is_some());
+    }
+
+    #[test]
+    fn toggle_of_another_format() {
+        let mut mule = mule_with_data(vec![0; 64]);
+        mule.input = ":memmap".to_string();
+        assert!(!mule.exec_command());
+        assert_eq!(
+            mule.status,
+            "the memory map is only available for Mach-O binaries"
+        );
+        assert!(mule.status_is_error);
+    }
+
+    #[test]
+    fn quoted_path_is_one_argument() {
+        let tokens = tokenize(":o \"my file.gb\"").unwrap();
+        assert_eq!(tokens, [":o", "my file.gb"]);
+    }
+}
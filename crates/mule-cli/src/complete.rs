@@ -0,0 +1,58 @@
+use std::fs;
+
+/// Result of completing a partial file path.
+pub struct PathCompletion {
+    /// The partial path extended to the longest common prefix of all candidates.
+    pub completed: String,
+    /// The matching directory entries, directories end with a `/`.
+    pub candidates: Vec<String>,
+}
+
+/// Completes `partial` against the entries of its directory.
+pub fn complete_path(partial: &str) -> Result<PathCompletion, String> {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(ix) => partial.split_at(ix + 1),
+        None => ("", partial),
+    };
+    let read_dir = if dir.is_empty() { "." } else { dir };
+
+    let entries = fs::read_dir(read_dir).map_err(|e| format!("{}: {}", read_dir, e))?;
+    let mut candidates = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+            continue;
+        }
+        if entry.path().is_dir() {
+            candidates.push(format!("{}/", name));
+        } else {
+            candidates.push(name);
+        }
+    }
+    candidates.sort();
+
+    let completed = match candidates.first() {
+        None => partial.to_string(),
+        Some(first) => {
+            let common = candidates
+                .iter()
+                .fold(first.as_str(), |common, c| common_prefix(common, c));
+            format!("{}{}", dir, common)
+        }
+    };
+    Ok(PathCompletion {
+        completed,
+        candidates,
+    })
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let mut end = 0;
+    for ((ix, ca), cb) in a.char_indices().zip(b.chars()) {
+        if ca != cb {
+            break;
+        }
+        end = ix + ca.len_utf8();
+    }
+    &a[..end]
+}
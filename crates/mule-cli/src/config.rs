@@ -0,0 +1,7 @@
+use std::{env, path::PathBuf};
+
+/// Path of `file` in the config dir `~/.config/mule`.
+pub fn config_path(file: &str) -> Option<PathBuf> {
+    let home = env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".config/mule").join(file))
+}
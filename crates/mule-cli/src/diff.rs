@@ -0,0 +1,109 @@
+use std::ops::Range;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DiffKind {
+    /// bytes present in both files but with different values
+    Changed,
+    /// the tail of the longer file
+    Added,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct DiffRange {
+    pub range: Range<usize>,
+    pub kind: DiffKind,
+}
+
+/// Compares `a` and `b` byte by byte and returns the ranges of consecutive
+/// differing bytes, sorted by offset. If one file is longer its tail is
+/// reported as a single `Added` range.
+pub fn diff_bytes(a: &[u8], b: &[u8]) -> Vec<DiffRange> {
+    let common = a.len().min(b.len());
+    let mut result = Vec::new();
+    let mut start = None;
+    for i in 0..common {
+        match (start, a[i] != b[i]) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                result.push(DiffRange {
+                    range: s..i,
+                    kind: DiffKind::Changed,
+                });
+                start = None;
+            }
+            _ => { /* continue the current range */ }
+        }
+    }
+    if let Some(s) = start {
+        result.push(DiffRange {
+            range: s..common,
+            kind: DiffKind::Changed,
+        });
+    }
+    let longest = a.len().max(b.len());
+    if longest > common {
+        result.push(DiffRange {
+            range: common..longest,
+            kind: DiffKind::Added,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(range: Range<usize>) -> DiffRange {
+        DiffRange {
+            range,
+            kind: DiffKind::Changed,
+        }
+    }
+
+    fn added(range: Range<usize>) -> DiffRange {
+        DiffRange {
+            range,
+            kind: DiffKind::Added,
+        }
+    }
+
+    #[test]
+    fn equal_bytes() {
+        assert_eq!(diff_bytes(&[1, 2, 3], &[1, 2, 3]), []);
+    }
+
+    #[test]
+    fn empty_files() {
+        assert_eq!(diff_bytes(&[], &[]), []);
+    }
+
+    #[test]
+    fn changed_at_the_start_and_the_end() {
+        assert_eq!(diff_bytes(&[9, 2, 3, 4], &[1, 2, 3, 4]), [changed(0..1)]);
+        assert_eq!(diff_bytes(&[1, 2, 3, 4], &[1, 2, 3, 9]), [changed(3..4)]);
+    }
+
+    #[test]
+    fn longer_file_has_an_added_tail() {
+        assert_eq!(diff_bytes(&[1, 2, 3, 4], &[1, 2]), [added(2..4)]);
+        assert_eq!(diff_bytes(&[1, 2], &[1, 2, 3, 4]), [added(2..4)]);
+        assert_eq!(diff_bytes(&[], &[1]), [added(0..1)]);
+    }
+
+    #[test]
+    fn consecutive_changes_are_one_range() {
+        assert_eq!(
+            diff_bytes(&[1, 2, 3, 4, 5], &[1, 9, 9, 9, 5]),
+            [changed(1..4)]
+        );
+    }
+
+    #[test]
+    fn equal_byte_separates_ranges() {
+        assert_eq!(
+            diff_bytes(&[1, 2, 3, 4, 5], &[1, 9, 3, 9, 5]),
+            [changed(1..2), changed(3..4)]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Disassembled lines of code regions (sections, banks, ...) keyed by the
+/// region. A region is only disassembled when it's shown the first time.
+/// The lines are the shown text or instructions that keep their text.
+pub struct DisassemblyCache<K, L = String> {
+    lines: HashMap<K, Vec<L>>,
+}
+
+impl<K: Hash + Eq, L> DisassemblyCache<K, L> {
+    pub fn new() -> DisassemblyCache<K, L> {
+        DisassemblyCache {
+            lines: HashMap::new(),
+        }
+    }
+
+    /// The lines of the region, disassembled with `disassemble` if they
+    /// aren't cached yet.
+    pub fn get_or_disassemble(&mut self, key: K, disassemble: impl FnOnce() -> Vec<L>) -> &[L] {
+        self.lines.entry(key).or_insert_with(disassemble)
+    }
+
+    /// The lines of the region if it was disassembled already.
+    pub fn get(&self, key: &K) -> Option<&[L]> {
+        self.lines.get(key).map(Vec::as_slice)
+    }
+
+    /// Drops all lines, e.g. after the bytes of the binary were edited.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn second_view_is_not_disassembled_again() {
+        let calls = Cell::new(0);
+        let disassemble = || {
+            calls.set(calls.get() + 1);
+            vec!["nop".to_string()]
+        };
+        let mut cache = DisassemblyCache::new();
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get_or_disassemble(1, disassemble), ["nop"]);
+        assert_eq!(cache.get_or_disassemble(1, disassemble), ["nop"]);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.get(&1), Some(&["nop".to_string()][..]));
+
+        cache.get_or_disassemble(2, disassemble);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn cleared_region_is_disassembled_again() {
+        let calls = Cell::new(0);
+        let disassemble = || {
+            calls.set(calls.get() + 1);
+            vec![calls.get()]
+        };
+        let mut cache = DisassemblyCache::new();
+        cache.get_or_disassemble("__text", disassemble);
+        cache.clear();
+        assert_eq!(cache.get(&"__text"), None);
+        assert_eq!(cache.get_or_disassemble("__text", disassemble), [2]);
+    }
+}
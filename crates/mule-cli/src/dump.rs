@@ -0,0 +1,226 @@
+use std::fmt::Write;
+
+use mule_elf::Elf;
+use mule_gb::{GBBinary, global_checksum, header_checksum, header_warnings, save_bytes};
+use mule_macho::{LoadCommand, Macho};
+use mule_pe::Pe;
+use mule_wasm::Wasm;
+
+use crate::{binary_view::BinaryView, view_elf, view_gb, view_macho, view_pe, view_wasm};
+
+/// The plain text report of `--dump`: the header fields and the lists of the
+/// file pane of the interactive view.
+pub fn dump(path: &str, view: &dyn BinaryView, data: &[u8]) -> String {
+    let mut out = String::new();
+    line(&mut out, &format!("{} ({})", path, view.summary()));
+    view.dump(data, &mut out);
+    out
+}
+
+pub fn dump_macho(out: &mut String, macho: &Macho) {
+    let header = &macho.header;
+    heading(out, "Header");
+    field(out, "Magic", format!("0x{:X}", header.magic));
+    field(
+        out,
+        "CPU",
+        format!("{:?} ({:?})", header.cpu_type, header.cpu_sub_type),
+    );
+    field(out, "File Type", format!("{:?}", header.file_type));
+    field(
+        out,
+        "Commands",
+        format!("{} ({} bytes)", header.no_cmds, header.size_of_cmds),
+    );
+    let flags: Vec<String> = header.flags.iter().map(|f| format!("{:?}", f)).collect();
+    field(out, "Flags", flags.join(", "));
+    field(out, "Debug Info", view_macho::debug_info_summary(macho));
+
+    heading(out, "Load Commands");
+    let commands = view_macho::command_list(macho);
+    for (i, (text, cmd)) in commands.iter().zip(&macho.load_commands).enumerate() {
+        line(out, &format!("{:>4}  {}", i, text));
+        if let LoadCommand::Segment64(segment) = cmd {
+            for section in &segment.sections {
+                line(
+                    out,
+                    &format!(
+                        "        {},{} addr 0x{:X} size 0x{:X} offset 0x{:X}",
+                        view_macho::display_name(&section.seg_name),
+                        view_macho::display_name(&section.name),
+                        section.address,
+                        section.size,
+                        section.offset
+                    ),
+                );
+            }
+        }
+    }
+}
+
+pub fn dump_gb(out: &mut String, gb: &GBBinary, data: &[u8]) {
+    let header = &gb.header;
+    heading(out, "Header");
+    field(
+        out,
+        "Game Title",
+        header.game_title.trim_end_matches('\0').to_string(),
+    );
+    field(
+        out,
+        "Manufacturer Code",
+        view_gb::manufacturer_display(&header.manufacturer_code).to_string(),
+    );
+    field(
+        out,
+        "GBC Flag",
+        view_gb::gbc_flag_display(header.gbc_flag).to_string(),
+    );
+    field(out, "Licensee Code", header.licensee_code.to_string());
+    field(
+        out,
+        "Super Gameboy Flag",
+        view_gb::sgb_flag_display(header.sgb_flag).to_string(),
+    );
+    field(
+        out,
+        "Cartridge Type",
+        view_gb::cartridge_description(header.cartridge_type),
+    );
+    field(
+        out,
+        "ROM Size",
+        view_gb::rom_display(header.rom_size).to_string(),
+    );
+    field(
+        out,
+        "RAM Size",
+        view_gb::ram_display(header.ram_size).to_string(),
+    );
+    field(
+        out,
+        "Save RAM",
+        view_gb::save_display(header.cartridge_type, save_bytes(header)),
+    );
+    field(
+        out,
+        "Destination Code",
+        view_gb::dest_code_display(header.destination_code).to_string(),
+    );
+    field(out, "ROM Version", header.rom_version.to_string());
+    field(
+        out,
+        "Checksum",
+        view_gb::checksum_display(header.checksum as u16, header_checksum(data) as u16, 2),
+    );
+    field(
+        out,
+        "Global Checksum",
+        view_gb::checksum_display(header.global_checksum, global_checksum(data), 4),
+    );
+    field(
+        out,
+        "Logo",
+        if header.is_logo_valid() {
+            "VALID"
+        } else {
+            "MODIFIED"
+        }
+        .to_string(),
+    );
+    field(out, "Banks", gb.bank_data.len().to_string());
+    for warning in header_warnings(header) {
+        field(out, "Warning", warning);
+    }
+}
+
+pub fn dump_elf(out: &mut String, elf: &Elf) {
+    let header = &elf.header;
+    heading(out, "Header");
+    field(out, "Class", format!("{:?}", header.class));
+    field(out, "Endianness", format!("{:?}", header.endianness));
+    field(out, "OS ABI", format!("0x{:02X}", header.os_abi));
+    field(out, "File Type", format!("{:?}", header.file_type));
+    field(out, "Machine", format!("{:?}", header.machine));
+    field(out, "Entry Point", format!("0x{:X}", header.entry_point));
+    field(out, "Flags", format!("0x{:X}", header.flags));
+
+    heading(out, "Segments");
+    list(out, &view_elf::segment_list(&elf.program_headers));
+    heading(out, "Sections");
+    list(out, &view_elf::section_list(&elf.section_headers));
+}
+
+pub fn dump_pe(out: &mut String, pe: &Pe) {
+    let coff = &pe.coff_header;
+    heading(out, "Header");
+    field(out, "Machine", format!("{:?}", coff.machine));
+    field(out, "Timestamp", format!("0x{:X}", coff.time_date_stamp));
+    field(
+        out,
+        "Characteristics",
+        view_pe::characteristics_display(coff.characteristics),
+    );
+    if let Some(optional) = &pe.optional_header {
+        field(out, "Format", format!("{:?}", optional.format));
+        field(out, "Entry Point", format!("0x{:X}", optional.entry_point));
+        field(out, "Image Base", format!("0x{:X}", optional.image_base));
+        field(out, "Subsystem", format!("{:?}", optional.subsystem));
+    }
+    for warning in &pe.warnings {
+        field(out, "Warning", warning.clone());
+    }
+
+    heading(out, "Sections");
+    list(out, &view_pe::section_list(&pe.section_headers));
+    heading(out, "Imports");
+    let imports: Vec<String> = pe
+        .imports
+        .iter()
+        .map(|i| format!("{} ({} functions)", i.dll, i.functions.len()))
+        .collect();
+    list(out, &imports);
+}
+
+pub fn dump_wasm(out: &mut String, wasm: &Wasm) {
+    heading(out, "Header");
+    field(out, "Version", wasm.version.to_string());
+    field(out, "Types", wasm.types.len().to_string());
+    field(out, "Imports", wasm.imports.len().to_string());
+    field(out, "Functions", wasm.functions.len().to_string());
+
+    heading(out, "Sections");
+    list(out, &view_wasm::section_list(&wasm.sections));
+    heading(out, "Exports");
+    let exports: Vec<String> = wasm
+        .exports
+        .iter()
+        .map(|e| format!("{} ({:?} {})", e.name, e.kind, e.index))
+        .collect();
+    list(out, &exports);
+}
+
+pub fn dump_raw(out: &mut String, data: &[u8]) {
+    heading(out, "Raw");
+    field(out, "Size", format!("{} bytes", data.len()));
+}
+
+fn line(out: &mut String, text: &str) {
+    // writing to a String can't fail
+    let _ = writeln!(out, "{}", text);
+}
+
+fn heading(out: &mut String, title: &str) {
+    line(out, "");
+    line(out, &format!("{}:", title));
+}
+
+fn field(out: &mut String, name: &str, value: String) {
+    line(out, &format!("  {:<20}{}", format!("{}:", name), value));
+}
+
+fn list(out: &mut String, entries: &[String]) {
+    for (i, entry) in entries.iter().enumerate() {
+        line(out, &format!("{:>4}  {}", i, entry));
+    }
+}
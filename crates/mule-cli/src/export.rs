@@ -0,0 +1,259 @@
+use std::fmt::Write;
+
+use ratatui::{
+    buffer::{Buffer, Cell, CellDiffOption},
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+/// The output formats of `:export-view`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    /// Text with ANSI escape codes, e.g. for `cat` in a terminal
+    Ansi,
+    /// A self-contained `<pre>` block with inline styles
+    Html,
+}
+
+impl ExportFormat {
+    /// HTML for `.html` and `.htm` paths, ANSI text for all others.
+    pub fn from_path(path: &str) -> ExportFormat {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".html") || lower.ends_with(".htm") {
+            ExportFormat::Html
+        } else {
+            ExportFormat::Ansi
+        }
+    }
+}
+
+// the colors of the HTML export for cells without a color
+const HTML_DEFAULT_FG: &str = "#d0d0d0";
+const HTML_DEFAULT_BG: &str = "#000000";
+
+/// Converts the cells of `area` to text in the format, keeping the colors
+/// and the bold, italic, underlined and reversed cells.
+pub fn export_buffer(buf: &Buffer, area: Rect, format: ExportFormat) -> String {
+    let area = area.intersection(buf.area);
+    let mut out = String::new();
+    if format == ExportFormat::Html {
+        let _ = write!(
+            out,
+            "<pre style=\"font-family: monospace; color: {}; background: {};\">",
+            HTML_DEFAULT_FG, HTML_DEFAULT_BG
+        );
+    }
+    for y in area.top()..area.bottom() {
+        // runs of cells with the same style share one escape sequence or span
+        let mut run: Option<(&Cell, String)> = None;
+        for x in area.left()..area.right() {
+            let cell = &buf[(x, y)];
+            if cell.diff_option == CellDiffOption::Skip {
+                continue;
+            }
+            match &mut run {
+                Some((first, text)) if same_style(first, cell) => text.push_str(cell.symbol()),
+                _ => {
+                    if let Some((first, text)) = run.take() {
+                        write_run(&mut out, first, &text, format);
+                    }
+                    run = Some((cell, cell.symbol().to_string()));
+                }
+            }
+        }
+        if let Some((first, text)) = run {
+            write_run(&mut out, first, &text, format);
+        }
+        out.push('\n');
+    }
+    if format == ExportFormat::Html {
+        out.push_str("</pre>\n");
+    }
+    out
+}
+
+fn same_style(a: &Cell, b: &Cell) -> bool {
+    a.fg == b.fg && a.bg == b.bg && a.modifier == b.modifier
+}
+
+fn write_run(out: &mut String, cell: &Cell, text: &str, format: ExportFormat) {
+    match format {
+        ExportFormat::Ansi => {
+            let codes = ansi_codes(cell);
+            if codes.is_empty() {
+                out.push_str(text);
+            } else {
+                let _ = write!(out, "\x1b[{}m{}\x1b[0m", codes.join(";"), text);
+            }
+        }
+        ExportFormat::Html => {
+            let css = html_style(cell);
+            let text = html_escape(text);
+            if css.is_empty() {
+                out.push_str(&text);
+            } else {
+                let _ = write!(out, "<span style=\"{}\">{}</span>", css, text);
+            }
+        }
+    }
+}
+
+/// The SGR parameters of the cell's colors and modifiers.
+fn ansi_codes(cell: &Cell) -> Vec<String> {
+    let mut codes = Vec::new();
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if cell.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = ansi_color(cell.fg, 30) {
+        codes.push(code);
+    }
+    if let Some(code) = ansi_color(cell.bg, 40) {
+        codes.push(code);
+    }
+    codes
+}
+
+// `base` is 30 for the foreground and 40 for the background
+fn ansi_color(color: Color, base: u8) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        Color::Indexed(ix) => return Some(format!("{};5;{}", base + 8, ix)),
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+    };
+    Some(code.to_string())
+}
+
+/// The inline CSS of the cell, empty for an unstyled cell.
+fn html_style(cell: &Cell) -> String {
+    let mut fg = html_color(cell.fg);
+    let mut bg = html_color(cell.bg);
+    if cell.modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (
+            Some(bg.unwrap_or_else(|| HTML_DEFAULT_BG.to_string())),
+            Some(fg.unwrap_or_else(|| HTML_DEFAULT_FG.to_string())),
+        );
+    }
+    let mut css = Vec::new();
+    if let Some(fg) = fg {
+        css.push(format!("color: {}", fg));
+    }
+    if let Some(bg) = bg {
+        css.push(format!("background: {}", bg));
+    }
+    if cell.modifier.contains(Modifier::BOLD) {
+        css.push("font-weight: bold".to_string());
+    }
+    if cell.modifier.contains(Modifier::DIM) {
+        css.push("opacity: 0.6".to_string());
+    }
+    if cell.modifier.contains(Modifier::ITALIC) {
+        css.push("font-style: italic".to_string());
+    }
+    if cell.modifier.contains(Modifier::UNDERLINED) {
+        css.push("text-decoration: underline".to_string());
+    } else if cell.modifier.contains(Modifier::CROSSED_OUT) {
+        css.push("text-decoration: line-through".to_string());
+    }
+    css.join("; ")
+}
+
+// the xterm colors of the 16 named colors
+const HTML_PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xCD, 0x00, 0x00),
+    (0x00, 0xCD, 0x00),
+    (0xCD, 0xCD, 0x00),
+    (0x00, 0x00, 0xEE),
+    (0xCD, 0x00, 0xCD),
+    (0x00, 0xCD, 0xCD),
+    (0xE5, 0xE5, 0xE5),
+    (0x7F, 0x7F, 0x7F),
+    (0xFF, 0x00, 0x00),
+    (0x00, 0xFF, 0x00),
+    (0xFF, 0xFF, 0x00),
+    (0x5C, 0x5C, 0xFF),
+    (0xFF, 0x00, 0xFF),
+    (0x00, 0xFF, 0xFF),
+    (0xFF, 0xFF, 0xFF),
+];
+
+fn html_color(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(ix) => indexed_rgb(ix),
+        Color::Black => HTML_PALETTE[0],
+        Color::Red => HTML_PALETTE[1],
+        Color::Green => HTML_PALETTE[2],
+        Color::Yellow => HTML_PALETTE[3],
+        Color::Blue => HTML_PALETTE[4],
+        Color::Magenta => HTML_PALETTE[5],
+        Color::Cyan => HTML_PALETTE[6],
+        Color::Gray => HTML_PALETTE[7],
+        Color::DarkGray => HTML_PALETTE[8],
+        Color::LightRed => HTML_PALETTE[9],
+        Color::LightGreen => HTML_PALETTE[10],
+        Color::LightYellow => HTML_PALETTE[11],
+        Color::LightBlue => HTML_PALETTE[12],
+        Color::LightMagenta => HTML_PALETTE[13],
+        Color::LightCyan => HTML_PALETTE[14],
+        Color::White => HTML_PALETTE[15],
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// The color of an entry of the 256 color palette: the named colors, a
+/// 6x6x6 color cube and 24 shades of gray.
+fn indexed_rgb(ix: u8) -> (u8, u8, u8) {
+    match ix {
+        0..=15 => HTML_PALETTE[ix as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let ix = ix - 16;
+            (level(ix / 36), level(ix / 6 % 6), level(ix % 6))
+        }
+        _ => {
+            let gray = 8 + (ix - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,158 @@
+/// The focused pane of a view and the order in which the panes are cycled
+/// through. While the view is unfocused no pane has the focus, the pane
+/// focused before is restored when the view gets the focus back.
+pub struct FocusRing<T: 'static> {
+    order: &'static [T],
+    focus_on: Option<T>,
+    previous: T,
+}
+
+impl<T: Copy + PartialEq> FocusRing<T> {
+    pub fn new(order: &'static [T], initial: T) -> FocusRing<T> {
+        FocusRing {
+            order,
+            focus_on: Some(initial),
+            previous: initial,
+        }
+    }
+
+    /// The focused pane, None while the view is unfocused.
+    pub fn current(&self) -> Option<T> {
+        self.focus_on
+    }
+
+    pub fn is(&self, pane: T) -> bool {
+        self.focus_on == Some(pane)
+    }
+
+    /// The focused pane or, while the view is unfocused, the pane that gets
+    /// the focus back.
+    pub fn active(&self) -> T {
+        self.focus_on.unwrap_or(self.previous)
+    }
+
+    /// Focuses `pane`, also if the view was unfocused.
+    pub fn set(&mut self, pane: T) {
+        self.focus_on = Some(pane);
+    }
+
+    /// Makes `pane` the active pane without changing whether the view is
+    /// focused.
+    pub fn set_active(&mut self, pane: T) {
+        if self.focus_on.is_some() {
+            self.focus_on = Some(pane);
+        } else {
+            self.previous = pane;
+        }
+    }
+
+    pub fn focus(&mut self) {
+        if self.focus_on.is_none() {
+            self.focus_on = Some(self.previous);
+        }
+    }
+
+    pub fn unfocus(&mut self) {
+        if let Some(pane) = self.focus_on.take() {
+            self.previous = pane;
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.move_focus(1);
+    }
+
+    pub fn prev(&mut self) {
+        self.move_focus(-1);
+    }
+
+    /// Position of the active pane in the cycle, used to restore the focus
+    /// of a session.
+    pub fn index(&self) -> usize {
+        self.position(self.active()).unwrap_or(0)
+    }
+
+    pub fn set_index(&mut self, ix: usize) {
+        if let Some(pane) = self.order.get(ix) {
+            self.focus_on = Some(*pane);
+        }
+    }
+
+    fn position(&self, pane: T) -> Option<usize> {
+        self.order.iter().position(|p| *p == pane)
+    }
+
+    // Moves from the active pane by `dir` with wraparound. A pane that is
+    // not part of the cycle moves to the first (forward) or the last
+    // (backward) pane of the cycle.
+    fn move_focus(&mut self, dir: isize) {
+        let len = self.order.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let ix = match self.position(self.active()) {
+            Some(ix) => (ix as isize + dir).rem_euclid(len),
+            None if dir > 0 => 0,
+            None => len - 1,
+        };
+        self.focus_on = Some(self.order[ix as usize]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Pane {
+        A,
+        B,
+        C,
+        Outside,
+    }
+
+    const ORDER: [Pane; 3] = [Pane::A, Pane::B, Pane::C];
+
+    #[test]
+    fn next_then_prev_returns_to_the_start() {
+        let mut ring = FocusRing::new(&ORDER, Pane::B);
+        for steps in 0..=ORDER.len() + 1 {
+            for _ in 0..steps {
+                ring.next();
+            }
+            for _ in 0..steps {
+                ring.prev();
+            }
+            assert_eq!(ring.current(), Some(Pane::B), "{} steps", steps);
+        }
+    }
+
+    #[test]
+    fn next_and_prev_wrap_around() {
+        let mut ring = FocusRing::new(&ORDER, Pane::C);
+        ring.next();
+        assert_eq!(ring.current(), Some(Pane::A));
+        ring.prev();
+        assert_eq!(ring.current(), Some(Pane::C));
+    }
+
+    #[test]
+    fn pane_outside_the_ring() {
+        let mut ring = FocusRing::new(&ORDER, Pane::Outside);
+        ring.next();
+        assert_eq!(ring.current(), Some(Pane::A));
+
+        let mut ring = FocusRing::new(&ORDER, Pane::Outside);
+        ring.prev();
+        assert_eq!(ring.current(), Some(Pane::C));
+    }
+
+    #[test]
+    fn unfocused_ring_moves_from_the_previous_pane() {
+        let mut ring = FocusRing::new(&ORDER, Pane::A);
+        ring.unfocus();
+        assert_eq!(ring.current(), None);
+        ring.next();
+        assert_eq!(ring.current(), Some(Pane::B));
+    }
+}
@@ -0,0 +1,46 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// Digests offered by `:hash`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HashAlgorithm {
+    /// The identifier of ROM databases (e.g. No-Intro)
+    Crc32,
+    Md5,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn parse(str: &str) -> Result<HashAlgorithm, String> {
+        match str.to_ascii_lowercase().as_str() {
+            "crc32" => Ok(HashAlgorithm::Crc32),
+            "md5" => Ok(HashAlgorithm::Md5),
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            _ => Err(format!(
+                "unknown hash '{}', expected crc32, md5 or sha256",
+                str
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Crc32 => "crc32",
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+/// The digest of `data` as lower case hex string.
+pub fn hash(algorithm: HashAlgorithm, data: &[u8]) -> String {
+    match algorithm {
+        HashAlgorithm::Crc32 => format!("{:08x}", crc32fast::hash(data)),
+        HashAlgorithm::Md5 => to_hex(&Md5::digest(data)),
+        HashAlgorithm::Sha256 => to_hex(&Sha256::digest(data)),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
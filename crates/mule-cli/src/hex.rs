@@ -1,17 +1,326 @@
+use std::ops::Range;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    widgets::{Block, Paragraph, Widget, WidgetRef},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
+        WidgetRef,
+    },
 };
+use serde::{Deserialize, Serialize};
+
+// the offset column shows at least 4 hex digits, followed by 3 spaces
+const MIN_OFFSET_DIGITS: usize = 4;
+const OFFSET_GAP_WIDTH: u16 = 3;
+// the group sizes that can be chosen, in the order they are cycled through
+const GROUP_SIZES: [usize; 5] = [1, 2, 4, 8, 16];
+const DEFAULT_GROUP_BYTES: usize = 4;
+// the enclosing '|' of the ASCII column
+const ASCII_BORDER_WIDTH: u16 = 2;
+const SCROLLBAR_WIDTH: u16 = 1;
+// bytes per line of the fixed layout
+const FIXED_BYTES_PER_LINE: usize = 16;
+
+/// Number of bytes whose hex digits are shown without a separator, one of
+/// 1, 2, 4, 8 or 16.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "usize", into = "usize")]
+pub struct GroupBytes(usize);
+
+impl GroupBytes {
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    /// The next larger group size, wraps around to a single byte.
+    pub fn next(self) -> GroupBytes {
+        let ix = GROUP_SIZES.iter().position(|g| *g == self.0).unwrap_or(0);
+        GroupBytes(GROUP_SIZES[(ix + 1) % GROUP_SIZES.len()])
+    }
+
+    // hex digits and separator plus the chars in the ASCII column, also the
+    // columns panned per horizontal scroll step
+    fn width(self) -> usize {
+        self.0 * 3 + 1
+    }
+}
+
+impl Default for GroupBytes {
+    fn default() -> Self {
+        GroupBytes(DEFAULT_GROUP_BYTES)
+    }
+}
+
+impl TryFrom<usize> for GroupBytes {
+    type Error = String;
+
+    fn try_from(bytes: usize) -> Result<Self, Self::Error> {
+        if GROUP_SIZES.contains(&bytes) {
+            Ok(GroupBytes(bytes))
+        } else {
+            Err(format!(
+                "invalid hex group size {}, has to be 1, 2, 4, 8 or 16",
+                bytes
+            ))
+        }
+    }
+}
+
+impl From<GroupBytes> for usize {
+    fn from(group: GroupBytes) -> usize {
+        group.0
+    }
+}
+
+/// Scroll position of a `Hex` view that has to survive across frames
+/// (like the `ListState` for a `List`).
+#[derive(Default)]
+pub struct HexState {
+    scroll_row: usize,
+    // resolved to a row on the next render, the row depends on the width
+    scroll_to_offset: Option<usize>,
+    // all updated on each render
+    page_rows: usize,
+    total_rows: usize,
+    bytes_per_line: usize,
+    data_len: usize,
+    // position of the edited byte, only set in the edit mode
+    cursor: Option<usize>,
+    // the first typed digit of the edited byte
+    high_nibble: Option<u8>,
+    // fixed bytes per line and horizontal scrolling instead of reflowing
+    // the lines to the width
+    fixed_layout: bool,
+    scroll_column: usize,
+    group_bytes: GroupBytes,
+    // color the bytes by their class, see `byte_class_style`
+    byte_colors: bool,
+    // updated on each render
+    text_width: usize,
+    line_info_width: u16,
+}
+
+impl HexState {
+    pub fn scroll_row(&self) -> usize {
+        self.scroll_row
+    }
+
+    /// Offset of the first visible byte (or of a pending scroll).
+    pub fn top_offset(&self) -> usize {
+        self.scroll_to_offset
+            .unwrap_or(self.scroll_row * self.bytes_per_line)
+    }
+
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.scroll_row = self
+            .scroll_row
+            .saturating_add(rows)
+            .min(self.max_scroll_row());
+    }
+
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.scroll_row = self.scroll_row.saturating_sub(rows);
+    }
+
+    /// Scrolls so that the row containing `offset` is the first visible row.
+    /// Offsets past the end of the data scroll to the last page.
+    pub fn scroll_to_offset(&mut self, offset: usize) {
+        self.scroll_to_offset = Some(offset);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.page_rows.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.page_rows.max(1));
+    }
+
+    pub fn scroll_to_start(&mut self) {
+        self.scroll_to_offset = None;
+        self.scroll_row = 0;
+    }
+
+    pub fn scroll_to_end(&mut self) {
+        self.scroll_to_offset = None;
+        self.scroll_row = self.max_scroll_row();
+    }
+
+    fn max_scroll_row(&self) -> usize {
+        self.total_rows.saturating_sub(self.page_rows)
+    }
+
+    /// Resets the scroll position and the edit cursor, keeps the layout.
+    pub fn reset(&mut self) {
+        *self = HexState {
+            fixed_layout: self.fixed_layout,
+            group_bytes: self.group_bytes,
+            byte_colors: self.byte_colors,
+            ..HexState::default()
+        };
+    }
+
+    /// Keeps the first visible byte when the size of the view changes, the
+    /// bytes per line of the reflowed layout depend on the width.
+    pub fn relayout(&mut self) {
+        if !self.fixed_layout {
+            self.scroll_to_offset = Some(self.top_offset());
+        }
+    }
+
+    pub fn fixed_layout(&self) -> bool {
+        self.fixed_layout
+    }
+
+    /// Switches between lines reflowed to the width and lines of a fixed 16
+    /// bytes that are scrolled horizontally. Keeps the first visible byte.
+    pub fn toggle_layout(&mut self) {
+        self.scroll_to_offset = Some(self.top_offset());
+        self.fixed_layout = !self.fixed_layout;
+        self.scroll_column = 0;
+    }
+
+    pub fn scroll_column(&self) -> usize {
+        self.scroll_column
+    }
+
+    pub fn group_bytes(&self) -> GroupBytes {
+        self.group_bytes
+    }
+
+    /// Changes the grouping of the hex digits. Keeps the first visible byte,
+    /// the bytes per line may change with the group size.
+    pub fn set_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.scroll_to_offset = Some(self.top_offset());
+        self.group_bytes = group_bytes;
+        self.scroll_column = 0;
+    }
+
+    pub fn set_byte_colors(&mut self, byte_colors: bool) {
+        self.byte_colors = byte_colors;
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.scroll_column = self.scroll_column.saturating_sub(self.group_bytes.width());
+    }
+
+    /// Only scrolls in the fixed layout, up to the end of the ASCII column.
+    pub fn scroll_right(&mut self) {
+        if self.fixed_layout {
+            self.scroll_column =
+                (self.scroll_column + self.group_bytes.width()).min(self.max_scroll_column());
+        }
+    }
+
+    fn max_scroll_column(&self) -> usize {
+        line_width(FIXED_BYTES_PER_LINE, self.line_info_width, self.group_bytes)
+            .saturating_sub(self.text_width)
+    }
+
+    /// Starts editing at the first visible byte.
+    pub fn start_edit(&mut self) {
+        self.cursor = Some(self.top_offset());
+        self.high_nibble = None;
+    }
+
+    pub fn stop_edit(&mut self) {
+        self.cursor = None;
+        self.high_nibble = None;
+    }
+
+    /// Position of the edited byte in the shown data.
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Moves the edit cursor by `columns` bytes and `rows` lines, clamped to
+    /// the data. A half typed byte is discarded.
+    pub fn move_cursor(&mut self, columns: isize, rows: isize) {
+        let Some(cursor) = self.cursor else {
+            return;
+        };
+        let delta = columns + rows * self.bytes_per_line as isize;
+        let max = self.data_len.saturating_sub(1) as isize;
+        self.cursor = Some((cursor as isize + delta).clamp(0, max) as usize);
+        self.high_nibble = None;
+    }
+
+    /// Types a hex digit (0-15) of the edited byte. Returns the position and
+    /// the new value once both digits of the byte are typed and advances the
+    /// cursor to the next byte.
+    pub fn type_digit(&mut self, digit: u8) -> Option<(usize, u8)> {
+        let cursor = self.cursor?;
+        match self.high_nibble.take() {
+            None => {
+                self.high_nibble = Some(digit);
+                None
+            }
+            Some(high) => {
+                self.move_cursor(1, 0);
+                Some((cursor, high << 4 | digit))
+            }
+        }
+    }
+
+    /// Keeps the row of the edit cursor visible.
+    fn scroll_to_cursor(&mut self) {
+        let Some(cursor) = self.cursor else {
+            return;
+        };
+        if self.bytes_per_line == 0 {
+            return;
+        }
+        let row = cursor / self.bytes_per_line;
+        if row < self.scroll_row {
+            self.scroll_row = row;
+        } else if row >= self.scroll_row + self.page_rows {
+            self.scroll_row = row + 1 - self.page_rows.max(1);
+        }
+        if self.fixed_layout {
+            let column = hex_column(
+                cursor % self.bytes_per_line,
+                self.line_info_width,
+                self.group_bytes,
+            );
+            if column < self.scroll_column {
+                self.scroll_column = column;
+            } else if column + 2 > self.scroll_column + self.text_width {
+                self.scroll_column = (column + 2).saturating_sub(self.text_width);
+            }
+        }
+    }
+}
 
 pub struct Hex<'a> {
-    data: &'a Vec<u8>,
+    data: &'a [u8],
     block: Option<Block<'a>>,
+    scroll_row: usize,
+    fixed_layout: bool,
+    scroll_column: usize,
+    group_bytes: GroupBytes,
+    byte_colors: bool,
+    base_offset: usize,
+    highlights: &'a [(Range<usize>, Style)],
+    cursor_style: Style,
 }
 
 impl<'a> Hex<'a> {
-    pub fn new(data: &'a Vec<u8>) -> Hex<'a> {
-        Hex { data, block: None }
+    pub fn new(data: &'a [u8]) -> Hex<'a> {
+        Hex {
+            data,
+            block: None,
+            scroll_row: 0,
+            fixed_layout: false,
+            scroll_column: 0,
+            group_bytes: GroupBytes::default(),
+            byte_colors: false,
+            base_offset: 0,
+            highlights: &[],
+            cursor_style: Style::default().reversed(),
+        }
     }
 
     pub fn block(mut self, block: Block<'a>) -> Self {
@@ -19,54 +328,476 @@ impl<'a> Hex<'a> {
         self
     }
 
-    fn render_hex(&self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new(hex_data_string(self.data, area.width)).render(area, buf);
+    /// Sets the first row that is rendered. Only used if rendered
+    /// without a `HexState`.
+    pub fn with_scroll(mut self, scroll_row: usize) -> Self {
+        self.scroll_row = scroll_row;
+        self
+    }
 
-        //let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
-        //let mut scrollbar_state = ScrollbarState::new(1000);
-        //StatefulWidget::render(scrollbar, area, buf, &mut scrollbar_state);
+    /// Sets the layout (see `HexState::toggle_layout`) and the first rendered
+    /// column. Only used if rendered without a `HexState`.
+    pub fn with_layout(mut self, fixed_layout: bool, scroll_column: usize) -> Self {
+        self.fixed_layout = fixed_layout;
+        self.scroll_column = scroll_column;
+        self
     }
-}
 
-impl<'a> WidgetRef for Hex<'a> {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+    /// Sets the number of bytes per group of hex digits. Only used if
+    /// rendered without a `HexState`.
+    pub fn with_group_bytes(mut self, group_bytes: GroupBytes) -> Self {
+        self.group_bytes = group_bytes;
+        self
+    }
+
+    /// Colors the bytes by their class (zero, printable ASCII, above 0x7F).
+    /// Only used if rendered without a `HexState`.
+    pub fn with_byte_colors(mut self, byte_colors: bool) -> Self {
+        self.byte_colors = byte_colors;
+        self
+    }
+
+    /// Offset of the first byte, e.g. in the file, the offset column shows
+    /// the offsets from there.
+    pub fn base_offset(mut self, base_offset: usize) -> Self {
+        self.base_offset = base_offset;
+        self
+    }
+
+    /// Styles the bytes in the given ranges, the ranges have to be sorted
+    /// and must not overlap.
+    pub fn highlights(mut self, highlights: &'a [(Range<usize>, Style)]) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Style of the edit cursor, reversed by default.
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    fn line_info_width(&self) -> u16 {
+        offset_digits(self.base_offset, self.data.len()) as u16 + OFFSET_GAP_WIDTH
+    }
+
+    fn render_block(&self, area: Rect, buf: &mut Buffer) -> Rect {
         if let Some(block) = &self.block {
             block.render_ref(area, buf);
-            let inner = block.inner(area);
-            self.render_hex(inner, buf);
+            block.inner(area)
         } else {
-            self.render_hex(area, buf);
+            area
+        }
+    }
+
+    fn render_hex(&self, area: Rect, buf: &mut Buffer, scroll: Scroll, cursor: Option<Cursor>) {
+        let info_width = self.line_info_width();
+        let per_line = layout_bytes_per_line(
+            area.width.saturating_sub(SCROLLBAR_WIDTH),
+            info_width,
+            scroll.fixed_layout,
+            scroll.group_bytes,
+        );
+        if per_line == 0 {
+            Paragraph::new("terminal too narrow").render(area, buf);
+            return;
+        }
+
+        let [ruler_area, area] = split_ruler(area);
+        let text_area = Rect {
+            width: area.width.saturating_sub(SCROLLBAR_WIDTH),
+            ..area
+        };
+        let scroll_column = scroll.column as u16;
+        Paragraph::new(ruler_line(per_line, info_width, scroll.group_bytes))
+            .scroll((0, scroll_column))
+            .render(ruler_area, buf);
+
+        let page_rows = area.height as usize;
+        let total_rows = total_rows(self.data.len(), per_line);
+        let scroll_row = scroll.row.min(total_rows.saturating_sub(page_rows));
+
+        let styles = ByteStyles {
+            highlights: self.highlights,
+            cursor,
+            byte_colors: scroll.byte_colors,
         };
+        Paragraph::new(hex_data_lines(
+            self.data,
+            self.base_offset,
+            per_line,
+            scroll.group_bytes,
+            scroll_row..scroll_row + page_rows,
+            &styles,
+        ))
+        .scroll((0, scroll_column))
+        .render(text_area, buf);
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
+        let mut scrollbar_state = ScrollbarState::new(total_rows.saturating_sub(page_rows) + 1)
+            .position(scroll_row)
+            .viewport_content_length(page_rows);
+        StatefulWidget::render(scrollbar, area, buf, &mut scrollbar_state);
+    }
+}
+
+impl<'a> WidgetRef for Hex<'a> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let inner = self.render_block(area, buf);
+        let scroll = Scroll {
+            row: self.scroll_row,
+            column: if self.fixed_layout {
+                self.scroll_column
+            } else {
+                0
+            },
+            fixed_layout: self.fixed_layout,
+            group_bytes: self.group_bytes,
+            byte_colors: self.byte_colors,
+        };
+        self.render_hex(inner, buf, scroll, None);
+    }
+}
+
+impl<'a> StatefulWidget for &Hex<'a> {
+    type State = HexState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut HexState) {
+        let inner = self.render_block(area, buf);
+        let text_width = inner.width.saturating_sub(SCROLLBAR_WIDTH);
+        let info_width = self.line_info_width();
+        let per_line = layout_bytes_per_line(
+            text_width,
+            info_width,
+            state.fixed_layout,
+            state.group_bytes,
+        );
+        let [_, data_area] = split_ruler(inner);
+        state.page_rows = data_area.height as usize;
+        state.total_rows = total_rows(self.data.len(), per_line);
+        state.bytes_per_line = per_line;
+        state.data_len = self.data.len();
+        state.text_width = text_width as usize;
+        state.line_info_width = info_width;
+        // a pending scroll is kept until a row fits again
+        if per_line > 0
+            && let Some(offset) = state.scroll_to_offset.take()
+        {
+            state.scroll_row = offset / per_line;
+        }
+        state.scroll_to_cursor();
+        state.scroll_row = state.scroll_row.min(state.max_scroll_row());
+        if !state.fixed_layout {
+            state.scroll_column = 0;
+        }
+        // the width may have grown since the last scroll
+        state.scroll_column = state.scroll_column.min(state.max_scroll_column());
+        let cursor = state.cursor.map(|offset| Cursor {
+            offset,
+            high_nibble: state.high_nibble,
+            style: self.cursor_style,
+        });
+        let scroll = Scroll {
+            row: state.scroll_row,
+            column: state.scroll_column,
+            fixed_layout: state.fixed_layout,
+            group_bytes: state.group_bytes,
+            byte_colors: state.byte_colors,
+        };
+        self.render_hex(inner, buf, scroll, cursor);
     }
 }
 
-fn hex_data_string(data: &Vec<u8>, width: u16) -> String {
-    let mut hex_string = String::new();
-    let line_info_width = 3 + 3;
-    let byte_blocks_per_line = (width - line_info_width) / 9;
+/// The first rendered row and column and the layout and coloring of the
+/// lines.
+#[derive(Clone, Copy)]
+struct Scroll {
+    row: usize,
+    column: usize,
+    fixed_layout: bool,
+    group_bytes: GroupBytes,
+    byte_colors: bool,
+}
+
+/// How the bytes of the hex lines are styled.
+struct ByteStyles<'a> {
+    highlights: &'a [(Range<usize>, Style)],
+    cursor: Option<Cursor>,
+    byte_colors: bool,
+}
 
-    let mut lines = 0;
-    let mut offset = 0;
-    while offset < data.len() {
-        hex_string.push_str(&format!("{:03X}   ", lines));
-        for _ in 0..byte_blocks_per_line {
-            hex_string.push_str(&format_block(data, offset));
-            hex_string.push(' ');
-            offset += 4;
+impl<'a> ByteStyles<'a> {
+    /// The style of the highlight containing `offset`, or of the class of the
+    /// byte if coloring by class.
+    fn at(&self, offset: usize, b: u8) -> Style {
+        match highlight_at(self.highlights, offset) {
+            Some(style) => style,
+            None if self.byte_colors => byte_class_style(b),
+            None => Style::default(),
         }
-        hex_string.push('\n');
-        lines += 1;
     }
-    hex_string
 }
 
-fn format_block(data: &Vec<u8>, offset: usize) -> String {
-    let mut block_str = String::new();
-    for i in 0..4 {
-        if (offset + i) >= data.len() {
-            break;
+/// The edited byte, rendered with the typed digit instead of the high nibble.
+#[derive(Clone, Copy)]
+struct Cursor {
+    offset: usize,
+    high_nibble: Option<u8>,
+    style: Style,
+}
+
+/// Splits off the first row of `area` for the column ruler, if there is room
+/// for at least one data row below it.
+fn split_ruler(area: Rect) -> [Rect; 2] {
+    let ruler_rows = if area.height > 1 { 1 } else { 0 };
+    let ruler = Rect {
+        height: ruler_rows,
+        ..area
+    };
+    let data = Rect {
+        y: area.y + ruler_rows,
+        height: area.height - ruler_rows,
+        ..area
+    };
+    [ruler, data]
+}
+
+/// The column header: the position of each byte in the line, aligned with the
+/// hex groups and (as single digit) with the ASCII column.
+fn ruler_line(bytes_per_line: usize, info_width: u16, group_bytes: GroupBytes) -> String {
+    let group_bytes = group_bytes.get();
+    let mut ruler = " ".repeat(info_width as usize);
+    for group in (0..bytes_per_line).step_by(group_bytes) {
+        for i in group..group + group_bytes {
+            ruler.push_str(&format!("{:02X}", i));
         }
-        block_str.push_str(&format!("{:02X}", data[offset + i]));
+        ruler.push(' ');
+    }
+    ruler.push('|');
+    for i in 0..bytes_per_line {
+        ruler.push_str(&format!("{:X}", i % 16));
+    }
+    ruler.push('|');
+    ruler
+}
+
+/// Bytes shown per line, 0 if the width is too small for a single group.
+fn bytes_per_line(width: u16, info_width: u16, group_bytes: GroupBytes) -> usize {
+    let groups_per_line =
+        width.saturating_sub(info_width + ASCII_BORDER_WIDTH) as usize / group_bytes.width();
+    groups_per_line * group_bytes.get()
+}
+
+/// Bytes per line of the layout, 0 if the reflowed lines don't fit a single
+/// group.
+fn layout_bytes_per_line(
+    width: u16,
+    info_width: u16,
+    fixed_layout: bool,
+    group_bytes: GroupBytes,
+) -> usize {
+    if fixed_layout {
+        FIXED_BYTES_PER_LINE
+    } else {
+        bytes_per_line(width, info_width, group_bytes)
+    }
+}
+
+/// Width of a line with `bytes_per_line` bytes, including the ASCII column.
+fn line_width(bytes_per_line: usize, info_width: u16, group_bytes: GroupBytes) -> usize {
+    let groups = bytes_per_line / group_bytes.get();
+    (info_width + ASCII_BORDER_WIDTH) as usize + groups * group_bytes.width()
+}
+
+/// Column of the hex digits of the byte at `position` in its line.
+fn hex_column(position: usize, info_width: u16, group_bytes: GroupBytes) -> usize {
+    let group_bytes = group_bytes.get();
+    // 2 hex digits per byte and a separator after each group
+    info_width as usize
+        + position / group_bytes * (group_bytes * 2 + 1)
+        + position % group_bytes * 2
+}
+
+/// Hex digits of the offset column, enough for the offset of the last byte.
+fn offset_digits(base_offset: usize, data_len: usize) -> usize {
+    let last = base_offset + data_len.saturating_sub(1);
+    let digits = (usize::BITS - last.leading_zeros()).div_ceil(4) as usize;
+    digits.max(MIN_OFFSET_DIGITS)
+}
+
+fn total_rows(data_len: usize, bytes_per_line: usize) -> usize {
+    if bytes_per_line == 0 {
+        return 0;
+    }
+    data_len.div_ceil(bytes_per_line)
+}
+
+/// Formats the lines `rows` of the hex dump. Each line starts with the
+/// offset of its first byte (plus `base_offset`), the hex digits are grouped
+/// by `group_bytes`. Bytes inside one of the highlight ranges get the
+/// range's style.
+fn hex_data_lines(
+    data: &[u8],
+    base_offset: usize,
+    bytes_per_line: usize,
+    group_bytes: GroupBytes,
+    rows: Range<usize>,
+    styles: &ByteStyles,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::with_capacity(rows.len());
+    if bytes_per_line == 0 {
+        // the offset would never advance
+        return lines;
+    }
+
+    let offset_digits = offset_digits(base_offset, data.len());
+    let group_bytes = group_bytes.get();
+    let mut line = rows.start;
+    let mut offset = rows.start * bytes_per_line;
+    while offset < data.len() && line < rows.end {
+        let mut spans = vec![Span::raw(format!(
+            "{:0offset_digits$X}{}",
+            base_offset + offset,
+            " ".repeat(OFFSET_GAP_WIDTH as usize)
+        ))];
+        for group in (0..bytes_per_line).step_by(group_bytes) {
+            for i in offset + group..offset + group + group_bytes {
+                match data.get(i) {
+                    Some(b) => match styles.cursor.filter(|c| c.offset == i) {
+                        Some(Cursor {
+                            high_nibble: Some(high),
+                            style,
+                            ..
+                        }) => spans.push(Span::styled(format!("{:X}_", high), style)),
+                        Some(Cursor { style, .. }) => {
+                            spans.push(Span::styled(format!("{:02X}", b), style))
+                        }
+                        None => spans.push(Span::styled(format!("{:02X}", b), styles.at(i, *b))),
+                    },
+                    // pad a partially filled group, keeps the ASCII column aligned
+                    None => spans.push(Span::raw("  ")),
+                }
+            }
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::raw("|"));
+        for i in offset..offset + bytes_per_line {
+            match data.get(i) {
+                Some(b) => spans.push(Span::styled(ascii_char(*b).to_string(), styles.at(i, *b))),
+                None => spans.push(Span::raw(" ")),
+            }
+        }
+        spans.push(Span::raw("|"));
+        lines.push(Line::from(spans));
+        offset += bytes_per_line;
+        line += 1;
+    }
+    lines
+}
+
+/// Style of the highlight containing `offset`, `highlights` are sorted and
+/// do not overlap.
+fn highlight_at(highlights: &[(Range<usize>, Style)], offset: usize) -> Option<Style> {
+    let ix = highlights.partition_point(|(range, _)| range.end <= offset);
+    match highlights.get(ix) {
+        Some((range, style)) if range.start <= offset => Some(*style),
+        _ => None,
+    }
+}
+
+/// Zero bytes are dimmed, printable ASCII is green and bytes above 0x7F are
+/// blue, other control bytes keep the default style.
+fn byte_class_style(b: u8) -> Style {
+    match b {
+        0x00 => Style::default().fg(Color::DarkGray),
+        0x20..=0x7E => Style::default().fg(Color::Green),
+        0x80..=0xFF => Style::default().fg(Color::LightBlue),
+        _ => Style::default(),
+    }
+}
+
+/// The character shown for `b` in the ASCII column.
+pub fn ascii_char(b: u8) -> char {
+    if b.is_ascii_graphic() || b == b' ' {
+        b as char
+    } else {
+        '.'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{buffer, buffer_text};
+
+    const DATA: [u8; 64] = [0x5A; 64];
+
+    #[test]
+    fn tiny_widths_render() {
+        for width in 0..=10 {
+            for height in [0, 1, 5] {
+                let hex = Hex::new(&DATA);
+                let mut state = HexState::default();
+                let mut buf = buffer(width, height);
+                StatefulWidget::render(&hex, buf.area, &mut buf, &mut state);
+                assert_eq!(state.bytes_per_line, 0, "width {}", width);
+
+                let mut buf = buffer(width, height);
+                hex.render_ref(buf.area, &mut buf);
+
+                let hex = Hex::new(&DATA).block(Block::bordered());
+                let mut buf = buffer(width, height);
+                StatefulWidget::render(&hex, buf.area, &mut buf, &mut state);
+            }
+        }
+    }
+
+    #[test]
+    fn too_narrow_message() {
+        let hex = Hex::new(&DATA);
+        let mut buf = buffer(10, 1);
+        StatefulWidget::render(&hex, buf.area, &mut buf, &mut HexState::default());
+        assert_eq!(buffer_text(&buf), "terminal t\n");
+    }
+
+    // renders the state at the size, after a resize like `Event::Resize`
+    fn render_resized(data: &[u8], state: &mut HexState, width: u16, height: u16) {
+        state.relayout();
+        let mut buf = buffer(width, height);
+        StatefulWidget::render(&Hex::new(data), buf.area, &mut buf, state);
+    }
+
+    #[test]
+    fn resize_keeps_the_first_shown_byte() {
+        let data = [0; 4096];
+        let mut state = HexState::default();
+        state.scroll_to_offset(0x400);
+        render_resized(&data, &mut state, 120, 10);
+        let wide_per_line = state.bytes_per_line;
+        let top = state.top_offset();
+        assert!(top <= 0x400 && 0x400 < top + wide_per_line);
+
+        render_resized(&data, &mut state, 60, 10);
+        assert_ne!(state.bytes_per_line, wide_per_line);
+        let narrow_top = state.top_offset();
+        assert!(narrow_top <= top && top < narrow_top + state.bytes_per_line);
+
+        // the first byte of the narrow view is in the first row again
+        render_resized(&data, &mut state, 120, 10);
+        let top = state.top_offset();
+        assert!(top <= narrow_top && narrow_top < top + wide_per_line);
+    }
+
+    #[test]
+    fn resize_clamps_the_scroll() {
+        let data = [0; 1024];
+        let mut state = HexState::default();
+        render_resized(&data, &mut state, 40, 10);
+        state.scroll_to_end();
+        assert!(state.scroll_row() > 0);
+
+        render_resized(&data, &mut state, 200, 40);
+        assert!(state.scroll_row() <= state.max_scroll_row());
+        assert!(state.scroll_row() + state.page_rows >= state.total_rows);
     }
-    block_str
 }
@@ -0,0 +1,147 @@
+use std::fmt::Write;
+
+/// Data bytes per record, as written by most tools.
+const RECORD_BYTES: usize = 16;
+
+const RECORD_DATA: u8 = 0x00;
+const RECORD_END_OF_FILE: u8 = 0x01;
+const RECORD_EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+
+/// True for the extensions of Intel HEX files: `.hex`, `.ihex` and `.ihx`.
+pub fn is_intel_hex_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    [".hex", ".ihex", ".ihx"]
+        .iter()
+        .any(|extension| lower.ends_with(extension))
+}
+
+/// Converts `data` to Intel HEX records, the first byte at the `address`.
+/// An extended linear address record precedes the data records of each 64
+/// KiB block, so addresses up to 4 GiB can be written.
+pub fn to_intel_hex(data: &[u8], address: usize) -> Result<String, String> {
+    if address as u64 + data.len() as u64 > u32::MAX as u64 + 1 {
+        return Err(format!(
+            "{} bytes at 0x{:X} exceed the 32-bit addresses of Intel HEX",
+            data.len(),
+            address
+        ));
+    }
+    let mut out = String::new();
+    let mut upper = None;
+    let mut offset = 0;
+    while offset < data.len() {
+        let record_address = (address + offset) as u32;
+        let block = (record_address >> 16) as u16;
+        if upper != Some(block) {
+            write_record(
+                &mut out,
+                RECORD_EXTENDED_LINEAR_ADDRESS,
+                0,
+                &block.to_be_bytes(),
+            );
+            upper = Some(block);
+        }
+        // a record doesn't cross into the next 64 KiB block
+        let block_end = 0x1_0000 - (record_address & 0xFFFF) as usize;
+        let len = RECORD_BYTES.min(block_end).min(data.len() - offset);
+        write_record(
+            &mut out,
+            RECORD_DATA,
+            record_address as u16,
+            &data[offset..offset + len],
+        );
+        offset += len;
+    }
+    write_record(&mut out, RECORD_END_OF_FILE, 0, &[]);
+    Ok(out)
+}
+
+/// Appends a `:LLAAAATT<data>CC` line, the checksum is the two's complement
+/// of the sum of all other bytes.
+fn write_record(out: &mut String, record_type: u8, address: u16, data: &[u8]) {
+    let [address_high, address_low] = address.to_be_bytes();
+    let mut sum = (data.len() as u8)
+        .wrapping_add(address_high)
+        .wrapping_add(address_low)
+        .wrapping_add(record_type);
+    let _ = write!(out, ":{:02X}{:04X}{:02X}", data.len(), address, record_type);
+    for b in data {
+        sum = sum.wrapping_add(*b);
+        let _ = write!(out, "{:02X}", b);
+    }
+    let _ = writeln!(out, "{:02X}", sum.wrapping_neg());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the bytes of a record line, without the start code
+    fn record_bytes(line: &str) -> Vec<u8> {
+        let hex = line.strip_prefix(':').unwrap();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn data_record_checksum() {
+        let data = [
+            0x21, 0x46, 0x01, 0x36, 0x01, 0x21, 0x47, 0x01, 0x36, 0x00, 0x7E, 0xFE, 0x09, 0xD2,
+            0x19, 0x01,
+        ];
+        let hex = to_intel_hex(&data, 0x100).unwrap();
+        assert_eq!(
+            hex,
+            ":020000040000FA\n:10010000214601360121470136007EFE09D2190140\n:00000001FF\n"
+        );
+    }
+
+    #[test]
+    fn records_sum_to_zero() {
+        let data: Vec<u8> = (0..=255).collect();
+        let hex = to_intel_hex(&data, 0x1234).unwrap();
+        assert_eq!(hex.lines().count(), 1 + 16 + 1);
+        for line in hex.lines() {
+            let sum = record_bytes(line)
+                .iter()
+                .fold(0u8, |sum, b| sum.wrapping_add(*b));
+            assert_eq!(sum, 0, "{}", line);
+        }
+    }
+
+    #[test]
+    fn extended_address_past_64_kib() {
+        let hex = to_intel_hex(&[0xAA, 0xBB, 0xCC, 0xDD], 0xFFFE).unwrap();
+        let lines: Vec<&str> = hex.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                ":020000040000FA",
+                ":02FFFE00AABB9C",
+                ":020000040001F9",
+                ":02000000CCDD55",
+                ":00000001FF",
+            ]
+        );
+    }
+
+    #[test]
+    fn end_of_file_record_only() {
+        assert_eq!(to_intel_hex(&[], 0).unwrap(), ":00000001FF\n");
+    }
+
+    #[test]
+    fn addresses_past_4_gib() {
+        assert!(to_intel_hex(&[0; 2], 0xFFFF_FFFF).is_err());
+        assert!(to_intel_hex(&[0; 1], 0xFFFF_FFFF).is_ok());
+    }
+
+    #[test]
+    fn intel_hex_paths() {
+        assert!(is_intel_hex_path("rom.HEX"));
+        assert!(is_intel_hex_path("a.ihx"));
+        assert!(!is_intel_hex_path("a.bin"));
+    }
+}
@@ -0,0 +1,48 @@
+/// Maximum number of positions kept to go back to
+const MAX_JUMPS: usize = 100;
+
+/// A position in the views of a binary: the focused pane (its index in the
+/// focus cycle) and the file offset shown in its hex view, if any.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Location {
+    pub focus: usize,
+    pub offset: Option<usize>,
+}
+
+/// The positions left by jumps (`:goto`, search matches, marks, ...) of a
+/// binary, like the jump list of vim.
+#[derive(Default)]
+pub struct JumpList {
+    back: Vec<Location>,
+    forward: Vec<Location>,
+}
+
+impl JumpList {
+    /// Remembers the position before a jump. A new jump drops the positions
+    /// that were gone back from.
+    pub fn push(&mut self, location: Location) {
+        self.forward.clear();
+        if self.back.last() != Some(&location) {
+            self.back.push(location);
+        }
+        if self.back.len() > MAX_JUMPS {
+            self.back.remove(0);
+        }
+    }
+
+    /// The position before the last jump, `current` can be returned to with
+    /// `forward`.
+    pub fn back(&mut self, current: Location) -> Option<Location> {
+        let location = self.back.pop()?;
+        self.forward.push(current);
+        Some(location)
+    }
+
+    /// The position last gone back from, `current` can be returned to with
+    /// `back`.
+    pub fn forward(&mut self, current: Location) -> Option<Location> {
+        let location = self.forward.pop()?;
+        self.back.push(current);
+        Some(location)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{cmp::Ordering, collections::HashMap, fs, io::ErrorKind};
+
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+
+use crate::config::config_path;
+
+/// What a key does in the interactive mode.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    FocusNext,
+    FocusPrev,
+    SelectNext,
+    SelectPrev,
+    PageDown,
+    PageUp,
+    Home,
+    End,
+    Left,
+    Right,
+    Confirm,
+    Close,
+    ToggleHelp,
+    ToggleDisassemble,
+    NextMatch,
+    PrevMatch,
+    CopyHex,
+    CopyAscii,
+    WidenFilePane,
+    NarrowFilePane,
+    Edit,
+    Undo,
+    Search,
+    ToggleHexLayout,
+    CycleHexGroup,
+    ToggleByteColors,
+    CommandPalette,
+    JumpBack,
+    JumpForward,
+    Quit,
+}
+
+/// Maps the keys of the interactive mode to actions. Loaded from
+/// `~/.config/mule/keys.toml`.
+pub struct KeyMap {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+const DEFAULT_BINDINGS: [(Action, &[KeyCode]); 29] = [
+    (Action::FocusNext, &[KeyCode::Tab]),
+    (Action::FocusPrev, &[KeyCode::BackTab]),
+    (Action::SelectNext, &[KeyCode::Down, KeyCode::Char('j')]),
+    (Action::SelectPrev, &[KeyCode::Up, KeyCode::Char('k')]),
+    (Action::PageDown, &[KeyCode::PageDown]),
+    (Action::PageUp, &[KeyCode::PageUp]),
+    (Action::Home, &[KeyCode::Home]),
+    (Action::End, &[KeyCode::End]),
+    (Action::Left, &[KeyCode::Left, KeyCode::Char('h')]),
+    (Action::Right, &[KeyCode::Right, KeyCode::Char('l')]),
+    (Action::Confirm, &[KeyCode::Enter]),
+    (Action::Close, &[KeyCode::Esc]),
+    (Action::ToggleHelp, &[KeyCode::Char('?')]),
+    (Action::ToggleDisassemble, &[KeyCode::Char('d')]),
+    (Action::NextMatch, &[KeyCode::Char('n')]),
+    (Action::PrevMatch, &[KeyCode::Char('N')]),
+    (Action::CopyHex, &[KeyCode::Char('y')]),
+    (Action::CopyAscii, &[KeyCode::Char('Y')]),
+    (Action::WidenFilePane, &[KeyCode::Char('>')]),
+    (Action::NarrowFilePane, &[KeyCode::Char('<')]),
+    (Action::Edit, &[KeyCode::Char('i')]),
+    (Action::Undo, &[KeyCode::Char('u')]),
+    (Action::Search, &[KeyCode::Char('/')]),
+    (Action::ToggleHexLayout, &[KeyCode::Char('w')]),
+    (Action::CycleHexGroup, &[KeyCode::Char('g')]),
+    (Action::ToggleByteColors, &[KeyCode::Char('c')]),
+    (Action::CommandPalette, &[KeyCode::Char('p')]),
+    (Action::JumpBack, &[KeyCode::Char('[')]),
+    (Action::JumpForward, &[KeyCode::Char(']')]),
+];
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        for (action, keys) in DEFAULT_BINDINGS {
+            for key in keys {
+                bindings.insert(*key, action);
+            }
+        }
+        KeyMap { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Loads the key file. Each entry replaces the default keys of an action,
+    /// e.g. `select_next = ["Down", "j"]`. A missing file results in the
+    /// default key map, a malformed file in an error.
+    pub fn load() -> Result<KeyMap, String> {
+        let Some(path) = config_path("keys.toml") else {
+            return Ok(KeyMap::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(KeyMap::default()),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        KeyMap::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// The default key map with the keys of the actions in the toml `content`.
+    pub fn parse(content: &str) -> Result<KeyMap, String> {
+        let file: HashMap<Action, Vec<String>> =
+            toml::from_str(content).map_err(|e| e.to_string())?;
+
+        let mut key_map = KeyMap::default();
+        for (action, keys) in file {
+            key_map.bindings.retain(|_, a| *a != action);
+            for key in keys {
+                key_map.bindings.insert(parse_key(&key)?, action);
+            }
+        }
+        Ok(key_map)
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// The keys bound to the action, named keys before characters.
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        let mut keys: Vec<KeyCode> = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(key, _)| *key)
+            .collect();
+        keys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        keys
+    }
+}
+
+/// The name of a key as shown in the help, e.g. `PageDown` or `j`.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::BackTab => "Shift+Tab".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        _ => format!("{:?}", key),
+    }
+}
+
+/// Parses a single character (`j`) or a key name (`PageDown`).
+fn parse_key(str: &str) -> Result<KeyCode, String> {
+    let mut chars = str.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    match str.to_ascii_lowercase().as_str() {
+        "tab" => Ok(KeyCode::Tab),
+        "backtab" | "shift+tab" => Ok(KeyCode::BackTab),
+        "up" => Ok(KeyCode::Up),
+        "down" => Ok(KeyCode::Down),
+        "left" => Ok(KeyCode::Left),
+        "right" => Ok(KeyCode::Right),
+        "pageup" => Ok(KeyCode::PageUp),
+        "pagedown" => Ok(KeyCode::PageDown),
+        "home" => Ok(KeyCode::Home),
+        "end" => Ok(KeyCode::End),
+        "enter" => Ok(KeyCode::Enter),
+        "esc" => Ok(KeyCode::Esc),
+        "backspace" => Ok(KeyCode::Backspace),
+        "space" => Ok(KeyCode::Char(' ')),
+        _ => Err(format!("unknown key '{}'", str)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_of_the_default_bindings() {
+        let key_map = KeyMap::default();
+        assert_eq!(
+            key_map.keys(Action::SelectNext),
+            [KeyCode::Down, KeyCode::Char('j')]
+        );
+        assert_eq!(key_map.keys(Action::FocusPrev), [KeyCode::BackTab]);
+        assert!(key_map.keys(Action::Quit).is_empty());
+    }
+
+    #[test]
+    fn file_replaces_the_keys_of_an_action() {
+        let key_map = KeyMap::parse("select_next = [\"s\", \"PageDown\"]\nquit = [\"q\"]").unwrap();
+        assert_eq!(
+            key_map.keys(Action::SelectNext),
+            [KeyCode::PageDown, KeyCode::Char('s')]
+        );
+        assert_eq!(key_map.action(KeyCode::Char('j')), None);
+        assert_eq!(key_map.action(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(
+            key_map.keys(Action::SelectPrev),
+            [KeyCode::Up, KeyCode::Char('k')]
+        );
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(KeyMap::parse("quit = [\"Hyper\"]").is_err());
+    }
+
+    #[test]
+    fn key_names_parse_back() {
+        for key in [
+            KeyCode::BackTab,
+            KeyCode::PageDown,
+            KeyCode::Esc,
+            KeyCode::Char(' '),
+            KeyCode::Char('?'),
+        ] {
+            assert_eq!(parse_key(&key_name(key)), Ok(key));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::open::{FormatParser, OpenedBinary, open_binary_file_cancelable};
+
+/// Files larger than this are parsed on a background thread.
+pub const BACKGROUND_LOAD_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How often the UI is redrawn while a file loads.
+pub const LOADING_TICK: Duration = Duration::from_millis(100);
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Tells an open running on another thread to stop. The open checks the
+/// flag while reading and inflating the file and between the parse steps,
+/// the parsing of a format itself runs to its end.
+#[derive(Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fails once the open was canceled.
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_canceled() {
+            Err("canceled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A binary that is read and parsed on a background thread. Dropping it
+/// cancels the load, the thread stops at its next check of the cancel flag.
+pub struct Loading {
+    pub file_path: String,
+    pub path: PathBuf,
+    receiver: Receiver<Result<Vec<OpenedBinary>, String>>,
+    cancel: CancelFlag,
+    started: Instant,
+}
+
+impl Loading {
+    pub fn start(
+        file_path: &str,
+        path: &Path,
+        parsers: &'static [&'static dyn FormatParser],
+    ) -> Loading {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = CancelFlag::default();
+        let thread_cancel = cancel.clone();
+        let thread_path = path.to_path_buf();
+        thread::spawn(move || {
+            // the receiver is gone if the load was canceled
+            let _ = sender.send(open_binary_file_cancelable(
+                &thread_path,
+                parsers,
+                &thread_cancel,
+            ));
+        });
+        Loading {
+            file_path: file_path.to_string(),
+            path: path.to_path_buf(),
+            receiver,
+            cancel,
+            started: Instant::now(),
+        }
+    }
+
+    /// The opened binaries once the thread is done, None while it still runs.
+    pub fn poll(&self) -> Option<Result<Vec<OpenedBinary>, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("the loading thread stopped unexpectedly".to_string()))
+            }
+        }
+    }
+
+    /// The status line shown while loading, with a spinner that advances
+    /// each tick.
+    pub fn status(&self) -> String {
+        let elapsed = self.started.elapsed();
+        let frame = (elapsed.as_millis() / LOADING_TICK.as_millis()) as usize;
+        format!(
+            "{} loading {} ({}s), Esc cancels",
+            SPINNER[frame % SPINNER.len()],
+            self.file_path,
+            elapsed.as_secs()
+        )
+    }
+}
+
+impl Drop for Loading {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::open::FORMAT_PARSERS;
+
+    #[test]
+    fn drop_cancels_the_load() {
+        let loading = Loading::start(
+            "missing",
+            Path::new("/nonexistent/missing.bin"),
+            &FORMAT_PARSERS,
+        );
+        let cancel = loading.cancel.clone();
+        assert!(!cancel.is_canceled());
+        drop(loading);
+        assert!(cancel.is_canceled());
+        assert_eq!(cancel.check(), Err("canceled".to_string()));
+    }
+}
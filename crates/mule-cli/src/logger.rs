@@ -0,0 +1,77 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Enables logging with the level of the `MULE_LOG` env var ("debug" if the
+/// var is set but not a level), or "debug" if `verbose` is set. Without
+/// either nothing is logged.
+pub fn init(verbose: bool) -> Result<(), String> {
+    let level = match env::var("MULE_LOG") {
+        Ok(value) => value.parse().unwrap_or(LevelFilter::Debug),
+        Err(_) if verbose => LevelFilter::Debug,
+        Err(_) => return Ok(()),
+    };
+    let Some(path) = log_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }))
+    .map_err(|e| e.to_string())?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// `~/.cache/mule/mule.log`
+fn log_path() -> Option<PathBuf> {
+    let home = env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".cache/mule/mule.log"))
+}
+
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if let Ok(mut file) = self.file.lock() {
+            // a failed write can't be reported anywhere, the screen belongs to the TUI
+            let _ = writeln!(
+                file,
+                "{} {:<5} {}: {}",
+                secs,
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
@@ -1,58 +1,321 @@
+mod binary_view;
+mod command;
+mod complete;
+mod config;
+mod diff;
+mod disasm_cache;
+mod dump;
+mod export;
+mod focus;
+mod hash;
 mod hex;
+mod ihex;
+mod jumps;
+mod keymap;
+mod loading;
+mod logger;
 mod open;
+mod patch;
+mod recent;
+mod search;
+mod session;
+mod settings;
+#[cfg(test)]
+mod test_util;
+mod theme;
 mod view;
+mod view_diff;
+mod view_elf;
+mod view_entropy;
 mod view_gb;
+mod view_inspector;
 mod view_macho;
+mod view_memmap;
+mod view_minimap;
+mod view_pe;
+mod view_raw;
+mod view_strings;
+mod view_wasm;
 
 use crate::{
-    open::{BinaryFile, open_binary_file},
-    view::style_focus,
-    view_gb::{GBInteractiveState, GBWidget},
-    view_macho::{MachoInteractiveState, MachoWidget},
+    binary_view::BinaryView,
+    command::{COMMANDS, Command, CommandOutcome, Palette, UNSAVED_EDITS_ERROR, dispatch},
+    complete::complete_path,
+    diff::diff_bytes,
+    export::{ExportFormat, export_buffer},
+    hash::{HashAlgorithm, hash},
+    hex::ascii_char,
+    ihex::{is_intel_hex_path, to_intel_hex},
+    jumps::{JumpList, Location},
+    keymap::{Action, KeyMap, key_name},
+    loading::{BACKGROUND_LOAD_BYTES, LOADING_TICK, Loading},
+    open::{
+        FORMAT_PARSERS, OpenedBinary, STDIN_PATH, companion_save, open_binary_file, open_stdin,
+    },
+    patch::{Patches, backup_original},
+    recent::{Recent, RecentPicker},
+    search::{SearchResult, count_matches, find_all, parse_hex_bytes},
+    session::{Session, SessionBinary},
+    settings::Settings,
+    theme::Theme,
+    view::find_line,
+    view_diff::{DiffState, DiffWidget},
+    view_entropy::{EntropyState, EntropyWidget, shannon_entropy},
+    view_gb::GBView,
+    view_inspector::{INSPECTOR_WIDTH, InspectorWidget},
+    view_minimap::{MINIMAP_WIDTH, MinimapState, MinimapWidget},
+    view_strings::{StringsState, StringsWidget, find_strings},
+};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
+    execute,
+};
+use mule_gb::{
+    EDITABLE_HEADER_FIELDS, GLOBAL_CHECKSUM_OFFSET, HEADER_CHECKSUM_OFFSET, encode_header_field,
+    global_checksum, header_checksum,
 };
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Flex, Layout, Position, Rect},
     style::{Color, Style, Stylize},
-    widgets::{Block, BorderType, Paragraph, Widget},
+    widgets::{
+        Block, BorderType, Clear, List, ListItem, Paragraph, Row, StatefulWidget, Table, Tabs,
+        Widget,
+    },
 };
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    io::stdout,
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+};
+use unicode_width::UnicodeWidthStr;
 
 fn main() -> Result<(), String> {
+    let mut restore = false;
+    let mut verbose = false;
+    let mut dump = false;
+    let mut format = None;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        if arg == "--restore" {
+            restore = true;
+        } else if arg == "--verbose" {
+            verbose = true;
+        } else if arg == "--dump" {
+            dump = true;
+        } else if let Some(extension) = arg.strip_prefix("--format=") {
+            format = Some(extension.to_ascii_lowercase());
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    if let Err(err) = logger::init(verbose) {
+        eprintln!("failed to open the log file: {}", err);
+    }
+
+    if dump {
+        if paths.is_empty() {
+            eprintln!("--dump needs at least one file");
+            process::exit(1);
+        }
+        if let Err(err) = dump_paths(&paths, format.as_deref()) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut mule = Mule::new();
+    if paths.is_empty() {
+        mule.init_session(restore);
+    } else if let Err(err) = mule.open_paths(&paths, format.as_deref()) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+
     let mut terminal = ratatui::init();
-    Mule::new().run(&mut terminal)?;
+    let result = execute!(stdout(), EnableMouseCapture)
+        .map_err(|e| e.to_string())
+        .and_then(|_| mule.run(&mut terminal));
+    // restored before an error is returned, it is unreadable on the
+    // alternate screen
+    let disabled = execute!(stdout(), DisableMouseCapture).map_err(|e| e.to_string());
     ratatui::restore();
+    result?;
+    disabled?;
+
+    if let Err(err) = mule.session().save() {
+        eprintln!("failed to save the session: {}", err);
+    }
     Ok(())
 }
 
+/// Prints the report of every binary in `paths` (each slice of a fat Mach-O)
+/// to stdout. `format` is the format of the standard input (path "-").
+fn dump_paths(paths: &[String], format: Option<&str>) -> Result<(), String> {
+    for (i, path) in paths.iter().enumerate() {
+        let opened = if path == STDIN_PATH {
+            open_stdin(format, &FORMAT_PARSERS)
+        } else {
+            open_binary_file(&PathBuf::from(path), &FORMAT_PARSERS)
+        }
+        .map_err(|e| format!("failed to open {}: {}", path, e))?;
+        for (j, binary) in opened.iter().enumerate() {
+            if i + j > 0 {
+                println!();
+            }
+            let name = match &binary.slice {
+                Some((index, arch)) => format!("{} [slice {}: {}]", path, index, arch),
+                None => path.clone(),
+            };
+            print!("{}", dump::dump(&name, binary.view.as_ref(), &binary.data));
+        }
+    }
+    Ok(())
+}
+
+/// Rows scrolled in a hex view per mouse wheel step
+const MOUSE_SCROLL_ROWS: usize = 3;
+
+/// Bytes searched for the match count while a `:find` pattern is typed
+const LIVE_FIND_MAX_BYTES: usize = 64 * 1024 * 1024;
+
 #[derive(Debug)]
 enum InputMode {
     Command,     // Focus in on the command line
     Interactive, // Focus is on the display widget
-}
-
-pub enum InteractiveState {
-    None,
-    Macho(MachoInteractiveState),
-    GB(GBInteractiveState),
+    Edit,        // Typed hex digits overwrite the byte under the hex cursor
 }
 
 pub enum InteractiveCommand {
     Focus,
     Unfocus,
-    Key(KeyCode),
+    Action(Action),
+    Mouse(MouseEvent),
+    Toggle(ViewToggle),
+}
+
+/// The panes of a single format that a command switches on and off.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ViewToggle {
+    Tiles,
+    MemoryMap,
+    Dylibs,
+    SectionGaps,
 }
 
 struct BinaryState {
     path: PathBuf,
-    file: BinaryFile,
+    /// The parsed binary and the state of its view
+    view: Box<dyn BinaryView>,
+    data: Vec<u8>,
+    /// Edits of `data` that are not written to the file yet
+    patches: Patches,
+    /// Digests computed by :hash, cleared when `data` changes
+    hashes: HashMap<HashAlgorithm, String>,
+    /// Bookmarked file offsets by name
+    marks: BTreeMap<String, usize>,
+    /// Positions before the jumps, for going back and forth
+    jumps: JumpList,
+    /// Index and architecture of the slice of a fat Mach-O
+    slice: Option<(usize, String)>,
+    /// The file on disk is compressed, `data` are the inflated bytes
+    compressed: bool,
+}
+
+impl BinaryState {
+    /// The focused pane and the offset shown in its hex view.
+    fn location(&self) -> Location {
+        Location {
+            focus: self.view.focus_index(),
+            offset: self.view.shown_offset(),
+        }
+    }
+
+    fn push_jump(&mut self) {
+        let location = self.location();
+        self.jumps.push(location);
+    }
+
+    /// Sets the byte at `offset` as an edit, unless it has the value already.
+    fn set_changed_byte(&mut self, offset: usize, value: u8) {
+        if self.data.get(offset).is_some_and(|b| *b != value)
+            && self.patches.set(&mut self.data, offset, value)
+        {
+            self.data_changed();
+        }
+    }
+
+    /// Drops the results computed from the bytes before they were edited.
+    fn data_changed(&mut self) {
+        self.hashes.clear();
+        self.view.clear_disassemblies();
+    }
+
+    /// Parses the binary again from the edited data, e.g. a GB ROM after its
+    /// header changed.
+    fn reparse(&mut self) -> Result<(), String> {
+        self.view.reparse(&self.data)
+    }
+
+    fn show_location(&mut self, location: Location) {
+        if let Some(offset) = location.offset {
+            self.view.show_offset(offset);
+        }
+        self.view.set_focus_index(location.focus);
+    }
+
+    /// The file name (and slice architecture), used as tab title. Unsaved
+    /// edits are marked with a '+'.
+    fn name(&self) -> String {
+        let file_name = if self.is_stdin() {
+            "stdin".to_string()
+        } else {
+            self.path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let name = match &self.slice {
+            Some((_, arch)) => format!("{} [{}]", file_name, arch),
+            None => file_name,
+        };
+        if self.patches.is_dirty() {
+            format!("{} +", name)
+        } else {
+            name
+        }
+    }
+
+    /// True if the bytes were piped in, there is no file to reload or write.
+    fn is_stdin(&self) -> bool {
+        self.path == Path::new(STDIN_PATH)
+    }
 }
 
+/// The opened binaries, each one is shown in its own tab.
 struct ProjectState {
-    binary: Option<BinaryState>,
-    interactive_state: InteractiveState,
+    binaries: Vec<BinaryState>,
+    active: usize,
+}
+
+impl ProjectState {
+    fn active(&self) -> Option<&BinaryState> {
+        self.binaries.get(self.active)
+    }
+
+    fn active_mut(&mut self) -> Option<&mut BinaryState> {
+        self.binaries.get_mut(self.active)
+    }
 }
 
 struct Mule {
@@ -60,28 +323,196 @@ struct Mule {
     input: String,
     input_mode: InputMode,
     character_index: usize,
+    history: Vec<String>,
+    // position in history while browsing it, None if not browsing
+    history_index: Option<usize>,
+    // the partially typed input before browsing the history started
+    history_draft: String,
+    status: String,
+    status_is_error: bool,
+    show_help: bool,
+    show_marks: bool,
+    show_inspector: bool,
+    theme: Theme,
+    key_map: KeyMap,
+    recent: Recent,
+    settings: Settings,
+    // the recent files popup is only shown if set
+    recent_picker: Option<RecentPicker>,
+    // the command palette is only shown if set
+    palette: Option<Palette>,
+    // a large file parsed in the background, one at a time
+    loading: Option<Loading>,
+    // the entropy pane is only shown if set
+    entropy: Option<EntropyState>,
+    // the minimap column is only shown if set
+    minimap: Option<MinimapState>,
+    // the strings view replaces the binary view if set
+    strings: Option<StringsState>,
+    // the diff view replaces the binary view if set
+    diff: Option<DiffState>,
+    // updated on render, used to map mouse clicks and by :export-view
+    frame_area: Rect,
+    content_area: Rect,
+    command_area: Rect,
+    search: Option<SearchResult>,
+    // the pattern of the last search in a disassembly
+    disassembly_search: Option<String>,
     exit: bool,
 }
 
 impl Mule {
     pub fn new() -> Mule {
         let project_state = ProjectState {
-            binary: None,
-            interactive_state: InteractiveState::None,
+            binaries: Vec::new(),
+            active: 0,
+        };
+
+        let (theme, mut status, mut status_is_error) = match Theme::load() {
+            Ok(theme) => (theme, String::new(), false),
+            Err(err) => (Theme::default(), err, true),
         };
+        let key_map = KeyMap::load().unwrap_or_else(|err| {
+            status = err;
+            status_is_error = true;
+            KeyMap::default()
+        });
+        let recent = Recent::load().unwrap_or_else(|err| {
+            status = err;
+            status_is_error = true;
+            Recent::default()
+        });
+        let settings = Settings::load().unwrap_or_else(|err| {
+            status = err;
+            status_is_error = true;
+            Settings::default()
+        });
 
         Mule {
             project_state,
             input: String::new(),
             input_mode: InputMode::Command,
             character_index: 0,
+            history: Vec::new(),
+            history_index: None,
+            history_draft: String::new(),
+            status,
+            status_is_error,
+            show_help: false,
+            show_marks: false,
+            show_inspector: false,
+            theme,
+            key_map,
+            recent,
+            settings,
+            recent_picker: None,
+            palette: None,
+            loading: None,
+            entropy: None,
+            minimap: None,
+            strings: None,
+            diff: None,
+            frame_area: Rect::default(),
+            content_area: Rect::default(),
+            command_area: Rect::default(),
+            search: None,
+            disassembly_search: None,
             exit: false,
         }
     }
 
+    /// Opens the binaries given on the command line and starts in the
+    /// interactive mode with the first one shown.
+    pub fn open_paths(&mut self, paths: &[String], format: Option<&str>) -> Result<(), String> {
+        for path in paths {
+            let result = if path == STDIN_PATH {
+                self.open_stdin(format)
+            } else {
+                self.open(path, None)
+            };
+            result.map_err(|e| format!("{}: {}", path, e))?;
+        }
+        self.project_state.active = 0;
+        self.input_mode = InputMode::Interactive;
+        Ok(())
+    }
+
+    /// Restores the session of the last run if `restore` is set, otherwise
+    /// only offers to restore it.
+    pub fn init_session(&mut self, restore: bool) {
+        let session = match Session::load() {
+            Ok(Some(session)) => session,
+            Ok(None) => return,
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+        if restore {
+            self.restore_session(session);
+        } else if !session.binaries.is_empty() {
+            self.set_info(format!(
+                "last session had {} binaries open, reopen them with :restore",
+                session.binaries.len()
+            ));
+        }
+    }
+
+    /// The opened binaries and their focus, to be saved on exit.
+    pub fn session(&self) -> Session {
+        let binaries = self
+            .project_state
+            .binaries
+            .iter()
+            .filter(|b| !b.is_stdin())
+            .map(|b| SessionBinary {
+                path: b.path.clone(),
+                focus: b.view.focus_index(),
+                marks: b.marks.clone(),
+                slice: b.slice.as_ref().map(|(ix, _)| *ix),
+            })
+            .collect();
+        // piped in binaries are not restored, they don't count for the index
+        let active = self
+            .project_state
+            .binaries
+            .iter()
+            .take(self.project_state.active)
+            .filter(|b| !b.is_stdin())
+            .count();
+        Session { binaries, active }
+    }
+
+    fn restore_session(&mut self, session: Session) {
+        let mut skipped = Vec::new();
+        for binary in &session.binaries {
+            let path = binary.path.to_string_lossy();
+            if !binary.path.exists() || self.open(&path, binary.slice).is_err() {
+                skipped.push(path.to_string());
+                continue;
+            }
+            if let Some(opened) = self.project_state.binaries.last_mut() {
+                opened.view.set_focus_index(binary.focus);
+                opened.marks = binary.marks.clone();
+            }
+        }
+        let opened = self.project_state.binaries.len();
+        self.project_state.active = session.active.min(opened.saturating_sub(1));
+        if skipped.is_empty() {
+            self.set_info(format!("restored {} binaries", opened));
+        } else {
+            self.set_error(format!(
+                "restored {} binaries, skipped {}",
+                opened,
+                skipped.join(", ")
+            ));
+        }
+    }
+
     /// runs the application's main loop until the user quits
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
         while !self.exit {
+            self.finish_loading();
             terminal
                 .draw(|frame| self.draw(frame))
                 .map_err(|e| e.to_string())?;
@@ -92,8 +523,42 @@ impl Mule {
         Ok(())
     }
 
+    /// Keeps the first shown byte of the hex views of all binaries when the
+    /// terminal is resized. The other scroll positions are clamped to the
+    /// new size on render.
+    fn relayout(&mut self) {
+        for binary in &mut self.project_state.binaries {
+            binary.view.relayout();
+        }
+        if let Some(diff) = &mut self.diff {
+            diff.relayout();
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
-        frame.render_widget(self, frame.area());
+        frame.render_widget(&mut *self, frame.area());
+        // the palette and popups take the typed keys
+        if matches!(self.input_mode, InputMode::Command)
+            && self.palette.is_none()
+            && self.recent_picker.is_none()
+        {
+            let inner = Block::bordered().inner(self.command_area);
+            let column = self.cursor_column() - self.command_scroll(inner.width);
+            frame.set_cursor_position((inner.x + column as u16, inner.y));
+        }
+    }
+
+    /// Display column of the cursor in the typed input. Wide characters (CJK,
+    /// emoji) take two cells, the cursor still moves by chars.
+    fn cursor_column(&self) -> usize {
+        self.input[..self.byte_index()].width()
+    }
+
+    /// Columns the input is scrolled left by to keep the cursor in a command
+    /// line of `width` cells.
+    fn command_scroll(&self, width: u16) -> usize {
+        self.cursor_column()
+            .saturating_sub((width as usize).saturating_sub(1))
     }
 
     fn move_cursor_left(&mut self) {
@@ -121,6 +586,7 @@ impl Mule {
 
             self.input = before_char_to_delete.chain(after_char_to_delete).collect();
             self.move_cursor_left();
+            self.count_find_matches();
         }
     }
 
@@ -136,126 +602,1469 @@ impl Mule {
         let index = self.byte_index();
         self.input.insert(index, new_char);
         self.move_cursor_right();
+        self.count_find_matches();
+    }
+
+    /// Shows the number of matches of the `:find` pattern while it is typed.
+    /// Only the start of large binaries is searched to keep typing responsive.
+    fn count_find_matches(&mut self) {
+        let Some(pattern) = self.input.strip_prefix(":find ") else {
+            return;
+        };
+        let pattern: String = pattern.split_whitespace().collect();
+        // wait for the second nibble of a byte
+        if pattern.is_empty() || !pattern.len().is_multiple_of(2) {
+            return;
+        }
+        let Some(binary) = self.project_state.active() else {
+            return;
+        };
+        let message = match parse_hex_bytes(&pattern) {
+            Ok(pattern) => match count_matches(&binary.data, &pattern, LIVE_FIND_MAX_BYTES) {
+                (1, true) => "1 match".to_string(),
+                (count, true) => format!("{} matches", count),
+                (count, false) => format!(
+                    "{} matches in the first {} MiB, Enter searches all",
+                    count,
+                    LIVE_FIND_MAX_BYTES / (1024 * 1024)
+                ),
+            },
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+        self.set_info(message);
+    }
+
+    fn history_previous(&mut self) {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return,
+            None => {
+                self.history_draft = self.input.clone();
+                self.history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone());
+    }
+
+    fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.set_input(self.history[index + 1].clone());
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.history_draft);
+            self.set_input(draft);
+        }
+    }
+
+    fn complete_open_path(&mut self) {
+        let partial = self.input[":o ".len()..].trim_start();
+        match complete_path(partial) {
+            Err(err) => self.set_error(err),
+            Ok(completion) => {
+                if completion.candidates.len() > 1 {
+                    self.set_info(completion.candidates.join("  "));
+                } else {
+                    self.status.clear();
+                }
+                self.set_input(format!(":o {}", completion.completed));
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.input = input;
+        self.character_index = self.input.chars().count();
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if matches!(self.input_mode, InputMode::Edit) {
+                    self.stop_edit();
+                }
+                let minimap_offset = match (&self.minimap, self.project_state.active()) {
+                    (Some(minimap), Some(binary)) if self.diff.is_none() => {
+                        minimap.offset_at(position, binary.data.len())
+                    }
+                    _ => None,
+                };
+                if let Some(offset) = minimap_offset {
+                    self.show_minimap_offset(offset);
+                } else if self.command_area.contains(position) {
+                    if matches!(self.input_mode, InputMode::Interactive) {
+                        self.forward_command(InteractiveCommand::Unfocus);
+                        self.input_mode = InputMode::Command;
+                    }
+                } else if self.content_area.contains(position) {
+                    if matches!(self.input_mode, InputMode::Command) {
+                        self.input_mode = InputMode::Interactive;
+                        self.forward_command(InteractiveCommand::Focus);
+                    }
+                    self.forward_command(InteractiveCommand::Mouse(mouse));
+                }
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+                if matches!(self.input_mode, InputMode::Interactive) =>
+            {
+                let down = mouse.kind == MouseEventKind::ScrollDown;
+                let hex_state = self
+                    .project_state
+                    .active_mut()
+                    .and_then(|b| b.view.focused_hex());
+                match hex_state {
+                    Some(hex_state) if down => hex_state.scroll_down(MOUSE_SCROLL_ROWS),
+                    Some(hex_state) => hex_state.scroll_up(MOUSE_SCROLL_ROWS),
+                    None => {
+                        let action = if down {
+                            Action::SelectNext
+                        } else {
+                            Action::SelectPrev
+                        };
+                        self.forward_command(InteractiveCommand::Action(action));
+                    }
+                }
+            }
+            _ => { /* ignore */ }
+        }
     }
 
     fn handle_events(&mut self) -> Result<bool, String> {
-        if let Event::Key(key) = event::read().map_err(|e| e.to_string())? {
+        // keep drawing the loading progress while no key is pressed
+        if self.loading.is_some() && !event::poll(LOADING_TICK).map_err(|e| e.to_string())? {
+            return Ok(false);
+        }
+        let event = event::read().map_err(|e| e.to_string())?;
+        if let Event::Resize(_, _) = event {
+            self.relayout();
+        }
+        if let Event::Mouse(mouse) = event {
+            self.handle_mouse(mouse);
+        }
+        if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 return Ok(false);
             }
+            if key.code == KeyCode::Esc && self.loading.is_some() {
+                self.cancel_loading();
+                return Ok(false);
+            }
+            if self.palette.is_some() {
+                return Ok(self.handle_palette_key(key.code));
+            }
             match self.input_mode {
                 InputMode::Command => match key.code {
                     KeyCode::Enter => {
-                        if self.exec_command()? {
+                        if self.exec_command() {
                             return Ok(true);
                         }
                         self.input_mode = InputMode::Interactive;
+                        self.forward_command(InteractiveCommand::Focus);
                     }
                     KeyCode::Char(to_insert) => self.enter_char(to_insert),
                     KeyCode::Backspace => self.delete_char(),
                     KeyCode::Left => self.move_cursor_left(),
                     KeyCode::Right => self.move_cursor_right(),
+                    KeyCode::Tab if self.input.starts_with(":o ") => self.complete_open_path(),
+                    KeyCode::Up => self.history_previous(),
+                    KeyCode::Down => self.history_next(),
                     _ => { /* ignore */ }
                 },
+                InputMode::Edit => self.handle_edit_key(key.code),
                 InputMode::Interactive => {
-                    match key.code {
-                        KeyCode::Esc => {
-                            self.forward_command(InteractiveCommand::Unfocus);
-                            self.input_mode = InputMode::Command;
-                        }
-                        _ => self.forward_command(InteractiveCommand::Key(key.code)),
+                    if let Some(action) = self.key_map.action(key.code) {
+                        return Ok(self.handle_action(action));
                     }
-                    // TODO forward event to current widget
                 }
             }
         }
         Ok(false)
     }
 
-    fn forward_command(&mut self, command: InteractiveCommand) {
-        match &mut self.project_state.interactive_state {
-            InteractiveState::None => {}
-            InteractiveState::Macho(s) => s.handle_command(command),
-            InteractiveState::GB(s) => s.handle_command(command),
+    /// Executes an action of the interactive mode. Returns true if the
+    /// application should quit.
+    fn handle_action(&mut self, action: Action) -> bool {
+        let list_action = matches!(
+            action,
+            Action::SelectNext
+                | Action::SelectPrev
+                | Action::PageDown
+                | Action::PageUp
+                | Action::Home
+                | Action::End
+        );
+        let diff_action = list_action
+            || matches!(
+                action,
+                Action::Left
+                    | Action::Right
+                    | Action::ToggleHexLayout
+                    | Action::JumpBack
+                    | Action::JumpForward
+            );
+        match action {
+            Action::Quit if self.has_unsaved_edits() => self.set_error(UNSAVED_EDITS_ERROR),
+            Action::Quit => return true,
+            Action::ToggleHelp => self.show_help = !self.show_help,
+            Action::Close if self.show_help => self.show_help = false,
+            Action::Close if self.show_marks => self.show_marks = false,
+            Action::Close if self.recent_picker.is_some() => self.recent_picker = None,
+            Action::Confirm if self.recent_picker.is_some() => self.open_recent(),
+            _ if list_action && self.recent_picker.is_some() => {
+                if let Some(picker) = &mut self.recent_picker {
+                    match action {
+                        Action::SelectNext | Action::PageDown => picker.select_next(),
+                        Action::Home => picker.select_first(),
+                        Action::End => picker.select_last(),
+                        _ => picker.select_previous(),
+                    }
+                }
+            }
+            Action::Close if self.diff.is_some() => self.diff = None,
+            _ if diff_action && self.diff.is_some() => {
+                if let Some(diff) = &mut self.diff {
+                    diff.handle_action(action);
+                }
+            }
+            Action::Close if self.strings.is_some() => self.strings = None,
+            Action::Confirm if self.strings.is_some() => self.show_selected_string(),
+            Action::Confirm if self.selected_jump_target().is_some() => self.follow_jump(),
+            _ if list_action && self.strings.is_some() => {
+                if let Some(strings) = &mut self.strings {
+                    match action {
+                        Action::SelectNext => strings.list_state.select_next(),
+                        Action::SelectPrev => strings.list_state.select_previous(),
+                        Action::PageDown => strings.page_down(),
+                        Action::PageUp => strings.page_up(),
+                        Action::Home => strings.list_state.select_first(),
+                        _ => strings.list_state.select_last(),
+                    }
+                }
+            }
+            Action::Close => {
+                self.forward_command(InteractiveCommand::Unfocus);
+                self.input_mode = InputMode::Command;
+            }
+            Action::Left if self.entropy.is_some() => {
+                if let Some(entropy) = &mut self.entropy {
+                    entropy.select_previous();
+                }
+                self.show_entropy_selection();
+            }
+            Action::Right if self.entropy.is_some() => {
+                if let Some(entropy) = &mut self.entropy {
+                    entropy.select_next();
+                }
+                self.show_entropy_selection();
+            }
+            Action::Edit => self.start_edit(),
+            Action::Undo => self.undo(),
+            Action::ToggleHexLayout => self.toggle_hex_layout(),
+            Action::CycleHexGroup => self.cycle_hex_group(),
+            Action::ToggleByteColors => self.toggle_byte_colors(),
+            Action::CommandPalette => self.open_palette(),
+            Action::JumpBack => self.jump_back(),
+            Action::JumpForward => self.jump_forward(),
+            Action::Search => {
+                self.forward_command(InteractiveCommand::Unfocus);
+                self.input_mode = InputMode::Command;
+                self.set_input("/".to_string());
+            }
+            Action::CopyHex => self.copy_region(false),
+            Action::CopyAscii => self.copy_region(true),
+            Action::NextMatch if self.search.is_some() => {
+                if let Some(search) = &mut self.search {
+                    search.next();
+                }
+                self.show_search_match();
+            }
+            Action::PrevMatch if self.search.is_some() => {
+                if let Some(search) = &mut self.search {
+                    search.previous();
+                }
+                self.show_search_match();
+            }
+            Action::NextMatch if self.disassembly_search.is_some() => {
+                self.find_in_disassembly(false)
+            }
+            Action::PrevMatch if self.disassembly_search.is_some() => {
+                self.find_in_disassembly(true)
+            }
+            _ => self.forward_command(InteractiveCommand::Action(action)),
         }
+        false
     }
 
-    fn exec_command(&mut self) -> Result<bool, String> {
-        if self.input == ":q" {
-            return Ok(true);
+    fn open_palette(&mut self) {
+        self.palette = Some(Palette::new());
+    }
+
+    /// Filters the command palette by the typed text. Returns true if the
+    /// command run from the palette quits the application.
+    fn handle_palette_key(&mut self, code: KeyCode) -> bool {
+        let Some(palette) = &mut self.palette else {
+            return false;
+        };
+        match code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Enter => {
+                let selected = palette.selected();
+                self.palette = None;
+                if let Some(command) = selected {
+                    return self.run_palette_command(command);
+                }
+            }
+            KeyCode::Char(c) => palette.push(c),
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Down => palette.select_next(),
+            KeyCode::Up => palette.select_previous(),
+            _ => { /* ignore */ }
         }
+        false
+    }
 
-        let input_cmd = self.input.clone();
+    /// Runs a command without arguments, a command with arguments is typed
+    /// into the command line for completing them.
+    fn run_palette_command(&mut self, command: &Command) -> bool {
+        let name = command.names[0];
+        if command.args.is_empty() {
+            self.set_input(format!(":{}", name));
+            return self.exec_command();
+        }
+        if matches!(self.input_mode, InputMode::Interactive) {
+            self.forward_command(InteractiveCommand::Unfocus);
+        }
+        self.input_mode = InputMode::Command;
+        self.set_input(format!(":{} ", name));
+        false
+    }
 
-        if input_cmd.starts_with(":o") {
-            let mut iter = input_cmd.split_whitespace();
-            iter.next();
+    /// Starts editing the bytes of the focused hex view.
+    fn start_edit(&mut self) {
+        let header_focused = self
+            .project_state
+            .active()
+            .is_some_and(|b| b.view.header_focused());
+        if header_focused {
+            // the header fields are edited on the command line
+            self.forward_command(InteractiveCommand::Unfocus);
+            self.input_mode = InputMode::Command;
+            self.set_input(":header ".to_string());
+            self.set_info(format!(
+                "set a header field: {}, the checksums are updated",
+                EDITABLE_HEADER_FIELDS.join(", ")
+            ));
+            return;
+        }
+        let hex_state = self
+            .project_state
+            .active_mut()
+            .and_then(|b| b.view.focused_hex());
+        match hex_state {
+            Some(hex_state) => {
+                hex_state.start_edit();
+                self.input_mode = InputMode::Edit;
+                self.set_info("-- EDIT -- type hex digits, arrows: move, u: undo, Esc: stop");
+            }
+            None => self.set_error("no hex view in focus"),
+        }
+    }
 
-            let file_path = iter.next().expect("file_path");
-            let path = PathBuf::from_str(file_path).map_err(|e| e.to_string())?;
-            let binary_file = open_binary_file(&path)?;
-            let interactive_state = match &binary_file {
-                BinaryFile::Macho(_) => InteractiveState::Macho(MachoInteractiveState::new()),
-                BinaryFile::GB(binary) => InteractiveState::GB(GBInteractiveState::new(binary)),
-            };
-            self.project_state.binary = Some(BinaryState {
-                path,
-                file: binary_file,
-            });
-            self.project_state.interactive_state = interactive_state;
-        } else if input_cmd.starts_with(":i") {
-            self.forward_command(InteractiveCommand::Focus);
+    /// Switches a pane of the active view on or off, `unsupported` is the
+    /// error for views of the other formats.
+    fn toggle_view(&mut self, toggle: ViewToggle, unsupported: &str) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        match binary
+            .view
+            .handle_command(InteractiveCommand::Toggle(toggle))
+        {
+            Some(message) => self.set_info(message),
+            None => self.set_error(unsupported),
+        }
+    }
+
+    /// Opens the `.sav` next to the active GB ROM in a new tab.
+    fn open_save(&mut self) -> Result<(), String> {
+        let binary = self.project_state.active().ok_or("no binary loaded")?;
+        if !binary.view.is::<GBView>() || binary.is_stdin() {
+            return Err("only a GB ROM file has a save".to_string());
+        }
+        let (save_path, _) = companion_save(&binary.path)
+            .ok_or_else(|| format!("no save next to {}", binary.path.display()))?;
+        self.start_open(&save_path.to_string_lossy())
+    }
+
+    fn toggle_hex_layout(&mut self) {
+        let hex_state = self
+            .project_state
+            .active_mut()
+            .and_then(|b| b.view.focused_hex());
+        let Some(hex_state) = hex_state else {
+            self.set_error("no hex view in focus");
+            return;
+        };
+        hex_state.toggle_layout();
+        let message = if hex_state.fixed_layout() {
+            "16 bytes per line, h/l scroll horizontally"
+        } else {
+            "lines fit the width"
+        };
+        self.set_info(message);
+    }
+
+    /// Switches all hex views to the next group size and remembers it.
+    fn cycle_hex_group(&mut self) {
+        let group_bytes = self.settings.hex_group_bytes.next();
+        self.settings.hex_group_bytes = group_bytes;
+        for binary in &mut self.project_state.binaries {
+            binary.view.set_hex_group_bytes(group_bytes);
+        }
+        if let Some(diff) = &mut self.diff {
+            diff.set_hex_group_bytes(group_bytes);
+        }
+        match self.settings.save() {
+            Ok(()) => self.set_info(format!("hex digits grouped by {} bytes", group_bytes.get())),
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    /// Switches the coloring of the bytes by class in all hex views and
+    /// remembers it. The diff view keeps its own colors.
+    fn toggle_byte_colors(&mut self) {
+        let byte_colors = !self.settings.hex_byte_colors;
+        self.settings.hex_byte_colors = byte_colors;
+        for binary in &mut self.project_state.binaries {
+            binary.view.set_hex_byte_colors(byte_colors);
+        }
+        let message = if byte_colors {
+            "bytes colored: zero dim, ASCII green, above 0x7F blue"
+        } else {
+            "bytes shown plain"
+        };
+        match self.settings.save() {
+            Ok(()) => self.set_info(message),
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    fn stop_edit(&mut self) {
+        if let Some(hex_state) = self
+            .project_state
+            .active_mut()
+            .and_then(|b| b.view.focused_hex())
+        {
+            hex_state.stop_edit();
+        }
+        self.input_mode = InputMode::Interactive;
+        self.status.clear();
+    }
+
+    fn handle_edit_key(&mut self, key: KeyCode) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.input_mode = InputMode::Interactive;
+            return;
+        };
+        let start = binary.view.focused_hex_start();
+        let hex_state = binary.view.focused_hex();
+        let (Some(start), Some(hex_state)) = (start, hex_state) else {
+            // the hex view is gone, e.g. after switching the binary
+            self.input_mode = InputMode::Interactive;
+            return;
+        };
+        if hex_state.cursor().is_none() {
+            self.input_mode = InputMode::Interactive;
+            return;
+        }
+        match key {
+            KeyCode::Esc => self.stop_edit(),
+            KeyCode::Left => hex_state.move_cursor(-1, 0),
+            KeyCode::Right => hex_state.move_cursor(1, 0),
+            KeyCode::Up => hex_state.move_cursor(0, -1),
+            KeyCode::Down => hex_state.move_cursor(0, 1),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char(c) => {
+                let Some(digit) = c.to_digit(16) else {
+                    return;
+                };
+                if let Some((pos, value)) = hex_state.type_digit(digit as u8)
+                    && binary.patches.set(&mut binary.data, start + pos, value)
+                {
+                    binary.data_changed();
+                }
+            }
+            _ => { /* ignore */ }
+        }
+    }
+
+    /// Sets a header field of the active GB ROM and updates the header and
+    /// global checksums. The changed bytes are edits like the ones in the hex
+    /// view, `:w` writes them and `u` undoes them byte by byte.
+    fn edit_gb_header(&mut self, field: &str, value: &str) -> Result<(), String> {
+        let binary = self.project_state.active_mut().ok_or("no binary loaded")?;
+        if !binary.view.is::<GBView>() {
+            return Err("only GameBoy ROMs have a cartridge header".to_string());
+        }
+        let (offset, bytes) = encode_header_field(&binary.data, field, value)?;
+        for (i, b) in bytes.into_iter().enumerate() {
+            binary.set_changed_byte(offset + i, b);
+        }
+        let checksum = header_checksum(&binary.data);
+        binary.set_changed_byte(HEADER_CHECKSUM_OFFSET, checksum);
+        let global = global_checksum(&binary.data);
+        for (i, b) in global.to_be_bytes().into_iter().enumerate() {
+            binary.set_changed_byte(GLOBAL_CHECKSUM_OFFSET + i, b);
+        }
+        binary.reparse()?;
+        self.set_info(format!(
+            "set the {} at 0x{:X}, checksums updated, :w writes the ROM",
+            field, offset
+        ));
+        Ok(())
+    }
+
+    /// Reverts the last edit of the active binary.
+    fn undo(&mut self) {
+        let editing = matches!(self.input_mode, InputMode::Edit);
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        let Some(offset) = binary.patches.undo(&mut binary.data) else {
+            self.set_info("nothing to undo");
+            return;
+        };
+        binary.data_changed();
+        // the parsed view shows the restored byte
+        let reparsed = binary.reparse();
+        // showing the offset would move the cursor out of the edited view
+        if !editing {
+            binary.view.show_offset(offset);
+        }
+        match reparsed {
+            Ok(()) => self.set_info(format!("undid the edit at 0x{:X}", offset)),
+            Err(err) => self.set_error(format!("undid the edit at 0x{:X}, {}", offset, err)),
+        }
+    }
+
+    /// Writes the edited bytes of the active binary back to its file.
+    fn write_patches(&mut self) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        if binary.slice.is_some() {
+            self.set_error("can't write a single slice of a fat binary, use :w <path>");
+            return;
+        }
+        if binary.is_stdin() {
+            self.set_error("stdin has no file to write to, use :w <path>");
+            return;
+        }
+        if binary.compressed {
+            self.set_error("can't write back into a compressed file, use :w <path>");
+            return;
+        }
+        if !binary.patches.is_dirty() {
+            self.set_info("no changes to write");
+            return;
+        }
+        let backup = if self.settings.backup_on_write {
+            match backup_original(&binary.path) {
+                Ok(backup) => backup,
+                Err(err) => {
+                    self.set_error(err);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        match fs::write(&binary.path, &binary.data) {
+            Ok(()) => {
+                binary.patches.saved();
+                let mut message = format!(
+                    "wrote {} bytes to {}",
+                    binary.data.len(),
+                    binary.path.display()
+                );
+                if let Some(backup) = backup {
+                    message.push_str(&format!(", the original is in {}", backup.display()));
+                }
+                self.set_info(message);
+            }
+            Err(err) => {
+                let message = format!("{}: {}", binary.path.display(), err);
+                self.set_error(message);
+            }
+        }
+    }
+
+    fn has_unsaved_edits(&self) -> bool {
+        self.project_state
+            .binaries
+            .iter()
+            .any(|b| b.patches.is_dirty())
+    }
+
+    fn active_has_unsaved_edits(&self) -> bool {
+        self.project_state
+            .active()
+            .is_some_and(|b| b.patches.is_dirty())
+    }
+
+    fn forward_command(&mut self, command: InteractiveCommand) {
+        let Some(binary) = self.project_state.active_mut() else {
+            return;
+        };
+        binary.view.handle_command(command);
+    }
+
+    /// Executes the command in the input line. Recoverable errors are shown
+    /// in the status bar. Returns true if the application should quit.
+    fn exec_command(&mut self) -> bool {
+        let input_cmd = self.input.clone();
+        self.status.clear();
+        self.history_index = None;
+        self.history_draft.clear();
+        if !input_cmd.trim().is_empty() {
+            log::debug!("command: {}", input_cmd);
+            self.history.push(input_cmd.clone());
+        }
+
+        if let Some(pattern) = input_cmd.strip_prefix('/') {
+            if pattern.is_empty() {
+                self.set_error("usage: /<pattern>");
+            } else {
+                self.disassembly_search = Some(pattern.to_string());
+                self.search = None;
+                self.find_in_disassembly(false);
+            }
+        } else if !input_cmd.is_empty() {
+            match dispatch(self, &input_cmd) {
+                Ok(CommandOutcome::Quit) => return true,
+                Ok(CommandOutcome::Continue) => {}
+                Err(err) => self.set_error(err),
+            }
         }
 
         self.input.clear();
         self.character_index = 0;
 
-        Ok(false)
+        false
+    }
+
+    fn set_info(&mut self, message: impl Into<String>) {
+        self.status = message.into();
+        self.status_is_error = false;
+    }
+
+    fn set_error(&mut self, message: impl Into<String>) {
+        self.status = message.into();
+        log::warn!("{}", self.status);
+        self.status_is_error = true;
+    }
+
+    /// Opens the binary in a new tab. Each slice of a fat Mach-O gets its
+    /// own tab, unless only the slice with the index `only_slice` is wanted.
+    fn open(&mut self, file_path: &str, only_slice: Option<usize>) -> Result<(), String> {
+        let path = PathBuf::from_str(file_path).map_err(|e| e.to_string())?;
+        let mut opened = open_binary_file(&path, &FORMAT_PARSERS)?;
+        if let Some(only_slice) = only_slice {
+            opened.retain(|b| b.slice.as_ref().is_some_and(|(ix, _)| *ix == only_slice));
+            if opened.is_empty() {
+                return Err(format!("no slice {} in {}", only_slice, file_path));
+            }
+        }
+        self.finish_open(file_path, &path, opened);
+        Ok(())
+    }
+
+    /// Opens the binary like `open`, but parses a large file on a background
+    /// thread. The tab is added once `finish_loading` picks up the result.
+    fn start_open(&mut self, file_path: &str) -> Result<(), String> {
+        if let Some(loading) = &self.loading {
+            return Err(format!("still loading {}", loading.file_path));
+        }
+        let path = PathBuf::from_str(file_path).map_err(|e| e.to_string())?;
+        let large = fs::metadata(&path).is_ok_and(|m| m.len() > BACKGROUND_LOAD_BYTES);
+        if !large {
+            return self.open(file_path, None);
+        }
+        self.loading = Some(Loading::start(file_path, &path, &FORMAT_PARSERS));
+        Ok(())
+    }
+
+    /// Adds the tabs of a file loaded in the background once it is parsed.
+    fn finish_loading(&mut self) {
+        let Some(result) = self.loading.as_ref().and_then(Loading::poll) else {
+            return;
+        };
+        let Some(loading) = self.loading.take() else {
+            return;
+        };
+        match result {
+            Ok(opened) => self.finish_open(&loading.file_path, &loading.path, opened),
+            Err(err) => self.set_error(format!("{}: {}", loading.file_path, err)),
+        }
+    }
+
+    fn cancel_loading(&mut self) {
+        if let Some(loading) = self.loading.take() {
+            self.set_info(format!("canceled loading {}", loading.file_path));
+        }
+    }
+
+    /// Adds the tabs of the opened binaries and remembers the file as
+    /// recently opened.
+    fn finish_open(&mut self, file_path: &str, path: &Path, opened: Vec<OpenedBinary>) {
+        self.add_opened(file_path, path, opened);
+        self.recent.add(path);
+        if let Err(err) = self.recent.save() {
+            self.set_error(err);
+        }
+    }
+
+    /// Opens the bytes piped into stdin, `format` is the file extension for
+    /// formats without a magic number.
+    fn open_stdin(&mut self, format: Option<&str>) -> Result<(), String> {
+        let opened = open_stdin(format, &FORMAT_PARSERS)?;
+        self.add_opened("stdin", Path::new(STDIN_PATH), opened);
+        Ok(())
+    }
+
+    /// Adds a tab for each of the `opened` binaries (slices) of `path` and
+    /// shows the first.
+    fn add_opened(&mut self, file_path: &str, path: &Path, opened: Vec<OpenedBinary>) {
+        let slices = opened.len();
+        let type_str = opened[0].view.type_name();
+        let save = companion_save(path).filter(|_| opened[0].view.is::<GBView>());
+        if slices > 1 {
+            self.set_info(format!(
+                "loaded {} ({}, {} slices)",
+                file_path, type_str, slices
+            ));
+        } else if let Some((save_path, _)) = save {
+            self.set_info(format!(
+                "loaded {} ({}), found save {}, :sav opens it",
+                file_path,
+                type_str,
+                save_path.display()
+            ));
+        } else {
+            self.set_info(format!("loaded {} ({})", file_path, type_str));
+        }
+        if let Some(err) = &opened[0].parse_error {
+            self.set_error(format!("loaded {} as raw hex, {}", file_path, err));
+        }
+        for OpenedBinary {
+            mut view,
+            data,
+            slice,
+            compressed,
+            ..
+        } in opened
+        {
+            view.set_path(path);
+            view.set_hex_group_bytes(self.settings.hex_group_bytes);
+            view.set_hex_byte_colors(self.settings.hex_byte_colors);
+            self.project_state.binaries.push(BinaryState {
+                path: path.to_path_buf(),
+                view,
+                data,
+                patches: Patches::default(),
+                hashes: HashMap::new(),
+                marks: BTreeMap::new(),
+                jumps: JumpList::default(),
+                slice,
+                compressed,
+            });
+        }
+        // show the first of the opened slices
+        self.project_state.active = self.project_state.binaries.len() - slices;
+        self.search = None;
+        self.strings = None;
+    }
+
+    /// Re-reads the active binary from disk, keeping its focus and marks. If
+    /// the file can't be read or parsed anymore the old state is kept.
+    fn reload(&mut self) {
+        let Some(binary) = self.project_state.active() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        let is_stdin = binary.is_stdin();
+        let path = binary.path.clone();
+        let slice = binary.slice.as_ref().map(|(ix, _)| *ix);
+        if is_stdin {
+            self.set_error("can't reload stdin");
+            return;
+        }
+        let opened = match open_binary_file(&path, &FORMAT_PARSERS) {
+            Ok(opened) => opened,
+            Err(err) => {
+                self.set_error(format!("{}: {}", path.display(), err));
+                return;
+            }
+        };
+        let fresh = opened
+            .into_iter()
+            .find(|b| b.slice.as_ref().map(|(ix, _)| *ix) == slice);
+        let Some(OpenedBinary {
+            mut view,
+            data,
+            slice,
+            compressed,
+            parse_error,
+        }) = fresh
+        else {
+            self.set_error(format!("{}: the slice is gone", path.display()));
+            return;
+        };
+
+        let Some(binary) = self.project_state.active_mut() else {
+            return;
+        };
+        view.set_path(&path);
+        view.set_hex_group_bytes(self.settings.hex_group_bytes);
+        view.set_hex_byte_colors(self.settings.hex_byte_colors);
+        view.set_focus_index(binary.view.focus_index());
+        let len = data.len();
+        binary.view = view;
+        binary.data = data;
+        binary.patches = Patches::default();
+        binary.hashes.clear();
+        binary.slice = slice;
+        binary.compressed = compressed;
+        // results computed from the old bytes
+        self.search = None;
+        self.strings = None;
+        self.diff = None;
+        match parse_error {
+            Some(err) => self.set_error(format!("reloaded {} as raw hex, {}", path.display(), err)),
+            None => self.set_info(format!("reloaded {} ({} bytes)", path.display(), len)),
+        }
+    }
+
+    /// Opens the file selected in the recent files popup and closes it.
+    fn open_recent(&mut self) {
+        let Some(picker) = self.recent_picker.take() else {
+            return;
+        };
+        let Some(path) = picker.selected() else {
+            self.set_error("none of the recent files exists anymore");
+            return;
+        };
+        let path = path.to_string_lossy().to_string();
+        if let Err(err) = self.start_open(&path) {
+            self.set_error(format!("{}: {}", path, err));
+        }
+    }
+
+    /// Writes the bytes of the selected region, or of the current search
+    /// match if nothing is selected, to `path`.
+    fn write_region(&mut self, path: &str, overwrite: bool) {
+        let Some(binary) = self.project_state.active() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        if !overwrite && fs::exists(path).unwrap_or(false) {
+            self.set_error(format!("{} exists (use :w! to overwrite)", path));
+            return;
+        }
+
+        let Some(Range { start, end }) = self.current_region() else {
+            self.set_error("no region selected");
+            return;
+        };
+        let bytes = &binary.data[start..end];
+        let written = if is_intel_hex_path(path) {
+            // the records keep the file offsets as addresses
+            to_intel_hex(bytes, start)
+                .and_then(|text| fs::write(path, text).map_err(|err| format!("{}: {}", path, err)))
+        } else {
+            fs::write(path, bytes).map_err(|err| format!("{}: {}", path, err))
+        };
+        match written {
+            Ok(()) => self.set_info(format!(
+                "wrote {} bytes (0x{:X}-0x{:X}) to {}",
+                bytes.len(),
+                start,
+                end,
+                path
+            )),
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    /// Renders the screen into a detached buffer and writes its content area
+    /// to `path`, as HTML for `.html` paths and as ANSI text otherwise.
+    fn export_view(&mut self, path: &str) {
+        let area = self.frame_area;
+        let mut buf = Buffer::empty(area);
+        Widget::render(&mut *self, area, &mut buf);
+        let format = ExportFormat::from_path(path);
+        let text = export_buffer(&buf, self.content_area, format);
+        match fs::write(path, text) {
+            Ok(()) => self.set_info(format!(
+                "exported the view ({}x{}) to {}",
+                self.content_area.width, self.content_area.height, path
+            )),
+            Err(err) => self.set_error(format!("{}: {}", path, err)),
+        }
+    }
+
+    /// The selected region of the focused view, or the current search match
+    /// if nothing is selected. Clamped to the binary data.
+    fn current_region(&self) -> Option<Range<usize>> {
+        let binary = self.project_state.active()?;
+        let region = binary.view.selected_region().or_else(|| {
+            self.search.as_ref().map(|search| {
+                let offset = search.current_offset();
+                offset..(offset + search.pattern_len)
+            })
+        })?;
+        let end = region.end.min(binary.data.len());
+        let start = region.start.min(end);
+        Some(start..end)
+    }
+
+    /// Copies the bytes of the current region to the clipboard, as hex
+    /// string or as ASCII (non-printable bytes as '.').
+    fn copy_region(&mut self, ascii: bool) {
+        let (Some(binary), Some(region)) = (self.project_state.active(), self.current_region())
+        else {
+            self.set_error("no region selected");
+            return;
+        };
+        let bytes = &binary.data[region];
+        let text: String = if ascii {
+            bytes.iter().map(|b| ascii_char(*b)).collect()
+        } else {
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        };
+        let len = bytes.len();
+        let copied = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+        match copied {
+            Ok(()) => self.set_info(format!("copied {} bytes", len)),
+            Err(err) => self.set_error(format!("clipboard: {}", err)),
+        }
+    }
+
+    fn switch_binary(&mut self, dir: isize) {
+        let len = self.project_state.binaries.len();
+        if len == 0 {
+            self.set_error("no binary loaded");
+            return;
+        }
+        let active = self.project_state.active as isize + dir;
+        self.project_state.active = active.rem_euclid(len as isize) as usize;
+        self.search = None;
+        self.strings = None;
+    }
+
+    fn close_binary(&mut self) {
+        let project_state = &mut self.project_state;
+        if project_state.binaries.is_empty() {
+            self.set_error("no binary loaded");
+            return;
+        }
+        project_state.binaries.remove(project_state.active);
+        project_state.active = project_state
+            .active
+            .min(project_state.binaries.len().saturating_sub(1));
+        self.search = None;
+        self.diff = None;
+        self.strings = None;
+    }
+
+    fn find(&mut self, pattern: &str) {
+        let Some(binary) = self.project_state.active() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        let pattern = match parse_hex_bytes(pattern) {
+            Ok(pattern) => pattern,
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+
+        let offsets = find_all(&binary.data, &pattern);
+        if offsets.is_empty() {
+            self.search = None;
+            self.strings = None;
+            self.set_info("0 matches");
+        } else {
+            self.disassembly_search = None;
+            self.search = Some(SearchResult {
+                offsets,
+                current: 0,
+                pattern_len: pattern.len(),
+            });
+            self.show_search_match();
+        }
+    }
+
+    /// Opens the diff view, `args` are the two (1-based) tab numbers to
+    /// compare. Without arguments the active tab is compared to the next one.
+    fn diff(&mut self, tabs: &[String]) {
+        let len = self.project_state.binaries.len();
+        if len < 2 {
+            self.set_error("diff needs two opened binaries");
+            return;
+        }
+        let (left, right) = match tabs {
+            [] => {
+                let active = self.project_state.active;
+                (active, (active + 1) % len)
+            }
+            [a, b] => match (a.parse::<usize>(), b.parse::<usize>()) {
+                (Ok(a), Ok(b)) if (1..=len).contains(&a) && (1..=len).contains(&b) => {
+                    (a - 1, b - 1)
+                }
+                _ => {
+                    self.set_error(format!("invalid tab numbers, expected 1 to {}", len));
+                    return;
+                }
+            },
+            _ => {
+                self.set_error("usage: :diff [<tab> <tab>]");
+                return;
+            }
+        };
+
+        let binaries = &self.project_state.binaries;
+        let ranges = diff_bytes(&binaries[left].data, &binaries[right].data);
+        let message = format!(
+            "{} differences between {} and {}",
+            ranges.len(),
+            binaries[left].name(),
+            binaries[right].name()
+        );
+        let mut diff = DiffState::new(left, right, ranges);
+        diff.set_hex_group_bytes(self.settings.hex_group_bytes);
+        self.diff = Some(diff);
+        self.set_info(message);
+    }
+
+    /// Opens the strings view, `args` is "[minlen] [section/bank]".
+    fn strings(&mut self, args: &[String]) {
+        let mut min_len = 4;
+        let mut filter = None;
+        for arg in args {
+            match arg.parse::<usize>() {
+                Ok(len) => min_len = len.max(1),
+                Err(_) => filter = Some(arg),
+            }
+        }
+
+        let scan = match self.project_state.active() {
+            None => Err("no binary loaded".to_string()),
+            Some(binary) => match filter {
+                None => Ok(0..binary.data.len()),
+                Some(name) => binary
+                    .view
+                    .named_region(name)
+                    .ok_or(format!("no section or bank named '{}'", name)),
+            }
+            .map(|region| {
+                let end = region.end.min(binary.data.len());
+                let start = region.start.min(end);
+                find_strings(&binary.data[start..end], start, min_len)
+            }),
+        };
+
+        match scan {
+            Ok((strings, capped)) => {
+                if capped {
+                    self.set_info(format!("showing the first {} strings only", strings.len()));
+                }
+                let title = format!("Strings in {}", filter.map_or("file", String::as_str));
+                self.strings = Some(StringsState::new(strings, title));
+            }
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    fn show_selected_string(&mut self) {
+        let Some(offset) = self
+            .strings
+            .as_ref()
+            .and_then(|s| s.selected())
+            .map(|s| s.offset)
+        else {
+            return;
+        };
+        self.strings = None;
+        self.push_jump();
+        let shown = self
+            .project_state
+            .active_mut()
+            .is_some_and(|b| b.view.show_offset(offset));
+        if shown {
+            self.set_info(format!("string at 0x{:X}", offset));
+        } else {
+            self.set_error(format!(
+                "string at 0x{:X} (not shown in a hex view)",
+                offset
+            ));
+        }
+    }
+
+    fn selected_jump_target(&self) -> Option<usize> {
+        let binary = self.project_state.active()?;
+        binary.view.selected_jump_target()
+    }
+
+    /// Selects the target of the jump instruction selected in the shown
+    /// disassembly, `[` goes back to the jump.
+    fn follow_jump(&mut self) {
+        let Some(target) = self.selected_jump_target() else {
+            return;
+        };
+        self.push_jump();
+        let shown = self
+            .project_state
+            .active_mut()
+            .is_some_and(|b| b.view.show_offset(target));
+        if shown {
+            self.set_info(format!("jump target at 0x{:X}", target));
+        } else {
+            self.set_error(format!("jump target at 0x{:X} is not in a bank", target));
+        }
+    }
+
+    fn show_search_match(&mut self) {
+        if let Some(search) = &self.search {
+            let offset = search.current_offset();
+            let mut message = format!(
+                "match {}/{} at 0x{:X}",
+                search.current + 1,
+                search.offsets.len(),
+                offset
+            );
+            self.push_jump();
+            let shown = self
+                .project_state
+                .active_mut()
+                .is_some_and(|b| b.view.show_offset(offset));
+            if !shown {
+                message.push_str(" (not shown in a hex view)");
+            }
+            self.set_info(message);
+        }
+    }
+
+    fn show_entropy_selection(&mut self) {
+        let (Some(entropy), Some(binary)) = (&self.entropy, self.project_state.active()) else {
+            return;
+        };
+        let range = entropy.selected_range(binary.data.len());
+        let message = format!(
+            "0x{:X}-0x{:X}: entropy {:.2}",
+            range.start,
+            range.end,
+            shannon_entropy(&binary.data[range.clone()])
+        );
+        self.set_info(message);
+    }
+
+    fn show_minimap_offset(&mut self, offset: usize) {
+        let Some(binary) = self.project_state.active_mut() else {
+            return;
+        };
+        binary.push_jump();
+        if binary.view.show_offset(offset) {
+            self.set_info(format!("0x{:X}", offset));
+        } else {
+            self.set_error(format!("0x{:X} (not shown in a hex view)", offset));
+        }
+    }
+
+    /// Shows a digest of the raw bytes of the active binary. Defaults to
+    /// CRC32 for GB ROMs and SHA-256 for all other binaries.
+    fn hash(&mut self, algorithm: &str) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        let algorithm = match algorithm {
+            "" => binary.view.default_hash(),
+            name => match HashAlgorithm::parse(name) {
+                Ok(algorithm) => algorithm,
+                Err(err) => {
+                    self.set_error(err);
+                    return;
+                }
+            },
+        };
+        let digest = binary
+            .hashes
+            .entry(algorithm)
+            .or_insert_with(|| hash(algorithm, &binary.data))
+            .clone();
+        self.set_info(format!("{} {}", algorithm.name(), digest));
+    }
+
+    /// Selects the next (or previous) line of the shown disassembly that
+    /// contains the pattern of the last `/` search.
+    fn find_in_disassembly(&mut self, backward: bool) {
+        let Some(pattern) = self.disassembly_search.clone() else {
+            return;
+        };
+        let disassembly = self
+            .project_state
+            .active_mut()
+            .and_then(|b| b.view.shown_disassembly());
+        let Some((lines, list_state)) = disassembly else {
+            self.set_error("no disassembly shown");
+            return;
+        };
+        let from = list_state.selected().unwrap_or(0);
+        match find_line(&lines, &pattern, from, backward) {
+            Some(ix) => {
+                list_state.select(Some(ix));
+                let message = format!("/{}: line {} of {}", pattern, ix + 1, lines.len());
+                self.set_info(message);
+            }
+            None => self.set_error(format!("pattern not found: {}", pattern)),
+        }
+    }
+
+    fn mark(&mut self, name: &str) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        match binary.view.shown_offset() {
+            Some(offset) => {
+                binary.marks.insert(name.to_string(), offset);
+                self.set_info(format!("marked 0x{:X} as '{}'", offset, name));
+            }
+            None => self.set_error("no hex view in focus"),
+        }
+    }
+
+    fn jump(&mut self, name: &str) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        let Some(offset) = binary.marks.get(name).copied() else {
+            self.set_error(format!("no mark named '{}'", name));
+            return;
+        };
+        binary.push_jump();
+        if binary.view.show_offset(offset) {
+            self.set_info(format!("'{}' at 0x{:X}", name, offset));
+        } else {
+            self.set_error(format!(
+                "'{}' at 0x{:X} (not shown in a hex view)",
+                name, offset
+            ));
+        }
+    }
+
+    /// Remembers the position of the active binary before a jump.
+    fn push_jump(&mut self) {
+        if let Some(binary) = self.project_state.active_mut() {
+            binary.push_jump();
+        }
+    }
+
+    fn jump_back(&mut self) {
+        let Some(binary) = self.project_state.active_mut() else {
+            return;
+        };
+        let current = binary.location();
+        match binary.jumps.back(current) {
+            Some(location) => {
+                binary.show_location(location);
+                self.set_info(location_display("back to", location));
+            }
+            None => self.set_info("no earlier position"),
+        }
+    }
+
+    fn jump_forward(&mut self) {
+        let Some(binary) = self.project_state.active_mut() else {
+            return;
+        };
+        let current = binary.location();
+        match binary.jumps.forward(current) {
+            Some(location) => {
+                binary.show_location(location);
+                self.set_info(location_display("forward to", location));
+            }
+            None => self.set_info("no later position"),
+        }
+    }
+
+    /// The inspected offset of the active binary and the structure it lies
+    /// in, shown in the status bar.
+    fn offset_location(&self) -> Option<String> {
+        let binary = self.project_state.active()?;
+        let offset = binary.view.inspected_offset()?;
+        let description = binary.view.describe_offset(offset)?;
+        Some(format!("0x{:X} {}", offset, description))
+    }
+
+    /// Shows the structure the file offset lies in.
+    fn describe_offset(&mut self, offset: usize) {
+        let Some(binary) = self.project_state.active() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        if offset >= binary.data.len() {
+            let message = format!("0x{:X} is past the end of the file", offset);
+            self.set_error(message);
+            return;
+        }
+        let message = match binary.view.describe_offset(offset) {
+            Some(description) => format!("0x{:X}: {}", offset, description),
+            None => format!("0x{:X}: in no known structure", offset),
+        };
+        self.set_info(message);
+    }
+
+    fn goto_offset(&mut self, offset: usize) {
+        let Some(binary) = self.project_state.active_mut() else {
+            self.set_error("no binary loaded");
+            return;
+        };
+        // only a shown offset is a jump, a failed show leaves the view as is
+        let location = binary.location();
+        if binary.view.show_offset(offset) {
+            binary.jumps.push(location);
+            self.set_info(format!("0x{:X}", offset));
+        } else {
+            self.set_error(format!("0x{:X} is not shown in a hex view", offset));
+        }
+    }
+}
+
+fn location_display(prefix: &str, location: Location) -> String {
+    match location.offset {
+        Some(offset) => format!("{} 0x{:X}", prefix, offset),
+        None => format!("{} the previous pane", prefix),
     }
 }
 
+/// Parses a decimal or 0x-prefixed hex offset.
+fn parse_offset(str: &str) -> Result<usize, String> {
+    let result = if let Some(hex) = str.strip_prefix("0x") {
+        usize::from_str_radix(hex, 16)
+    } else {
+        str.parse::<usize>()
+    };
+    result.map_err(|e| format!("invalid offset '{}': {}", str, e))
+}
+
 impl Widget for &mut Mule {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let main_layout =
-            Layout::vertical([Constraint::Max(3), Constraint::Min(0), Constraint::Max(3)]);
-        let [header, content, command] = main_layout.areas(area);
+        let main_layout = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Max(3),
+            Constraint::Min(0),
+            Constraint::Max(3),
+            Constraint::Length(1),
+        ]);
+        let [tabs, header, content, command, status] = main_layout.areas(area);
+        self.frame_area = area;
+        self.content_area = content;
+        self.command_area = command;
+
+        let tab_titles = self.project_state.binaries.iter().map(|b| b.name());
+        Tabs::new(tab_titles)
+            .select(self.project_state.active)
+            .highlight_style(self.theme.style_highlight())
+            .render(tabs, buf);
 
         let header_block = Block::bordered()
             .border_type(BorderType::Plain)
             .title("Binary");
 
-        let binary_str = if let Some(binary_state) = self.project_state.binary.as_ref() {
+        let binary_str = if let Some(binary_state) = self.project_state.active() {
             let binary_path = binary_state.path.to_str().unwrap();
-            let binary_name = binary_file_type_str(&binary_state.file);
-            &format!("{} ({})", binary_path, binary_name)
+            &format!("{} ({})", binary_path, binary_state.view.summary())
         } else {
             "<no binary loaded>"
         };
 
         Paragraph::new(binary_str.bold())
+            .style(self.theme.style_header())
             .block(header_block)
             .render(header, buf);
 
-        if let Some(binary_state) = self.project_state.binary.as_ref() {
-            match &binary_state.file {
-                BinaryFile::Macho(macho) => {
-                    if let InteractiveState::Macho(state) =
-                        &mut self.project_state.interactive_state
-                    {
-                        let mut widget = MachoWidget::new(macho, state);
-                        widget.render(content, buf);
-                    } else {
-                        panic!("BinaryFile does not match InteractiveState")
-                    }
-                }
-                BinaryFile::GB(gb_binary) => {
-                    if let InteractiveState::GB(state) = &mut self.project_state.interactive_state {
-                        let mut widget = GBWidget::new(gb_binary, state);
-                        widget.render(content, buf);
-                    } else {
-                        panic!("BinaryFile does not match InteractiveState")
-                    }
-                }
+        if let Some(diff) = &mut self.diff {
+            let binaries = &self.project_state.binaries;
+            let (left, right) = (&binaries[diff.left], &binaries[diff.right]);
+            let (left_name, right_name) = (left.name(), right.name());
+            DiffWidget::new(
+                (&left_name, &left.data),
+                (&right_name, &right.data),
+                &self.theme,
+                diff,
+            )
+            .render(content, buf);
+        } else if let Some(binary_state) = self.project_state.active_mut() {
+            let content = if let Some(entropy) = &mut self.entropy {
+                let [entropy_area, content] =
+                    Layout::vertical([Constraint::Length(7), Constraint::Min(0)]).areas(content);
+                EntropyWidget::new(&binary_state.data, &self.theme, entropy)
+                    .render(entropy_area, buf);
+                content
+            } else {
+                content
+            };
+            let content = if let Some(minimap) = &mut self.minimap {
+                let [content, minimap_area] =
+                    Layout::horizontal([Constraint::Min(0), Constraint::Length(MINIMAP_WIDTH)])
+                        .areas(content);
+                let marker = binary_state.view.shown_offset();
+                MinimapWidget::new(&binary_state.data, marker, &self.theme, minimap)
+                    .render(minimap_area, buf);
+                content
+            } else {
+                content
+            };
+            let content = if self.show_inspector {
+                let [content, inspector_area] =
+                    Layout::horizontal([Constraint::Min(0), Constraint::Length(INSPECTOR_WIDTH)])
+                        .areas(content);
+                let offset = binary_state.view.inspected_offset();
+                InspectorWidget::new(
+                    &binary_state.data,
+                    offset,
+                    binary_state.view.byte_order(),
+                    &self.theme,
+                )
+                .render(inspector_area, buf);
+                content
+            } else {
+                content
+            };
+            match &mut self.strings {
+                Some(strings) => StringsWidget::new(&self.theme, strings).render(content, buf),
+                None => binary_state.view.render(
+                    content,
+                    buf,
+                    &binary_state.data,
+                    &binary_state.patches,
+                    &self.theme,
+                ),
             }
         } else {
             let placeholder_block = Block::bordered().border_type(BorderType::Plain);
@@ -267,20 +2076,389 @@ impl Widget for &mut Mule {
             .render(content, buf)
         }
 
+        if let Some(palette) = &mut self.palette {
+            render_palette(content, buf, &self.theme, palette);
+        } else if self.show_help {
+            render_help(content, buf, &self.theme, &self.key_map);
+        } else if self.show_marks {
+            let marks = self.project_state.active().map(|b| &b.marks);
+            render_marks(content, buf, &self.theme, marks);
+        } else if let Some(picker) = &mut self.recent_picker {
+            render_recent(content, buf, &self.theme, picker);
+        }
+
         let command_block = Block::bordered().border_type(BorderType::Plain);
+        let scroll = self.command_scroll(command_block.inner(command).width);
         Paragraph::new(self.input.as_str())
+            .scroll((0, scroll as u16))
             .style(match self.input_mode {
-                InputMode::Interactive => Style::default(),
-                InputMode::Command => style_focus(),
+                InputMode::Interactive | InputMode::Edit => self.theme.style_normal(),
+                InputMode::Command => self.theme.style_focus(),
             })
             .block(command_block)
             .render(command, buf);
+
+        // what the inspected offset lies in, right of the status message
+        let location = self.offset_location().unwrap_or_default();
+        let [status, location_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(location.chars().count() as u16),
+        ])
+        .areas(status);
+        Paragraph::new(location.as_str())
+            .fg(Color::DarkGray)
+            .render(location_area, buf);
+        match &self.loading {
+            Some(loading) => Paragraph::new(loading.status()).fg(Color::Gray),
+            None => Paragraph::new(self.status.as_str()).fg(if self.status_is_error {
+                Color::LightRed
+            } else {
+                Color::Gray
+            }),
+        }
+        .render(status, buf);
     }
 }
 
-fn binary_file_type_str(binary: &BinaryFile) -> String {
-    match binary {
-        BinaryFile::Macho(_) => "Mach-O".to_string(),
-        BinaryFile::GB(_) => "GameBoy ROM".to_string(),
+/// The keys of a help entry. Keys of the command line are fixed, the keys
+/// of actions are looked up in the key map.
+enum HelpKeys {
+    Fixed(&'static str),
+    Actions(&'static [Action]),
+}
+
+/// The keys of the command and the interactive mode, shown in the help after
+/// the commands.
+static HELP_ENTRIES: [(HelpKeys, &str); 23] = [
+    (
+        HelpKeys::Fixed("Enter"),
+        "execute the command, switch to interactive mode",
+    ),
+    (
+        HelpKeys::Fixed("Up/Down (command)"),
+        "browse the command history",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Close]),
+        "switch to command mode, close a popup or view",
+    ),
+    (
+        HelpKeys::Actions(&[Action::FocusNext, Action::FocusPrev]),
+        "cycle the focus between the panes",
+    ),
+    (
+        HelpKeys::Actions(&[Action::SelectPrev, Action::SelectNext]),
+        "move the selection or scroll the focused pane",
+    ),
+    (
+        HelpKeys::Actions(&[Action::PageUp, Action::PageDown]),
+        "scroll the focused pane by a page",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Home, Action::End]),
+        "scroll the focused pane to its start or end",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Left, Action::Right]),
+        "select an entropy column, scroll a 16 bytes per line hex view",
+    ),
+    (
+        HelpKeys::Actions(&[Action::ToggleHexLayout]),
+        "toggle between 16 bytes per line and lines fitting the width",
+    ),
+    (
+        HelpKeys::Actions(&[Action::CycleHexGroup]),
+        "group the hex digits by 1, 2, 4, 8 or 16 bytes",
+    ),
+    (
+        HelpKeys::Actions(&[Action::ToggleByteColors]),
+        "toggle coloring the hex bytes by zero, ASCII and high bytes",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Search]),
+        "search the shown disassembly for a text (/<pattern>)",
+    ),
+    (
+        HelpKeys::Actions(&[Action::NextMatch, Action::PrevMatch]),
+        "jump to the next/previous search match or disassembly line",
+    ),
+    (
+        HelpKeys::Actions(&[Action::ToggleDisassemble]),
+        "toggle the disassembly of a Mach-O __text section or GB bank",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Confirm]),
+        "toggle the hex view of a Mach-O section, follow a GB jp/call/jr",
+    ),
+    (
+        HelpKeys::Actions(&[Action::CopyHex, Action::CopyAscii]),
+        "copy the selected region as hex / ASCII",
+    ),
+    (
+        HelpKeys::Actions(&[Action::Edit]),
+        "edit the bytes of the focused hex view, Esc stops editing",
+    ),
+    (HelpKeys::Actions(&[Action::Undo]), "undo the last edit"),
+    (
+        HelpKeys::Actions(&[Action::JumpBack, Action::JumpForward]),
+        "jump back / forward, in a diff to the previous / next difference",
+    ),
+    (
+        HelpKeys::Actions(&[Action::NarrowFilePane, Action::WidenFilePane]),
+        "narrow / widen the file pane",
+    ),
+    (
+        HelpKeys::Actions(&[Action::CommandPalette]),
+        "open the command palette, type to filter, Enter runs",
+    ),
+    (HelpKeys::Actions(&[Action::ToggleHelp]), "toggle this help"),
+    (HelpKeys::Actions(&[Action::Quit]), "quit"),
+];
+
+/// A centered area of at most `width` x `height` inside `area`.
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [popup] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(popup);
+    popup
+}
+
+/// The keys of the help entry in the key map, the keys of each action are
+/// separated by `,`, the actions by `/`. None if no action has a key.
+fn help_keys(key_map: &KeyMap, keys: &HelpKeys) -> Option<String> {
+    let actions = match keys {
+        HelpKeys::Fixed(keys) => return Some(keys.to_string()),
+        HelpKeys::Actions(actions) => actions,
+    };
+    let names: Vec<String> = actions
+        .iter()
+        .map(|action| {
+            let keys: Vec<String> = key_map.keys(*action).into_iter().map(key_name).collect();
+            keys.join(", ")
+        })
+        .collect();
+    if names.iter().all(String::is_empty) {
+        return None;
+    }
+    let names: Vec<&str> = names
+        .iter()
+        .map(|n| if n.is_empty() { "-" } else { n.as_str() })
+        .collect();
+    Some(names.join(" / "))
+}
+
+/// The commands and the keys of the key map with their description, the
+/// entries of unbound actions are left out. Ends with the keys closing the
+/// help.
+fn help_rows(key_map: &KeyMap) -> Vec<(String, String)> {
+    let commands = COMMANDS
+        .iter()
+        .map(|c| (c.usage(), c.description.to_string()));
+    let keys = HELP_ENTRIES.iter().filter_map(|(keys, description)| {
+        help_keys(key_map, keys).map(|keys| (keys, description.to_string()))
+    });
+    let close: Vec<String> = [Action::Close, Action::ToggleHelp]
+        .into_iter()
+        .flat_map(|action| key_map.keys(action))
+        .map(key_name)
+        .collect();
+    let footer = [
+        (String::new(), String::new()),
+        (
+            String::new(),
+            format!("press {} to close", close.join(" or ")),
+        ),
+    ];
+    commands
+        .chain([(String::new(), String::new())])
+        .chain(keys)
+        .chain(footer)
+        .collect()
+}
+
+fn render_help(area: Rect, buf: &mut Buffer, theme: &Theme, key_map: &KeyMap) {
+    let rows = help_rows(key_map);
+    let popup = popup_area(area, 80, rows.len() as u16 + 2);
+    let key_width = rows.iter().map(|(keys, _)| keys.width()).max().unwrap_or(0);
+    let widths = [
+        Constraint::Length(key_width.max(20) as u16),
+        Constraint::Fill(1),
+    ];
+    let rows = rows
+        .into_iter()
+        .map(|(keys, description)| Row::new(vec![keys, description]));
+    let block = Block::bordered()
+        .border_type(BorderType::Plain)
+        .style(theme.style_focus())
+        .title("Help");
+    Clear.render(popup, buf);
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, popup, buf);
+}
+
+fn render_marks(
+    area: Rect,
+    buf: &mut Buffer,
+    theme: &Theme,
+    marks: Option<&BTreeMap<String, usize>>,
+) {
+    let rows: Vec<Row> = marks
+        .into_iter()
+        .flatten()
+        .map(|(name, offset)| Row::new(vec![name.clone(), format!("0x{:X}", offset)]))
+        .collect();
+    let popup = popup_area(area, 50, rows.len().max(1) as u16 + 2);
+    let widths = [Constraint::Fill(1), Constraint::Length(18)];
+    let block = Block::bordered()
+        .border_type(BorderType::Plain)
+        .style(theme.style_focus())
+        .title("Marks")
+        .title_bottom("Esc: close, :jump <name>");
+    Clear.render(popup, buf);
+    if rows.is_empty() {
+        Paragraph::new("no marks, add one with :mark <name>")
+            .block(block)
+            .render(popup, buf);
+    } else {
+        let table = Table::new(rows, widths).block(block);
+        Widget::render(table, popup, buf);
+    }
+}
+
+fn render_recent(area: Rect, buf: &mut Buffer, theme: &Theme, picker: &mut RecentPicker) {
+    let items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .map(|(path, exists)| {
+            let item = ListItem::new(path.display().to_string());
+            if *exists {
+                item
+            } else {
+                item.style(Style::default().fg(Color::DarkGray))
+            }
+        })
+        .collect();
+    let popup = popup_area(area, 80, items.len().max(1) as u16 + 2);
+    let block = Block::bordered()
+        .border_type(BorderType::Plain)
+        .style(theme.style_focus())
+        .title("Recent")
+        .title_bottom("Enter: open, Esc: close");
+    Clear.render(popup, buf);
+    if items.is_empty() {
+        Paragraph::new("no recently opened files")
+            .block(block)
+            .render(popup, buf);
+    } else {
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme.style_highlight());
+        StatefulWidget::render(list, popup, buf, &mut picker.list_state);
+    }
+}
+
+fn render_palette(area: Rect, buf: &mut Buffer, theme: &Theme, palette: &mut Palette) {
+    let items: Vec<ListItem> = palette
+        .matches
+        .iter()
+        .map(|c| ListItem::new(format!("{:<22}{}", c.usage(), c.description)))
+        .collect();
+    // one row for the query
+    let popup = popup_area(area, 80, COMMANDS.len() as u16 + 3);
+    let block = Block::bordered()
+        .border_type(BorderType::Plain)
+        .style(theme.style_focus())
+        .title("Commands")
+        .title_bottom("type to filter, Enter: run, Esc: close");
+    Clear.render(popup, buf);
+    let inner = block.inner(popup);
+    block.render(popup, buf);
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+    Paragraph::new(format!("> {}", palette.query)).render(query_area, buf);
+    if items.is_empty() {
+        Paragraph::new("no matching command").render(list_area, buf);
+    } else {
+        let list = List::new(items).highlight_style(theme.style_highlight());
+        StatefulWidget::render(list, list_area, buf, &mut palette.list_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mule_with_input(input: &str) -> Mule {
+        let mut mule = Mule::new();
+        mule.set_input(input.to_string());
+        mule
+    }
+
+    #[test]
+    fn cursor_column_of_ascii() {
+        let mut mule = mule_with_input(":o a.gb");
+        assert_eq!(mule.cursor_column(), 7);
+        mule.move_cursor_left();
+        assert_eq!(mule.cursor_column(), 6);
+    }
+
+    #[test]
+    fn cursor_column_of_wide_characters() {
+        // CJK characters and the emoji take two cells each
+        let mut mule = mule_with_input(":o 日本.gb");
+        assert_eq!(mule.cursor_column(), 10);
+        mule.move_cursor_left();
+        mule.move_cursor_left();
+        mule.move_cursor_left();
+        assert_eq!(mule.cursor_column(), 7);
+        mule.move_cursor_left();
+        assert_eq!(mule.cursor_column(), 5);
+
+        let mut mule = mule_with_input(":o 🦀x");
+        assert_eq!(mule.cursor_column(), 6);
+        mule.move_cursor_left();
+        assert_eq!(mule.cursor_column(), 5);
+        mule.move_cursor_left();
+        assert_eq!(mule.cursor_column(), 3);
+    }
+
+    #[test]
+    fn cursor_column_after_typing() {
+        let mut mule = mule_with_input(":o ");
+        for c in "é日x".chars() {
+            mule.enter_char(c);
+        }
+        assert_eq!(mule.input, ":o é日x");
+        assert_eq!(mule.cursor_column(), 7);
+    }
+
+    #[test]
+    fn command_scroll_keeps_the_cursor_visible() {
+        let mule = mule_with_input(":o 日本語");
+        assert_eq!(mule.cursor_column(), 9);
+        assert_eq!(mule.command_scroll(20), 0);
+        assert_eq!(mule.command_scroll(10), 0);
+        assert_eq!(mule.command_scroll(5), 5);
+    }
+
+    #[test]
+    fn help_shows_the_keys_of_the_key_map() {
+        let rows = help_rows(&KeyMap::default());
+        assert!(rows.contains(&(
+            "Up, k / Down, j".to_string(),
+            "move the selection or scroll the focused pane".to_string()
+        )));
+        assert!(!rows.iter().any(|(_, description)| description == "quit"));
+        assert_eq!(rows.last().unwrap().1, "press Esc or ? to close");
+
+        let key_map =
+            KeyMap::parse("select_next = [\"s\"]\nclose = [\"x\"]\nquit = [\"q\"]").unwrap();
+        let rows = help_rows(&key_map);
+        assert!(rows.iter().any(|(keys, _)| keys == "Up, k / s"));
+        assert!(rows.contains(&("q".to_string(), "quit".to_string())));
+        assert_eq!(rows.last().unwrap().1, "press x or ? to close");
     }
 }
@@ -1,34 +1,548 @@
 use std::ffi::OsStr;
 use std::fs;
-use std::io::Read;
-use std::{fs::File, path::Path};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
-use mule_gb::GBBinary;
-use mule_macho::Macho;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use mule_macho::{CPUType, FatArch};
 
-pub enum BinaryFile {
-    Macho(Macho),
-    GB(GBBinary),
+use crate::{
+    binary_view::BinaryView, loading::CancelFlag, view_elf::ElfView, view_gb::GBView,
+    view_macho::MachoView, view_pe::PeView, view_raw::RawView, view_wasm::WasmView,
+};
+
+/// Short name of the architecture, as used by `lipo` and `otool`.
+pub fn cpu_type_str(cpu_type: CPUType) -> &'static str {
+    match cpu_type {
+        CPUType::ARM64 => "arm64",
+        CPUType::X86_64 => "x86_64",
+        CPUType::PowerPC64 => "ppc64",
+    }
+}
+
+/// A parsed binary with its raw bytes.
+pub struct OpenedBinary {
+    pub view: Box<dyn BinaryView>,
+    pub data: Vec<u8>,
+    /// Index and architecture of the slice if opened from a fat Mach-O,
+    /// `data` are then the bytes of the slice only.
+    pub slice: Option<(usize, String)>,
+    /// Whether the file is gzip or zlib compressed, `data` are then the
+    /// inflated bytes.
+    pub compressed: bool,
+    /// Why the detected format failed to parse, the binary is then shown as
+    /// raw hex.
+    pub parse_error: Option<String>,
+}
+
+/// The path that stands for the standard input.
+pub const STDIN_PATH: &str = "-";
+
+/// Compressed input that inflates to more than this is refused.
+pub const MAX_INFLATED_BYTES: u64 = 1024 * 1024 * 1024;
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Files and inflated data are read in chunks of this size, the cancel flag
+/// is checked after each chunk.
+const READ_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Opens and parses the binary with the first of `parsers` that detects its
+/// format. A fat Mach-O results in one binary per supported architecture
+/// slice, all other files in exactly one binary.
+pub fn open_binary_file(
+    path: &Path,
+    parsers: &[&dyn FormatParser],
+) -> Result<Vec<OpenedBinary>, String> {
+    open_binary_file_cancelable(path, parsers, &CancelFlag::default())
+}
+
+/// Opens the binary like `open_binary_file`, fails with "canceled" once
+/// `cancel` is set.
+pub fn open_binary_file_cancelable(
+    path: &Path,
+    parsers: &[&dyn FormatParser],
+    cancel: &CancelFlag,
+) -> Result<Vec<OpenedBinary>, String> {
+    log::info!("opening {}", path.display());
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let data = read_chunks(file, cancel).map_err(|e| e.to_string())?;
+    let mut extension = path.extension().and_then(OsStr::to_str);
+    if extension.is_some_and(|e| e.eq_ignore_ascii_case("gz")) {
+        // the format of rom.gb.gz is the extension before the .gz
+        extension = path
+            .file_stem()
+            .map(Path::new)
+            .and_then(Path::extension)
+            .and_then(OsStr::to_str);
+    }
+    open_binary_data(data, extension, parsers, cancel)
+}
+
+/// The battery save next to a GB ROM, `game.sav` for `game.gb`, with its
+/// size in bytes if it exists.
+pub fn companion_save(path: &Path) -> Option<(PathBuf, u64)> {
+    let save_path = path.with_extension("sav");
+    if save_path == path {
+        return None;
+    }
+    let metadata = fs::metadata(&save_path).ok().filter(|m| m.is_file())?;
+    Some((save_path, metadata.len()))
+}
+
+/// Reads the standard input to its end and parses it like a file. `format`
+/// is the file extension (e.g. `gb`) for formats without a magic number.
+pub fn open_stdin(
+    format: Option<&str>,
+    parsers: &[&dyn FormatParser],
+) -> Result<Vec<OpenedBinary>, String> {
+    log::info!("reading stdin");
+    let mut data = Vec::new();
+    io::stdin()
+        .read_to_end(&mut data)
+        .map_err(|e| format!("failed to read stdin: {}", e))?;
+    if data.is_empty() {
+        return Err("stdin is empty".to_string());
+    }
+    open_binary_data(data, format, parsers, &CancelFlag::default())
+}
+
+/// Detects the format of `data` by its magic number, or by `extension` for
+/// formats without one. Gzip and zlib compressed data is inflated first.
+fn open_binary_data(
+    data: Vec<u8>,
+    extension: Option<&str>,
+    parsers: &[&dyn FormatParser],
+    cancel: &CancelFlag,
+) -> Result<Vec<OpenedBinary>, String> {
+    let (data, compressed) = match inflate(&data, cancel)? {
+        Some(inflated) => (inflated, true),
+        None => (data, false),
+    };
+    let mut opened = open_uncompressed_data(data, extension, parsers, cancel)?;
+    for binary in &mut opened {
+        binary.compressed = compressed;
+    }
+    Ok(opened)
+}
+
+/// The inflated bytes if `data` is gzip or zlib compressed, None if it is
+/// not compressed.
+fn inflate(data: &[u8], cancel: &CancelFlag) -> Result<Option<Vec<u8>>, String> {
+    if data.starts_with(&GZIP_MAGIC) {
+        log::debug!("gzip compressed, inflating");
+        return read_capped(GzDecoder::new(data), cancel)
+            .map(Some)
+            .map_err(|e| format!("gzip: {}", e));
+    }
+    if is_zlib_header(data) {
+        // the two byte header is a weak magic number, data that fails to
+        // inflate is most likely not compressed at all
+        return match read_capped(ZlibDecoder::new(data), cancel) {
+            Ok(inflated) => {
+                log::debug!("zlib compressed, inflated");
+                Ok(Some(inflated))
+            }
+            Err(err) if err.kind() == io::ErrorKind::FileTooLarge => Err(format!("zlib: {}", err)),
+            Err(err) => {
+                log::debug!("no zlib data ({}), trying the other formats", err);
+                Ok(None)
+            }
+        };
+    }
+    Ok(None)
+}
+
+// deflate with a 32K window, no preset dictionary and a valid header checksum
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => {
+            *cmf == 0x78 && flg & 0x20 == 0 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0
+        }
+        _ => false,
+    }
 }
 
-pub fn open_binary_file(path: &Path) -> Result<BinaryFile, String> {
-    let mut file = File::open(path).map_err(|e| e.to_string())?;
+/// Reads the decoder to its end, fails once more than `MAX_INFLATED_BYTES`
+/// come out of it to not run out of memory on a decompression bomb.
+fn read_capped(decoder: impl Read, cancel: &CancelFlag) -> io::Result<Vec<u8>> {
+    let inflated = read_chunks(decoder.take(MAX_INFLATED_BYTES + 1), cancel)?;
+    if inflated.len() as u64 > MAX_INFLATED_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::FileTooLarge,
+            format!(
+                "inflates to more than {} MiB, refusing to open it",
+                MAX_INFLATED_BYTES / (1024 * 1024)
+            ),
+        ));
+    }
+    Ok(inflated)
+}
+
+/// Reads to the end like `read_to_end`, fails once `cancel` is set.
+fn read_chunks(mut reader: impl Read, cancel: &CancelFlag) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK_BYTES];
+    loop {
+        if cancel.is_canceled() {
+            return Err(io::Error::other("canceled"));
+        }
+        match reader.read(&mut chunk) {
+            Ok(0) => return Ok(data),
+            Ok(len) => data.extend_from_slice(&chunk[..len]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn open_uncompressed_data(
+    data: Vec<u8>,
+    extension: Option<&str>,
+    parsers: &[&dyn FormatParser],
+    cancel: &CancelFlag,
+) -> Result<Vec<OpenedBinary>, String> {
+    cancel.check()?;
+    let magic_be = u32::from_be_bytes(magic(&data));
+    if magic_be == mule_macho::FAT_MAGIC || magic_be == mule_macho::FAT_MAGIC_64 {
+        match mule_macho::load_fat(&data) {
+            Ok(archs) => {
+                log::debug!("fat mach-o with {} architectures", archs.len());
+                return open_fat_slices(&data, &archs, cancel);
+            }
+            Err(err) => log::debug!("no fat mach-o ({}), trying the other formats", err),
+        }
+    }
+
+    let (view, parse_error) = open_single_binary(&data, extension, parsers);
+    Ok(vec![OpenedBinary {
+        view,
+        data,
+        slice: None,
+        compressed: false,
+        parse_error,
+    }])
+}
+
+fn open_fat_slices(
+    data: &[u8],
+    archs: &[FatArch],
+    cancel: &CancelFlag,
+) -> Result<Vec<OpenedBinary>, String> {
+    let mut result = Vec::new();
+    for (ix, arch) in archs.iter().enumerate() {
+        cancel.check()?;
+        if !arch.is_supported() {
+            log::debug!(
+                "skipping unsupported slice {} (cpu 0x{:X})",
+                ix,
+                arch.cpu_type
+            );
+            continue;
+        }
+        let start = arch.offset as usize;
+        let end = start.saturating_add(arch.size as usize);
+        let slice_data = data
+            .get(start..end)
+            .ok_or_else(|| format!("slice {} exceeds the file size", ix))?
+            .to_vec();
+        let macho = mule_macho::load(&slice_data).map_err(|e| format!("slice {}: {}", ix, e))?;
+        let arch_name = cpu_type_str(macho.header.cpu_type).to_string();
+        result.push(OpenedBinary {
+            view: Box::new(MachoView::new(macho)),
+            data: slice_data,
+            slice: Some((ix, arch_name)),
+            compressed: false,
+            parse_error: None,
+        });
+    }
+    if result.is_empty() {
+        return Err("no supported architecture in the fat binary".to_string());
+    }
+    Ok(result)
+}
+
+/// A binary format that can be detected and parsed. The open functions try
+/// the parsers they are passed in order, a format that is not built in is
+/// opened by passing its parser in front of `FORMAT_PARSERS`.
+pub trait FormatParser: Sync {
+    /// Name of the format in the log, e.g. "mach-o"
+    fn name(&self) -> &'static str;
+
+    /// True if `data` is in the format, by its magic number or `extension`
+    /// for formats without one.
+    fn detect(&self, data: &[u8], extension: Option<&str>) -> bool;
+
+    /// Parses `data` into the view that shows it.
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String>;
+}
+
+struct MachoParser;
+struct ElfParser;
+struct PeParser;
+struct WasmParser;
+struct GBParser;
+
+/// The built-in formats, the ones detected by their extension last.
+pub static FORMAT_PARSERS: [&dyn FormatParser; 5] =
+    [&MachoParser, &ElfParser, &PeParser, &WasmParser, &GBParser];
+
+/// The first four bytes of the data, zeros if it is shorter.
+fn magic(data: &[u8]) -> [u8; 4] {
     let mut magic = [0; 4];
-    file.read(&mut magic).map_err(|e| e.to_string())?;
-    let magic_u32 = u32::from_le_bytes(magic);
-    if magic_u32 == mule_macho::MAGIC_HEADER {
-        let data = fs::read(path).map_err(|e| e.to_string())?;
-        let macho_file = mule_macho::load(&data)?;
-        return Ok(BinaryFile::Macho(macho_file));
+    if data.len() >= magic.len() {
+        magic.copy_from_slice(&data[0..4]);
+    }
+    magic
+}
+
+impl FormatParser for MachoParser {
+    fn name(&self) -> &'static str {
+        "mach-o"
+    }
+
+    fn detect(&self, data: &[u8], _: Option<&str>) -> bool {
+        let magic = u32::from_le_bytes(magic(data));
+        magic == mule_macho::MAGIC_HEADER || magic == mule_macho::CIGAM_HEADER
     }
 
-    let extension = path.extension().and_then(OsStr::to_str);
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+        Ok(Box::new(MachoView::new(mule_macho::load(data)?)))
+    }
+}
+
+impl FormatParser for ElfParser {
+    fn name(&self) -> &'static str {
+        "elf"
+    }
 
-    if extension == Some("gb") || extension == Some("gbc") {
-        let data = fs::read(path).map_err(|e| e.to_string())?;
-        let gb_file = mule_gb::load(&data)?;
-        return Ok(BinaryFile::GB(gb_file));
+    fn detect(&self, data: &[u8], _: Option<&str>) -> bool {
+        u32::from_le_bytes(magic(data)) == mule_elf::MAGIC_HEADER
     }
 
-    Err("file not supported".to_string())
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+        Ok(Box::new(ElfView::new(mule_elf::load(data)?)))
+    }
+}
+
+impl FormatParser for PeParser {
+    fn name(&self) -> &'static str {
+        "pe"
+    }
+
+    // `MZ` alone is any DOS executable or a text starting with it, only a
+    // `PE\0\0` signature at `e_lfanew` makes it a PE file
+    fn detect(&self, data: &[u8], _: Option<&str>) -> bool {
+        let magic = magic(data);
+        if u16::from_le_bytes([magic[0], magic[1]]) != mule_pe::DOS_MAGIC {
+            return false;
+        }
+        let Some(e_lfanew) = data.get(mule_pe::E_LFANEW_OFFSET..mule_pe::E_LFANEW_OFFSET + 4)
+        else {
+            return false;
+        };
+        let pe_offset = u32::from_le_bytes(e_lfanew.try_into().unwrap()) as usize;
+        data.get(pe_offset..pe_offset.saturating_add(4))
+            == Some(&mule_pe::PE_SIGNATURE.to_le_bytes()[..])
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+        Ok(Box::new(PeView::new(mule_pe::load(data)?)))
+    }
+}
+
+impl FormatParser for WasmParser {
+    fn name(&self) -> &'static str {
+        "wasm"
+    }
+
+    fn detect(&self, data: &[u8], _: Option<&str>) -> bool {
+        u32::from_le_bytes(magic(data)) == mule_wasm::MAGIC_HEADER
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+        Ok(Box::new(WasmView::new(mule_wasm::load(data)?)))
+    }
+}
+
+impl FormatParser for GBParser {
+    fn name(&self) -> &'static str {
+        "gameboy rom"
+    }
+
+    // a ROM has no magic number at its start, only by the extension
+    fn detect(&self, _: &[u8], extension: Option<&str>) -> bool {
+        extension == Some("gb") || extension == Some("gbc")
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+        Ok(Box::new(GBView::new(mule_gb::load(data)?, data)))
+    }
+}
+
+/// Parses `data` with the first detected format that accepts it. Unknown or
+/// broken content is shown as raw hex, together with the error of the first
+/// detected format that failed to parse it.
+fn open_single_binary(
+    data: &[u8],
+    extension: Option<&str>,
+    parsers: &[&dyn FormatParser],
+) -> (Box<dyn BinaryView>, Option<String>) {
+    let mut parse_error = None;
+    for parser in parsers {
+        if !parser.detect(data, extension) {
+            continue;
+        }
+        log::debug!("detected {}", parser.name());
+        match parser.parse(data) {
+            Ok(view) => return (view, None),
+            Err(err) => {
+                log::debug!(
+                    "{} failed to parse ({}), trying the other formats",
+                    parser.name(),
+                    err
+                );
+                parse_error.get_or_insert_with(|| format!("{}: {}", parser.name(), err));
+            }
+        }
+    }
+
+    log::debug!("unknown format, loading as raw data");
+    (Box::new(RawView::new()), parse_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use mule_macho::test_util::macho_bytes;
+
+    use super::*;
+    use crate::binary_view::ByteOrder;
+
+    #[test]
+    fn read_chunks_reads_past_a_chunk() {
+        let data = vec![7; READ_CHUNK_BYTES + 3];
+        let read = read_chunks(Cursor::new(&data), &CancelFlag::default()).unwrap();
+        assert_eq!(read, data);
+    }
+
+    #[test]
+    fn canceled_read() {
+        let cancel = CancelFlag::default();
+        cancel.cancel();
+        let err = read_chunks(Cursor::new([1, 2, 3]), &cancel).unwrap_err();
+        assert_eq!(err.to_string(), "canceled");
+    }
+
+    #[test]
+    fn canceled_open() {
+        let path = std::env::temp_dir().join(format!("mule-canceled-{}.bin", std::process::id()));
+        fs::write(&path, [0; 64]).unwrap();
+        let cancel = CancelFlag::default();
+        cancel.cancel();
+        let result = open_binary_file_cancelable(&path, &FORMAT_PARSERS, &cancel);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result.err(), Some("canceled".to_string()));
+    }
+
+    #[test]
+    fn canceled_after_reading() {
+        let cancel = CancelFlag::default();
+        cancel.cancel();
+        let result = open_binary_data(vec![0; 64], None, &FORMAT_PARSERS, &cancel);
+        assert_eq!(result.err(), Some("canceled".to_string()));
+    }
+
+    #[test]
+    fn parsed_into_the_view_of_the_format() {
+        let opened = open_binary_data(
+            macho_bytes(&[]),
+            None,
+            &FORMAT_PARSERS,
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        let view = &opened[0].view;
+        assert!(view.is::<MachoView>());
+        assert_eq!(view.summary(), "Mach-O, arm64, executable");
+        assert_eq!(view.byte_order(), Some(ByteOrder::Little));
+    }
+
+    #[test]
+    fn unknown_format_is_shown_raw() {
+        let opened =
+            open_binary_data(vec![0; 64], None, &FORMAT_PARSERS, &CancelFlag::default()).unwrap();
+        let view = &opened[0].view;
+        assert!(view.is::<RawView>());
+        assert_eq!(view.type_name(), "raw");
+        assert_eq!(view.byte_order(), None);
+    }
+
+    #[test]
+    fn broken_format_is_shown_raw() {
+        let mut data = vec![0; 64];
+        data[0..4].copy_from_slice(&mule_elf::MAGIC_HEADER.to_le_bytes());
+        let opened = open_binary_data(data, None, &FORMAT_PARSERS, &CancelFlag::default()).unwrap();
+        assert!(opened[0].view.is::<RawView>());
+        assert!(opened[0].parse_error.as_ref().unwrap().starts_with("elf: "));
+
+        let opened = open_binary_data(
+            vec![0; 64],
+            Some("gb"),
+            &FORMAT_PARSERS,
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        assert!(opened[0].view.is::<RawView>());
+        assert!(opened[0].parse_error.is_some());
+    }
+
+    struct BrokenParser;
+
+    impl FormatParser for BrokenParser {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn detect(&self, _: &[u8], extension: Option<&str>) -> bool {
+            extension == Some("broken")
+        }
+
+        fn parse(&self, _: &[u8]) -> Result<Box<dyn BinaryView>, String> {
+            Err("always fails".to_string())
+        }
+    }
+
+    #[test]
+    fn passed_parser_before_the_built_in_ones() {
+        let parsers = [&BrokenParser as &dyn FormatParser, &ElfParser];
+        let data = mule_elf::MAGIC_HEADER.to_le_bytes().to_vec();
+        let opened =
+            open_binary_data(data, Some("broken"), &parsers, &CancelFlag::default()).unwrap();
+        assert_eq!(
+            opened[0].parse_error.as_deref(),
+            Some("broken: always fails")
+        );
+    }
+
+    #[test]
+    fn pe_signature_at_e_lfanew() {
+        let mut data = vec![0; 0x84];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        data[0x80..0x84].copy_from_slice(b"PE\0\0");
+        assert!(PeParser.detect(&data, None));
+
+        data[0x3C..0x40].copy_from_slice(&0x1000u32.to_le_bytes());
+        assert!(!PeParser.detect(&data, None));
+    }
+
+    #[test]
+    fn text_starting_with_mz_is_shown_raw() {
+        let data = b"MZ is not a PE file, just text that starts like a DOS header".to_vec();
+        let opened =
+            open_binary_data(data, Some("txt"), &FORMAT_PARSERS, &CancelFlag::default()).unwrap();
+        assert!(opened[0].view.is::<RawView>());
+    }
 }
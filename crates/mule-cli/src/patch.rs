@@ -0,0 +1,80 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// The bytes edited in memory but not yet written to the file. Every edit
+/// can be undone, the last one first.
+#[derive(Default)]
+pub struct Patches {
+    // offset and previous value of each edit, the last edit at the end
+    undo: Vec<(usize, u8)>,
+}
+
+impl Patches {
+    /// Sets the byte at `offset` to `value`. Returns false if the offset is
+    /// outside of `data`.
+    pub fn set(&mut self, data: &mut [u8], offset: usize, value: u8) -> bool {
+        let Some(byte) = data.get_mut(offset) else {
+            return false;
+        };
+        self.undo.push((offset, *byte));
+        *byte = value;
+        true
+    }
+
+    /// Restores the byte of the last edit, returns its offset.
+    pub fn undo(&mut self, data: &mut [u8]) -> Option<usize> {
+        let (offset, value) = self.undo.pop()?;
+        data[offset] = value;
+        Some(offset)
+    }
+
+    /// True if the data differs from the file.
+    pub fn is_dirty(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Forgets all edits after the data was written to the file.
+    pub fn saved(&mut self) {
+        self.undo.clear();
+    }
+
+    /// The edited bytes inside `range` as sorted, merged ranges relative to
+    /// the start of `range`.
+    pub fn modified_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut offsets: Vec<usize> = self
+            .undo
+            .iter()
+            .map(|(offset, _)| *offset)
+            .filter(|offset| range.contains(offset))
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for offset in offsets {
+            let offset = offset - range.start;
+            match ranges.last_mut() {
+                Some(last) if last.end == offset => last.end += 1,
+                _ => ranges.push(offset..offset + 1),
+            }
+        }
+        ranges
+    }
+}
+
+/// Copies the file at `path` to `<path>.bak` before it is written the first
+/// time. Later writes keep the existing backup of the original, returns the
+/// path of a new backup.
+pub fn backup_original(path: &Path) -> Result<Option<PathBuf>, String> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    if fs::exists(&backup).unwrap_or(false) {
+        return Ok(None);
+    }
+    fs::copy(path, &backup).map_err(|e| format!("{}: {}", backup.display(), e))?;
+    Ok(Some(backup))
+}
@@ -0,0 +1,111 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use ratatui::widgets::ListState;
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_path;
+
+const RECENT_FILE: &str = "recent.toml";
+
+/// Number of paths kept in the recent list.
+const MAX_RECENT: usize = 20;
+
+/// The recently opened files, most recent first.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Recent {
+    pub paths: Vec<PathBuf>,
+}
+
+impl Recent {
+    /// Loads the recent list. A missing file results in an empty list.
+    pub fn load() -> Result<Recent, String> {
+        let Some(path) = config_path(RECENT_FILE) else {
+            return Ok(Recent::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Recent::default()),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = config_path(RECENT_FILE) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Moves `path` to the front of the list, adding it if it is new.
+    pub fn add(&mut self, path: &Path) {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.paths.retain(|p| *p != path);
+        self.paths.insert(0, path);
+        self.paths.truncate(MAX_RECENT);
+    }
+}
+
+/// State of the `:recent` popup. Paths that no longer exist are listed but
+/// can't be selected.
+pub struct RecentPicker {
+    pub entries: Vec<(PathBuf, bool)>,
+    pub list_state: ListState,
+}
+
+impl RecentPicker {
+    pub fn new(recent: &Recent) -> RecentPicker {
+        let entries: Vec<(PathBuf, bool)> = recent
+            .paths
+            .iter()
+            .map(|p| (p.clone(), p.exists()))
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select(entries.iter().position(|(_, exists)| *exists));
+        RecentPicker {
+            entries,
+            list_state,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        let from = self.list_state.selected().map_or(0, |s| s + 1);
+        if let Some(ix) = (from..self.entries.len()).find(|ix| self.entries[*ix].1) {
+            self.list_state.select(Some(ix));
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let to = self.list_state.selected().unwrap_or(0);
+        if let Some(ix) = (0..to).rev().find(|ix| self.entries[*ix].1) {
+            self.list_state.select(Some(ix));
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        if let Some(ix) = self.entries.iter().position(|(_, exists)| *exists) {
+            self.list_state.select(Some(ix));
+        }
+    }
+
+    pub fn select_last(&mut self) {
+        if let Some(ix) = self.entries.iter().rposition(|(_, exists)| *exists) {
+            self.list_state.select(Some(ix));
+        }
+    }
+
+    /// The selected path, None if no listed file exists.
+    pub fn selected(&self) -> Option<&Path> {
+        self.list_state
+            .selected()
+            .map(|ix| self.entries[ix].0.as_path())
+    }
+}
@@ -0,0 +1,63 @@
+/// Result of a byte pattern search over the raw binary data.
+pub struct SearchResult {
+    pub offsets: Vec<usize>,
+    pub current: usize,
+    pub pattern_len: usize,
+}
+
+impl SearchResult {
+    pub fn current_offset(&self) -> usize {
+        self.offsets[self.current]
+    }
+
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % self.offsets.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.current = (self.current + self.offsets.len() - 1) % self.offsets.len();
+    }
+}
+
+/// Parses a string of hex digits (e.g. "4889e5") into bytes.
+pub fn parse_hex_bytes(str: &str) -> Result<Vec<u8>, String> {
+    if !str.len().is_multiple_of(2) {
+        return Err(format!("odd number of nibbles in '{}'", str));
+    }
+    let mut bytes = Vec::with_capacity(str.len() / 2);
+    for i in (0..str.len()).step_by(2) {
+        let byte = str
+            .get(i..(i + 2))
+            .and_then(|b| u8::from_str_radix(b, 16).ok())
+            .ok_or_else(|| format!("invalid hex bytes '{}'", str))?;
+        bytes.push(byte);
+    }
+    Ok(bytes)
+}
+
+/// Counts the occurrences of `pattern` starting in the first `max_bytes` of
+/// `data`. The flag is false if the data was longer and not searched to its
+/// end.
+pub fn count_matches(data: &[u8], pattern: &[u8], max_bytes: usize) -> (usize, bool) {
+    if pattern.is_empty() {
+        return (0, true);
+    }
+    let searched = &data[..data.len().min(max_bytes.saturating_add(pattern.len() - 1))];
+    let count = searched
+        .windows(pattern.len())
+        .filter(|window| *window == pattern)
+        .count();
+    (count, data.len() <= max_bytes)
+}
+
+/// Returns the offsets of all occurrences of `pattern` in `data`.
+pub fn find_all(data: &[u8], pattern: &[u8]) -> Vec<usize> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    data.windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(offset, _)| offset)
+        .collect()
+}
@@ -0,0 +1,54 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_path;
+
+const SESSION_FILE: &str = "session.toml";
+
+/// The opened binaries of the last run, saved on exit.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Session {
+    pub binaries: Vec<SessionBinary>,
+    pub active: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionBinary {
+    pub path: PathBuf,
+    /// Position of the focused pane in the focus cycle of the view
+    pub focus: usize,
+    /// Bookmarked file offsets by name
+    #[serde(default)]
+    pub marks: BTreeMap<String, usize>,
+    /// Index of the architecture slice of a fat Mach-O
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slice: Option<usize>,
+}
+
+impl Session {
+    /// Loads the session of the last run, None if there is no session file.
+    pub fn load() -> Result<Option<Session>, String> {
+        let Some(path) = config_path(SESSION_FILE) else {
+            return Ok(None);
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        let session = toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Some(session))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = config_path(SESSION_FILE) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
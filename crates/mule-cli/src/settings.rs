@@ -0,0 +1,54 @@
+use std::{fs, io::ErrorKind};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::config_path, hex::GroupBytes};
+
+const SETTINGS_FILE: &str = "settings.toml";
+
+/// Preferences changed at runtime that are kept across starts.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hex_group_bytes: GroupBytes,
+    /// Color the bytes of the hex views by their class
+    pub hex_byte_colors: bool,
+    /// Copy a file to `<file>.bak` before the edits are written to it
+    pub backup_on_write: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hex_group_bytes: GroupBytes::default(),
+            hex_byte_colors: true,
+            backup_on_write: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings. A missing file results in the defaults.
+    pub fn load() -> Result<Settings, String> {
+        let Some(path) = config_path(SETTINGS_FILE) else {
+            return Ok(Settings::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = config_path(SETTINGS_FILE) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the unit tests of the views.
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+/// The symbols of the buffer cells, one line per row.
+pub fn buffer_text(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            text.push_str(buf[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// An empty buffer of the size.
+pub fn buffer(width: u16, height: u16) -> Buffer {
+    Buffer::empty(Rect::new(0, 0, width, height))
+}
+
+/// A 32 KiB ROM without a cartridge type or title, all code is `nop`.
+pub fn gb_rom() -> Vec<u8> {
+    vec![0; 0x8000]
+}
@@ -0,0 +1,91 @@
+use std::{fs, io::ErrorKind, str::FromStr};
+
+use ratatui::style::{Color, Style};
+use serde::Deserialize;
+
+use crate::config::config_path;
+
+/// Colors used by all views. Loaded from `~/.config/mule/theme.toml`.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub focus: Color,
+    pub normal: Color,
+    pub header: Color,
+    pub highlight: Color,
+    pub highlight_text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            focus: Color::Yellow,
+            normal: Color::Reset,
+            header: Color::Reset,
+            highlight: Color::White,
+            highlight_text: Color::Black,
+        }
+    }
+}
+
+/// The theme file, all colors are optional and default to the built-in theme.
+/// Colors are either named (`yellow`) or hex (`#ffcc00`) values.
+#[derive(Deserialize)]
+struct ThemeFile {
+    focus: Option<String>,
+    normal: Option<String>,
+    header: Option<String>,
+    highlight: Option<String>,
+    highlight_text: Option<String>,
+}
+
+impl Theme {
+    /// Loads the theme file. A missing file results in the default theme,
+    /// a malformed file in an error.
+    pub fn load() -> Result<Theme, String> {
+        let Some(path) = config_path("theme.toml") else {
+            return Ok(Theme::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Theme::default()),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        let file: ThemeFile =
+            toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let default = Theme::default();
+        Ok(Theme {
+            focus: parse_color(file.focus, default.focus)?,
+            normal: parse_color(file.normal, default.normal)?,
+            header: parse_color(file.header, default.header)?,
+            highlight: parse_color(file.highlight, default.highlight)?,
+            highlight_text: parse_color(file.highlight_text, default.highlight_text)?,
+        })
+    }
+
+    pub fn style_focus(&self) -> Style {
+        Style::default().fg(self.focus)
+    }
+
+    pub fn style_normal(&self) -> Style {
+        Style::default().fg(self.normal)
+    }
+
+    pub fn style_header(&self) -> Style {
+        Style::default().fg(self.header)
+    }
+
+    /// Style of the selected entry in a list.
+    pub fn style_highlight(&self) -> Style {
+        Style::default().fg(self.highlight_text).bg(self.highlight)
+    }
+}
+
+fn parse_color(value: Option<String>, default: Color) -> Result<Color, String> {
+    match value {
+        None => Ok(default),
+        Some(value) => {
+            Color::from_str(&value).map_err(|_| format!("invalid color '{}' in theme", value))
+        }
+    }
+}
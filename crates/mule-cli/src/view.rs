@@ -1,9 +1,228 @@
-use ratatui::style::{Color, Style};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Margin, Position, Rect},
+    widgets::{
+        Block, List, ListState, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, Table, Widget,
+    },
+};
 
-pub fn style_focus() -> Style {
-    Style::default().fg(Color::Yellow)
+use crate::{keymap::Action, theme::Theme};
+
+/// Areas of the panes of a view, updated on render. Used to find the pane
+/// under the mouse.
+pub struct PaneAreas<F> {
+    areas: Vec<(F, Rect)>,
+}
+
+impl<F: Copy> PaneAreas<F> {
+    pub fn new() -> PaneAreas<F> {
+        PaneAreas { areas: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.areas.clear();
+    }
+
+    pub fn push(&mut self, pane: F, area: Rect) {
+        self.areas.push((pane, area));
+    }
+
+    pub fn pane_at(&self, column: u16, row: u16) -> Option<(F, Rect)> {
+        self.areas
+            .iter()
+            .find(|(_, area)| area.contains(Position::new(column, row)))
+            .copied()
+    }
+}
+
+impl<F: Copy + PartialEq> PaneAreas<F> {
+    /// Rows inside the borders of the pane, 0 if it wasn't rendered.
+    pub fn page_rows(&self, pane: F) -> usize {
+        self.areas
+            .iter()
+            .find(|(p, _)| *p == pane)
+            .map_or(0, |(_, area)| area.height.saturating_sub(2) as usize)
+    }
+}
+
+/// Moves the selection of a list by a page of `page_rows` or to its first or
+/// last entry. Actions other than paging are ignored.
+pub fn page_list(list: &mut ListState, action: Action, page_rows: usize) {
+    let rows = page_rows.max(1) as u16;
+    match action {
+        Action::PageDown => list.scroll_down_by(rows),
+        Action::PageUp => list.scroll_up_by(rows),
+        Action::Home => list.select_first(),
+        Action::End => list.select_last(),
+        _ => { /* ignore */ }
+    }
+}
+
+/// Scroll position of a pane without a selection to follow, e.g. a table of
+/// header fields. Clamped to the content on render.
+#[derive(Default)]
+pub struct PaneScroll {
+    row: usize,
+    // updated on render
+    page_rows: usize,
+}
+
+impl PaneScroll {
+    /// Scrolls by a page or to the start or end. Actions other than paging
+    /// are ignored.
+    pub fn handle_action(&mut self, action: Action) {
+        let page = self.page_rows.max(1);
+        match action {
+            Action::PageDown => self.row = self.row.saturating_add(page),
+            Action::PageUp => self.row = self.row.saturating_sub(page),
+            Action::Home => self.row = 0,
+            Action::End => self.row = usize::MAX,
+            _ => { /* ignore */ }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.row = 0;
+    }
+
+    /// The first shown of `rows` rows when `visible_rows` of them fit.
+    pub fn first_row(&mut self, rows: usize, visible_rows: usize) -> usize {
+        self.page_rows = visible_rows;
+        self.row = self.row.min(rows.saturating_sub(visible_rows));
+        self.row
+    }
+}
+
+/// Renders the rows of a table from the scroll position on.
+pub fn render_scrolled_table<'a>(
+    rows: impl IntoIterator<Item = Row<'a>>,
+    widths: &[Constraint],
+    block: Block,
+    area: Rect,
+    buf: &mut Buffer,
+    scroll: &mut PaneScroll,
+) {
+    let rows: Vec<Row> = rows.into_iter().collect();
+    let first = scroll.first_row(rows.len(), block.inner(area).height as usize);
+    let table = Table::new(rows.into_iter().skip(first), widths).block(block);
+    Widget::render(table, area, buf);
 }
 
-pub fn style_normal() -> Style {
-    Style::default()
+const MIN_SPLIT_PERCENT: u16 = 10;
+const MAX_SPLIT_PERCENT: u16 = 90;
+const SPLIT_STEP_PERCENT: u16 = 5;
+
+/// Horizontal split between the file pane (left) and the detail pane (right)
+/// of a view.
+pub struct Split {
+    file_percent: u16,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Split { file_percent: 30 }
+    }
+}
+
+impl Split {
+    pub fn widen_file_pane(&mut self) {
+        self.file_percent = (self.file_percent + SPLIT_STEP_PERCENT).min(MAX_SPLIT_PERCENT);
+    }
+
+    pub fn narrow_file_pane(&mut self) {
+        self.file_percent = (self.file_percent - SPLIT_STEP_PERCENT).max(MIN_SPLIT_PERCENT);
+    }
+
+    pub fn constraints(&self) -> [Constraint; 2] {
+        [
+            Constraint::Percentage(self.file_percent),
+            Constraint::Percentage(100 - self.file_percent),
+        ]
+    }
+}
+
+/// Index of the entry at the terminal `row` of a bordered list rendered
+/// into `area`. The index may be past the end of the list.
+pub fn list_index_at(area: Rect, state: &ListState, row: u16) -> Option<usize> {
+    let inner = area.inner(Margin::new(1, 1));
+    if row < inner.y || row >= inner.bottom() {
+        return None;
+    }
+    Some(state.offset() + (row - inner.y) as usize)
+}
+
+/// Renders a scrollbar on the right border of a bordered list with `len`
+/// entries rendered into `area`. Nothing is rendered if all entries fit.
+pub fn render_list_scrollbar(area: Rect, buf: &mut Buffer, len: usize, state: &ListState) {
+    let track = area.inner(Margin::new(0, 1));
+    let visible_rows = track.height as usize;
+    if len <= visible_rows {
+        return;
+    }
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    let mut scrollbar_state = ScrollbarState::new(len - visible_rows + 1)
+        .position(state.offset())
+        .viewport_content_length(visible_rows);
+    StatefulWidget::render(scrollbar, track, buf, &mut scrollbar_state);
+}
+
+/// Index of the next line after `from` that contains `pattern` (ignoring
+/// case), wrapping around at the end. Searches towards the start if
+/// `backward` is set.
+pub fn find_line(lines: &[&str], pattern: &str, from: usize, backward: bool) -> Option<usize> {
+    let pattern = pattern.to_lowercase();
+    let len = lines.len();
+    let from = from.min(len.saturating_sub(1));
+    (1..=len)
+        .map(|step| {
+            if backward {
+                (from + len - step) % len
+            } else {
+                (from + step) % len
+            }
+        })
+        .find(|ix| lines[*ix].to_lowercase().contains(&pattern))
+}
+
+/// Renders a list of `len` items where only the visible items are created with `item`.
+/// Avoids formatting every entry on each frame for lists with thousands of entries.
+/// The `state` keeps the selection and offset into the complete list.
+pub fn render_lazy_list(
+    block: Block,
+    len: usize,
+    item: impl Fn(usize) -> String,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &mut ListState,
+    theme: &Theme,
+) {
+    let visible_rows = block.inner(area).height as usize;
+    let selected = state.selected().map(|s| s.min(len.saturating_sub(1)));
+    state.select(selected);
+
+    let mut offset = state.offset().min(len.saturating_sub(1));
+    if let Some(selected) = selected {
+        if selected < offset {
+            offset = selected;
+        } else if visible_rows > 0 && selected >= offset + visible_rows {
+            offset = selected + 1 - visible_rows;
+        }
+    }
+    *state.offset_mut() = offset;
+
+    let end = (offset + visible_rows).min(len);
+    let items: Vec<String> = (offset..end).map(item).collect();
+    let mut window_state = ListState::default().with_selected(
+        selected
+            .filter(|s| *s >= offset && *s < end)
+            .map(|s| s - offset),
+    );
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.style_highlight());
+    StatefulWidget::render(list, area, buf, &mut window_state);
+    render_list_scrollbar(area, buf, len, state);
 }
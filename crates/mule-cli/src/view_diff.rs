@@ -0,0 +1,241 @@
+use std::ops::Range;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, ListState, StatefulWidget, Widget, WidgetRef},
+};
+
+use crate::{
+    diff::{DiffKind, DiffRange},
+    hex::{GroupBytes, Hex, HexState},
+    keymap::Action,
+    theme::Theme,
+    view::render_lazy_list,
+};
+
+const DIFF_LIST_WIDTH: u16 = 28;
+
+/// Compares the raw bytes of two opened binaries, identified by their tab index.
+pub struct DiffState {
+    pub left: usize,
+    pub right: usize,
+    ranges: Vec<DiffRange>,
+    highlights: Vec<(Range<usize>, Style)>,
+    list_state: ListState,
+    // shared by both hex views, keeps them in sync
+    hex_state: HexState,
+    // start of the selected difference until the hex views are scrolled
+    anchor: Option<usize>,
+}
+
+impl DiffState {
+    pub fn new(left: usize, right: usize, ranges: Vec<DiffRange>) -> DiffState {
+        let highlights = ranges
+            .iter()
+            .map(|r| {
+                let color = match r.kind {
+                    DiffKind::Changed => Color::Red,
+                    DiffKind::Added => Color::Green,
+                };
+                (r.range.clone(), Style::default().fg(color))
+            })
+            .collect();
+        let mut state = DiffState {
+            left,
+            right,
+            ranges,
+            highlights,
+            list_state: ListState::default(),
+            hex_state: HexState::default(),
+            anchor: None,
+        };
+        state.select(0);
+        state
+    }
+
+    pub fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.hex_state.set_group_bytes(group_bytes);
+    }
+
+    pub fn relayout(&mut self) {
+        self.hex_state.relayout();
+    }
+
+    pub fn ranges(&self) -> &[DiffRange] {
+        &self.ranges
+    }
+
+    pub fn handle_action(&mut self, action: Action) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        match action {
+            Action::SelectNext => self.select(selected.saturating_add(1)),
+            Action::SelectPrev => self.select(selected.saturating_sub(1)),
+            Action::PageDown => self.scrolled(HexState::page_down),
+            Action::PageUp => self.scrolled(HexState::page_up),
+            Action::Home => self.scrolled(HexState::scroll_to_start),
+            Action::End => self.scrolled(HexState::scroll_to_end),
+            Action::JumpForward => self.select_next_from_view(false),
+            Action::JumpBack => self.select_next_from_view(true),
+            Action::Left => self.hex_state.scroll_left(),
+            Action::Right => self.hex_state.scroll_right(),
+            Action::ToggleHexLayout => self.hex_state.toggle_layout(),
+            _ => { /* ignore */ }
+        }
+    }
+
+    fn select(&mut self, ix: usize) {
+        let ix = ix.min(self.ranges.len().saturating_sub(1));
+        if let Some(diff) = self.ranges.get(ix) {
+            self.list_state.select(Some(ix));
+            self.hex_state.scroll_to_offset(diff.range.start);
+            self.anchor = Some(diff.range.start);
+        }
+    }
+
+    fn scrolled(&mut self, scroll: fn(&mut HexState)) {
+        scroll(&mut self.hex_state);
+        self.anchor = None;
+    }
+
+    /// Selects the first difference after (or the last before) the selected
+    /// one, or after the first shown byte once the hex views were scrolled.
+    fn select_next_from_view(&mut self, backward: bool) {
+        let position = self.anchor.unwrap_or(self.hex_state.top_offset());
+        // the ranges are sorted and don't overlap
+        let after = self.ranges.partition_point(|r| r.range.start <= position);
+        if backward {
+            let before = self.ranges.partition_point(|r| r.range.start < position);
+            if before > 0 {
+                self.select(before - 1);
+            }
+        } else if after < self.ranges.len() {
+            self.select(after);
+        }
+    }
+}
+
+pub struct DiffWidget<'a> {
+    left: (&'a str, &'a Vec<u8>),
+    right: (&'a str, &'a Vec<u8>),
+    theme: &'a Theme,
+    state: &'a mut DiffState,
+}
+
+impl<'a> DiffWidget<'a> {
+    pub fn new(
+        left: (&'a str, &'a Vec<u8>),
+        right: (&'a str, &'a Vec<u8>),
+        theme: &'a Theme,
+        state: &'a mut DiffState,
+    ) -> DiffWidget<'a> {
+        DiffWidget {
+            left,
+            right,
+            theme,
+            state,
+        }
+    }
+}
+
+impl<'a> Widget for &mut DiffWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // equal widths for the hex views, so that both show the same rows
+        let hex_width = area.width.saturating_sub(DIFF_LIST_WIDTH) / 2;
+        let [list_area, left_area, right_area] = Layout::horizontal([
+            Constraint::Length(DIFF_LIST_WIDTH),
+            Constraint::Length(hex_width),
+            Constraint::Length(hex_width),
+        ])
+        .areas(area);
+
+        let ranges = &self.state.ranges;
+        let list_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.theme.style_focus())
+            .title(format!("Differences ({})", ranges.len()))
+            .title_bottom("Esc: close");
+        render_lazy_list(
+            list_block,
+            ranges.len(),
+            |i| {
+                let r = &ranges[i];
+                let kind = match r.kind {
+                    DiffKind::Changed => "~",
+                    DiffKind::Added => "+",
+                };
+                format!("{} 0x{:X}-0x{:X}", kind, r.range.start, r.range.end)
+            },
+            list_area,
+            buf,
+            &mut self.state.list_state,
+            self.theme,
+        );
+
+        let (left_name, left_data) = self.left;
+        let left_hex = Hex::new(left_data)
+            .highlights(&self.state.highlights)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .title(left_name),
+            );
+        StatefulWidget::render(&left_hex, left_area, buf, &mut self.state.hex_state);
+
+        // scrolled to the position resolved by the left view
+        let (right_name, right_data) = self.right;
+        let right_hex = Hex::new(right_data)
+            .highlights(&self.state.highlights)
+            .with_scroll(self.state.hex_state.scroll_row())
+            .with_layout(
+                self.state.hex_state.fixed_layout(),
+                self.state.hex_state.scroll_column(),
+            )
+            .with_group_bytes(self.state.hex_state.group_bytes())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .title(right_name),
+            );
+        right_hex.render_ref(right_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::diff_bytes;
+
+    fn diff_state() -> DiffState {
+        // three differences at 1, 4 and 8
+        let ranges = diff_bytes(&[0, 1, 0, 0, 1, 0, 0, 0, 1], &[0; 9]);
+        assert_eq!(ranges.len(), 3);
+        DiffState::new(0, 1, ranges)
+    }
+
+    #[test]
+    fn next_difference_stops_at_the_last() {
+        let mut state = diff_state();
+        assert_eq!(state.list_state.selected(), Some(0));
+        state.handle_action(Action::JumpForward);
+        state.handle_action(Action::JumpForward);
+        assert_eq!(state.list_state.selected(), Some(2));
+        state.handle_action(Action::JumpForward);
+        assert_eq!(state.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn previous_difference_stops_at_the_first() {
+        let mut state = diff_state();
+        state.handle_action(Action::JumpBack);
+        assert_eq!(state.list_state.selected(), Some(0));
+        state.handle_action(Action::JumpForward);
+        state.handle_action(Action::JumpForward);
+        state.handle_action(Action::JumpBack);
+        assert_eq!(state.list_state.selected(), Some(1));
+        state.handle_action(Action::JumpBack);
+        state.handle_action(Action::JumpBack);
+        assert_eq!(state.list_state.selected(), Some(0));
+    }
+}
@@ -0,0 +1,503 @@
+use std::ops::Range;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use mule_elf::{
+    Elf, Endianness, PF_R, PF_W, PF_X, ProgramHeader, SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE,
+    SectionHeader, SectionType,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, Widget},
+};
+
+use crate::{
+    InteractiveCommand,
+    binary_view::{BinaryView, ByteOrder},
+    dump,
+    focus::FocusRing,
+    keymap::Action,
+    patch::Patches,
+    theme::Theme,
+    view::{
+        PaneAreas, PaneScroll, Split, list_index_at, page_list, render_list_scrollbar,
+        render_scrolled_table,
+    },
+};
+
+#[derive(PartialEq, Copy, Clone)]
+enum Focus {
+    Header,
+    Segments,
+    Sections,
+}
+
+static FOCUS_CYCLE_ORDER: [Focus; 3] = [Focus::Header, Focus::Segments, Focus::Sections];
+
+pub struct ElfInteractiveState {
+    focus: FocusRing<Focus>,
+    segment_list_state: ListState,
+    section_list_state: ListState,
+    // of the header table in the detail pane
+    header_scroll: PaneScroll,
+    pane_areas: PaneAreas<Focus>,
+    split: Split,
+}
+
+impl ElfInteractiveState {
+    pub fn new() -> ElfInteractiveState {
+        let mut segment_list_state = ListState::default();
+        segment_list_state.select(Some(0));
+        let mut section_list_state = ListState::default();
+        section_list_state.select(Some(0));
+        ElfInteractiveState {
+            segment_list_state,
+            section_list_state,
+            header_scroll: PaneScroll::default(),
+            pane_areas: PaneAreas::new(),
+            split: Split::default(),
+            focus: FocusRing::new(&FOCUS_CYCLE_ORDER, Focus::Sections),
+        }
+    }
+
+    pub fn handle_command(&mut self, command: InteractiveCommand) {
+        match command {
+            InteractiveCommand::Action(action) => match action {
+                Action::FocusNext => self.focus.next(),
+                Action::FocusPrev => self.focus.prev(),
+                Action::WidenFilePane => self.split.widen_file_pane(),
+                Action::NarrowFilePane => self.split.narrow_file_pane(),
+                Action::SelectNext => match self.focus.current() {
+                    Some(Focus::Segments) => self.segment_list_state.select_next(),
+                    Some(Focus::Sections) => self.section_list_state.select_next(),
+                    _ => { /* ignore */ }
+                },
+                Action::SelectPrev => match self.focus.current() {
+                    Some(Focus::Segments) => self.segment_list_state.select_previous(),
+                    Some(Focus::Sections) => self.section_list_state.select_previous(),
+                    _ => { /* ignore */ }
+                },
+                Action::PageDown | Action::PageUp | Action::Home | Action::End => {
+                    match self.focus.current() {
+                        Some(Focus::Header) => self.header_scroll.handle_action(action),
+                        Some(Focus::Segments) => page_list(
+                            &mut self.segment_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::Segments),
+                        ),
+                        Some(Focus::Sections) => page_list(
+                            &mut self.section_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::Sections),
+                        ),
+                        None => { /* ignore */ }
+                    }
+                }
+                _ => { /* ignore */ }
+            },
+            InteractiveCommand::Focus => self.focus.focus(),
+            InteractiveCommand::Unfocus => self.focus.unfocus(),
+            InteractiveCommand::Mouse(mouse) => self.handle_mouse(mouse),
+            InteractiveCommand::Toggle(_) => { /* ignore */ }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let Some((pane, area)) = self.pane_areas.pane_at(mouse.column, mouse.row) else {
+            return;
+        };
+        self.focus.set(pane);
+        let list = match pane {
+            Focus::Segments => &mut self.segment_list_state,
+            Focus::Sections => &mut self.section_list_state,
+            _ => return,
+        };
+        if let Some(ix) = list_index_at(area, list, mouse.row) {
+            list.select(Some(ix));
+        }
+    }
+
+    /// File byte range of the selected segment or section, depending on
+    /// which list is (or was before unfocusing) in focus.
+    pub fn selected_region(&self, elf: &Elf) -> Option<Range<usize>> {
+        match self.focus.active() {
+            Focus::Segments => {
+                let segment = elf
+                    .program_headers
+                    .get(self.segment_list_state.selected()?)?;
+                let start = segment.offset as usize;
+                Some(start..start.saturating_add(segment.file_size as usize))
+            }
+            Focus::Sections => {
+                let section = elf
+                    .section_headers
+                    .get(self.section_list_state.selected()?)?;
+                if section.section_type == SectionType::NoBits {
+                    return None;
+                }
+                let start = section.offset as usize;
+                Some(start..start.saturating_add(section.size as usize))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn focus_index(&self) -> usize {
+        self.focus.index()
+    }
+
+    pub fn set_focus_index(&mut self, ix: usize) {
+        self.focus.set_index(ix);
+    }
+}
+
+/// An ELF binary and the state of its view.
+pub struct ElfView {
+    pub elf: Elf,
+    pub state: ElfInteractiveState,
+}
+
+impl ElfView {
+    pub fn new(elf: Elf) -> ElfView {
+        ElfView {
+            elf,
+            state: ElfInteractiveState::new(),
+        }
+    }
+}
+
+impl BinaryView for ElfView {
+    fn type_name(&self) -> &'static str {
+        "ELF"
+    }
+
+    fn summary(&self) -> String {
+        let header = &self.elf.header;
+        format!(
+            "ELF, {:?}, {:?}, {:?}",
+            header.class, header.machine, header.file_type
+        )
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        Some(match self.elf.header.endianness {
+            Endianness::Little => ByteOrder::Little,
+            Endianness::Big => ByteOrder::Big,
+        })
+    }
+
+    fn dump(&self, _: &[u8], out: &mut String) {
+        dump::dump_elf(out, &self.elf);
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _: &[u8], _: &Patches, theme: &Theme) {
+        let mut widget = ElfWidget::new(&self.elf, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command);
+        None
+    }
+
+    /// A section by its name, e.g. ".text".
+    fn named_region(&self, name: &str) -> Option<Range<usize>> {
+        let section = self.elf.section_headers.iter().find(|s| s.name == name)?;
+        let start = section.offset as usize;
+        Some(start..start.saturating_add(section.size as usize))
+    }
+
+    fn describe_offset(&self, offset: usize) -> Option<String> {
+        let offset = offset as u64;
+        let within = |start: u64, len: u64| (start..start.saturating_add(len)).contains(&offset);
+        let section =
+            self.elf.section_headers.iter().find(|s| {
+                !matches!(s.section_type, SectionType::NoBits) && within(s.offset, s.size)
+            });
+        if let Some(section) = section {
+            return Some(format!("{} +0x{:X}", section.name, offset - section.offset));
+        }
+        let (ix, segment) = self
+            .elf
+            .program_headers
+            .iter()
+            .enumerate()
+            .find(|(_, p)| within(p.offset, p.file_size))?;
+        Some(format!(
+            "segment {} ({:?}) +0x{:X}",
+            ix,
+            segment.segment_type,
+            offset - segment.offset
+        ))
+    }
+
+    fn focus_index(&self) -> usize {
+        self.state.focus_index()
+    }
+
+    fn set_focus_index(&mut self, ix: usize) {
+        self.state.set_focus_index(ix);
+    }
+
+    fn selected_region(&self) -> Option<Range<usize>> {
+        self.state.selected_region(&self.elf)
+    }
+}
+
+pub struct ElfWidget<'a> {
+    pub elf: &'a Elf,
+    pub theme: &'a Theme,
+    pub state: &'a mut ElfInteractiveState,
+}
+
+impl<'a> ElfWidget<'a> {
+    pub fn new(
+        elf: &'a Elf,
+        theme: &'a Theme,
+        state: &'a mut ElfInteractiveState,
+    ) -> ElfWidget<'a> {
+        ElfWidget { elf, theme, state }
+    }
+
+    fn focus_style(&self, focus: Focus) -> Style {
+        if self.state.focus.is(focus) {
+            self.theme.style_focus()
+        } else {
+            self.theme.style_normal()
+        }
+    }
+
+    fn render_detail_view(&mut self, content_detail: Rect, buf: &mut Buffer) {
+        let detail_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .title("Details");
+
+        match self.state.focus.active() {
+            Focus::Header => self.render_header_detail(detail_block, content_detail, buf),
+            Focus::Segments => {
+                let selected = self.state.segment_list_state.selected();
+                if let Some(segment) = selected.and_then(|i| self.elf.program_headers.get(i)) {
+                    render_segment_detail(segment, detail_block, content_detail, buf);
+                }
+            }
+            Focus::Sections => {
+                let selected = self.state.section_list_state.selected();
+                if let Some(section) = selected.and_then(|i| self.elf.section_headers.get(i)) {
+                    render_section_detail(section, detail_block, content_detail, buf);
+                }
+            }
+        }
+    }
+
+    fn render_header_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let header = &self.elf.header;
+        let class_text = &format!("{:?}", header.class);
+        let endianness_text = &format!("{:?}", header.endianness);
+        let os_abi_text = &format!("0x{:02X}", header.os_abi);
+        let file_type_text = &format!("{:?}", header.file_type);
+        let machine_text = &format!("{:?}", header.machine);
+        let entry_text = &format!("0x{:X}", header.entry_point);
+        let ph_text = &format!("{} @ 0x{:X}", header.ph_num, header.ph_offset);
+        let sh_text = &format!("{} @ 0x{:X}", header.sh_num, header.sh_offset);
+        let flags_text = &format!("0x{:X}", header.flags);
+        let rows = [
+            Row::new(vec!["Class:", class_text]),
+            Row::new(vec!["Endianness:", endianness_text]),
+            Row::new(vec!["OS ABI:", os_abi_text]),
+            Row::new(vec!["Type:", file_type_text]),
+            Row::new(vec!["Machine:", machine_text]),
+            Row::new(vec!["Entry Point:", entry_text]),
+            Row::new(vec!["Program Headers:", ph_text]),
+            Row::new(vec!["Section Headers:", sh_text]),
+            Row::new(vec!["Flags:", flags_text]),
+        ];
+        let widths = [Constraint::Length(18), Constraint::Fill(1)];
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.header_scroll,
+        );
+    }
+}
+
+fn render_segment_detail(
+    segment: &ProgramHeader,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+) {
+    let type_text = &format!("{:?}", segment.segment_type);
+    let flags_text = &segment_flags_display(segment.flags);
+    let offset_text = &format!("0x{:X}", segment.offset);
+    let vm_addr_text = &format!("0x{:X}", segment.vm_addr);
+    let phys_addr_text = &format!("0x{:X}", segment.phys_addr);
+    let file_size_text = &format!("0x{:X}", segment.file_size);
+    let mem_size_text = &format!("0x{:X}", segment.mem_size);
+    let align_text = &format!("0x{:X}", segment.align);
+    let rows = [
+        Row::new(vec!["Type:", type_text]),
+        Row::new(vec!["Flags:", flags_text]),
+        Row::new(vec!["Offset:", offset_text]),
+        Row::new(vec!["Virtual Address:", vm_addr_text]),
+        Row::new(vec!["Physical Address:", phys_addr_text]),
+        Row::new(vec!["File Size:", file_size_text]),
+        Row::new(vec!["Memory Size:", mem_size_text]),
+        Row::new(vec!["Align:", align_text]),
+    ];
+    let widths = [Constraint::Length(18), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn render_section_detail(
+    section: &SectionHeader,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+) {
+    let type_text = &format!("{:?}", section.section_type);
+    let flags_text = &section_flags_display(section.flags);
+    let addr_text = &format!("0x{:X}", section.addr);
+    let offset_text = &format!("0x{:X}", section.offset);
+    let size_text = &format!("0x{:X}", section.size);
+    let link_text = &format!("{}", section.link);
+    let info_text = &format!("{}", section.info);
+    let align_text = &format!("0x{:X}", section.addr_align);
+    let entry_size_text = &format!("0x{:X}", section.entry_size);
+    let rows = [
+        Row::new(vec!["Name:", &section.name]),
+        Row::new(vec!["Type:", type_text]),
+        Row::new(vec!["Flags:", flags_text]),
+        Row::new(vec!["Address:", addr_text]),
+        Row::new(vec!["Offset:", offset_text]),
+        Row::new(vec!["Size:", size_text]),
+        Row::new(vec!["Link:", link_text]),
+        Row::new(vec!["Info:", info_text]),
+        Row::new(vec!["Align:", align_text]),
+        Row::new(vec!["Entry Size:", entry_size_text]),
+    ];
+    let widths = [Constraint::Length(18), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn segment_flags_display(flags: u32) -> String {
+    let mut result = String::with_capacity(3);
+    result.push(if flags & PF_R != 0 { 'R' } else { '-' });
+    result.push(if flags & PF_W != 0 { 'W' } else { '-' });
+    result.push(if flags & PF_X != 0 { 'X' } else { '-' });
+    result
+}
+
+fn section_flags_display(flags: u64) -> String {
+    let mut result = String::with_capacity(3);
+    result.push(if flags & SHF_ALLOC != 0 { 'A' } else { '-' });
+    result.push(if flags & SHF_WRITE != 0 { 'W' } else { '-' });
+    result.push(if flags & SHF_EXECINSTR != 0 { 'X' } else { '-' });
+    result
+}
+
+impl<'a> Widget for &mut ElfWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let content_layout = Layout::horizontal(self.state.split.constraints());
+        let [content_file, content_detail] = content_layout.areas(area);
+
+        let file_layout = Layout::vertical([
+            Constraint::Max(3),
+            Constraint::Percentage(30),
+            Constraint::Fill(1),
+        ]);
+        let [elf_header, elf_segments, elf_sections] = file_layout.areas(content_file);
+
+        let pane_areas = &mut self.state.pane_areas;
+        pane_areas.clear();
+        pane_areas.push(Focus::Header, elf_header);
+        pane_areas.push(Focus::Segments, elf_segments);
+        pane_areas.push(Focus::Sections, elf_sections);
+
+        let header_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Header))
+            .title("Header");
+
+        Paragraph::new(format!(
+            "{:?} | {:?} | {:?}",
+            self.elf.header.class, self.elf.header.machine, self.elf.header.file_type,
+        ))
+        .block(header_block)
+        .render(elf_header, buf);
+
+        let segment_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Segments))
+            .title(format!("Segments ({})", self.elf.program_headers.len()));
+        let segment_list = List::new(segment_list(&self.elf.program_headers))
+            .block(segment_block)
+            .highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            segment_list,
+            elf_segments,
+            buf,
+            &mut self.state.segment_list_state,
+        );
+        render_list_scrollbar(
+            elf_segments,
+            buf,
+            self.elf.program_headers.len(),
+            &self.state.segment_list_state,
+        );
+
+        let section_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Sections))
+            .title(format!("Sections ({})", self.elf.section_headers.len()));
+        let section_list = List::new(section_list(&self.elf.section_headers))
+            .block(section_block)
+            .highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            section_list,
+            elf_sections,
+            buf,
+            &mut self.state.section_list_state,
+        );
+        render_list_scrollbar(
+            elf_sections,
+            buf,
+            self.elf.section_headers.len(),
+            &self.state.section_list_state,
+        );
+
+        self.render_detail_view(content_detail, buf);
+    }
+}
+
+pub fn segment_list(segments: &[ProgramHeader]) -> Vec<String> {
+    let mut result = Vec::with_capacity(segments.len());
+    for segment in segments {
+        result.push(format!(
+            "{:?} | {}",
+            segment.segment_type,
+            segment_flags_display(segment.flags)
+        ));
+    }
+    result
+}
+
+pub fn section_list(sections: &[SectionHeader]) -> Vec<String> {
+    let mut result = Vec::with_capacity(sections.len());
+    for section in sections {
+        if section.name.is_empty() {
+            result.push(format!("<{:?}>", section.section_type));
+        } else {
+            result.push(section.name.clone());
+        }
+    }
+    result
+}
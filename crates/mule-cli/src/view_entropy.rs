@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    symbols::bar::NINE_LEVELS,
+    widgets::{Block, BorderType, Widget},
+};
+
+use crate::theme::Theme;
+
+pub struct EntropyState {
+    selected: usize,
+    // updated on render, the number of chunks the data is split into
+    columns: usize,
+}
+
+impl EntropyState {
+    pub fn new() -> EntropyState {
+        EntropyState {
+            selected: 0,
+            columns: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.columns.saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// The byte range of the selected column.
+    pub fn selected_range(&self, data_len: usize) -> Range<usize> {
+        chunk_range(self.selected, self.columns.max(1), data_len)
+    }
+}
+
+/// Shannon entropy of the bytes in bits per byte (0.0 to 8.0).
+pub fn shannon_entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for b in bytes {
+        counts[*b as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    let mut entropy = 0.0;
+    for count in counts {
+        if count > 0 {
+            let p = count as f64 / len;
+            entropy -= p * p.log2();
+        }
+    }
+    entropy
+}
+
+pub fn chunk_range(column: usize, columns: usize, data_len: usize) -> Range<usize> {
+    (column * data_len / columns)..((column + 1) * data_len / columns)
+}
+
+/// Draws the entropy of the data as bars, one bar per chunk of the data.
+pub struct EntropyWidget<'a> {
+    pub data: &'a [u8],
+    pub theme: &'a Theme,
+    pub state: &'a mut EntropyState,
+}
+
+impl<'a> EntropyWidget<'a> {
+    pub fn new(data: &'a [u8], theme: &'a Theme, state: &'a mut EntropyState) -> EntropyWidget<'a> {
+        EntropyWidget { data, theme, state }
+    }
+}
+
+impl<'a> Widget for &mut EntropyWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .title("Entropy");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let columns = inner.width as usize;
+        self.state.columns = columns;
+        self.state.selected = self.state.selected.min(columns.saturating_sub(1));
+        if inner.height == 0 {
+            return;
+        }
+
+        let levels = [
+            NINE_LEVELS.empty,
+            NINE_LEVELS.one_eighth,
+            NINE_LEVELS.one_quarter,
+            NINE_LEVELS.three_eighths,
+            NINE_LEVELS.half,
+            NINE_LEVELS.five_eighths,
+            NINE_LEVELS.three_quarters,
+            NINE_LEVELS.seven_eighths,
+            NINE_LEVELS.full,
+        ];
+        let max_eighths = inner.height as usize * 8;
+        for column in 0..columns {
+            let range = chunk_range(column, columns, self.data.len());
+            let entropy = shannon_entropy(&self.data[range]);
+            let mut eighths = (entropy / 8.0 * max_eighths as f64).round() as usize;
+            let style = if column == self.state.selected {
+                self.theme.style_highlight()
+            } else {
+                self.theme.style_focus()
+            };
+            let x = inner.x + column as u16;
+            for row in (0..inner.height).rev() {
+                let level = eighths.min(8);
+                eighths -= level;
+                buf[(x, inner.y + row)]
+                    .set_symbol(levels[level])
+                    .set_style(style);
+            }
+        }
+    }
+}
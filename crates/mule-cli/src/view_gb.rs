@@ -1,268 +1,1071 @@
-use crossterm::event::KeyCode;
+use std::{
+    collections::BTreeSet,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::Style,
+    style::{Color, Style},
     widgets::{
-        Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, Widget,
-        WidgetRef,
+        Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, TableState,
+        Widget,
     },
 };
 
-use mule_gb::{DestinationCode, GBBinary, GBCFlag, RAMSize, ROMSize, SGBFlag, num_banks};
+use mule_gb::{
+    BANK_BYTES, CartridgeType, DATA_START, DestinationCode, GBBinary, GBCFlag, RAMSize, ROMSize,
+    SGBFlag, bank_at, bank_offset, declared_rom_bytes, global_checksum, header_checksum,
+    header_region_at, header_warnings, save_bytes,
+};
 use psy::dasm::gb;
 
 use crate::{
-    InteractiveCommand,
-    hex::Hex,
-    view::{style_focus, style_normal},
+    InteractiveCommand, ViewToggle,
+    binary_view::{BinaryView, ByteOrder},
+    disasm_cache::DisassemblyCache,
+    dump,
+    focus::FocusRing,
+    hash::HashAlgorithm,
+    hex::{GroupBytes, Hex, HexState},
+    keymap::Action,
+    open::companion_save,
+    patch::Patches,
+    theme::Theme,
+    view::{
+        PaneAreas, PaneScroll, Split, list_index_at, render_lazy_list, render_list_scrollbar,
+        render_scrolled_table,
+    },
 };
 
 #[derive(PartialEq, Clone, Copy)]
 enum Focus {
-    None,
     Restarts,
     Interrupts,
     Header,
     Banks,
+    BankData,
 }
 
-static FOCUS_CYCLE_ORDER: [Focus; 4] = [
+static FOCUS_CYCLE_ORDER: [Focus; 5] = [
     Focus::Restarts,
     Focus::Interrupts,
     Focus::Header,
     Focus::Banks,
+    Focus::BankData,
 ];
 
+/// How the bytes of the selected bank are shown.
+#[derive(PartialEq, Clone, Copy)]
+enum BankView {
+    Hex,
+    Disassembly,
+    Tiles,
+}
+
+/// A disassembled code vector, one instruction per line, or the error
+/// message if it can't be disassembled.
+type Disassembly = Result<Vec<String>, String>;
+
 /// Cached disassembles that are only computed once
 struct GBDisassembles {
-    entry_point: Vec<String>,
+    entry_point: Disassembly,
 
-    interrupt_v_blank: Vec<String>,
-    interrupt_lcd_stat: Vec<String>,
-    interrupt_timer: Vec<String>,
-    interrupt_serial: Vec<String>,
-    interrupt_joypad: Vec<String>,
-    rst_0: Vec<String>,
-    rst_1: Vec<String>,
-    rst_2: Vec<String>,
-    rst_3: Vec<String>,
-    rst_4: Vec<String>,
-    rst_5: Vec<String>,
-    rst_6: Vec<String>,
-    rst_7: Vec<String>,
+    interrupt_v_blank: Disassembly,
+    interrupt_lcd_stat: Disassembly,
+    interrupt_timer: Disassembly,
+    interrupt_serial: Disassembly,
+    interrupt_joypad: Disassembly,
+    rst_0: Disassembly,
+    rst_1: Disassembly,
+    rst_2: Disassembly,
+    rst_3: Disassembly,
+    rst_4: Disassembly,
+    rst_5: Disassembly,
+    rst_6: Disassembly,
+    rst_7: Disassembly,
+}
 
-    banks: Vec<Vec<String>>,
+impl GBDisassembles {
+    fn all(&self) -> [&Disassembly; 14] {
+        [
+            &self.entry_point,
+            &self.interrupt_v_blank,
+            &self.interrupt_lcd_stat,
+            &self.interrupt_timer,
+            &self.interrupt_serial,
+            &self.interrupt_joypad,
+            &self.rst_0,
+            &self.rst_1,
+            &self.rst_2,
+            &self.rst_3,
+            &self.rst_4,
+            &self.rst_5,
+            &self.rst_6,
+            &self.rst_7,
+        ]
+    }
 }
 
 pub struct GBInteractiveState {
-    previous_focus: Focus,
-    focus_on: Focus,
+    focus: FocusRing<Focus>,
     bank_list_state: ListState,
-    show_bank_disassemble: bool,
+    // selected vector in the detail of the restarts and interrupts
+    restart_table_state: TableState,
+    interrupt_table_state: TableState,
+    // of the header table in the detail pane
+    header_scroll: PaneScroll,
+    bank_hex_state: HexState,
+    bank_count: usize,
+    bank_view: BankView,
+    // first shown tile row and column of the tiles view
+    tile_scroll: (usize, usize),
     disassembles: GBDisassembles,
+    // banks containing a jp/call target of the entry point or a vector
+    referenced_banks: BTreeSet<usize>,
+    // disassembled on first display, keyed by bank
+    bank_disassembles: DisassemblyCache<usize, Instruction>,
+    bank_disassemble_list_state: ListState,
+    // file offset of the instruction to select once the bank is disassembled
+    pending_instruction: Option<usize>,
+    // updated on render, used for paging the disassembly
+    detail_page_rows: usize,
+    // checksums computed over the raw ROM bytes
+    expected_checksum: u8,
+    expected_global_checksum: u16,
+    file_size: usize,
+    // the .sav next to the ROM and its size
+    save_file: Option<(PathBuf, u64)>,
+    pane_areas: PaneAreas<Focus>,
+    split: Split,
 }
 
 impl GBInteractiveState {
-    pub fn new(binary: &GBBinary) -> GBInteractiveState {
+    pub fn new(
+        binary: &GBBinary,
+        data: &[u8],
+        save_file: Option<(PathBuf, u64)>,
+    ) -> GBInteractiveState {
         let mut bank_list_state = ListState::default();
         bank_list_state.select(Some(0));
-
-        let mut banks = Vec::with_capacity(binary.bank_data.len());
-        for bank in &binary.bank_data {
-            banks.push(disassemble(bank));
-        }
+        let disassembles = GBDisassembles {
+            entry_point: disassemble(&binary.header.entry_point, 0x100),
+            interrupt_v_blank: disassemble(&binary.interrupts.v_blank, 0x40),
+            interrupt_lcd_stat: disassemble(&binary.interrupts.lcd_stat, 0x48),
+            interrupt_timer: disassemble(&binary.interrupts.timer, 0x50),
+            interrupt_serial: disassemble(&binary.interrupts.serial, 0x58),
+            interrupt_joypad: disassemble(&binary.interrupts.joypad, 0x60),
+            rst_0: disassemble(&binary.restart_calls.rst_0, 0x00),
+            rst_1: disassemble(&binary.restart_calls.rst_1, 0x08),
+            rst_2: disassemble(&binary.restart_calls.rst_2, 0x10),
+            rst_3: disassemble(&binary.restart_calls.rst_3, 0x18),
+            rst_4: disassemble(&binary.restart_calls.rst_4, 0x20),
+            rst_5: disassemble(&binary.restart_calls.rst_5, 0x28),
+            rst_6: disassemble(&binary.restart_calls.rst_6, 0x30),
+            rst_7: disassemble(&binary.restart_calls.rst_7, 0x38),
+        };
+        let referenced_banks = referenced_banks(&disassembles, binary.bank_data.len());
 
         GBInteractiveState {
             bank_list_state,
-            previous_focus: Focus::None,
-            focus_on: Focus::Header,
-            show_bank_disassemble: false,
-            disassembles: GBDisassembles {
-                entry_point: disassemble(&binary.header.entry_point),
-                interrupt_v_blank: disassemble(&binary.interrupts.v_blank),
-                interrupt_lcd_stat: disassemble(&binary.interrupts.lcd_stat),
-                interrupt_timer: disassemble(&binary.interrupts.timer),
-                interrupt_serial: disassemble(&binary.interrupts.serial),
-                interrupt_joypad: disassemble(&binary.interrupts.joypad),
-                rst_0: disassemble(&binary.restart_calls.rst_0),
-                rst_1: disassemble(&binary.restart_calls.rst_1),
-                rst_2: disassemble(&binary.restart_calls.rst_2),
-                rst_3: disassemble(&binary.restart_calls.rst_3),
-                rst_4: disassemble(&binary.restart_calls.rst_4),
-                rst_5: disassemble(&binary.restart_calls.rst_5),
-                rst_6: disassemble(&binary.restart_calls.rst_6),
-                rst_7: disassemble(&binary.restart_calls.rst_7),
-                banks,
-            },
+            restart_table_state: TableState::default().with_selected(Some(0)),
+            interrupt_table_state: TableState::default().with_selected(Some(0)),
+            header_scroll: PaneScroll::default(),
+            bank_hex_state: HexState::default(),
+            bank_count: binary.bank_data.len(),
+            focus: FocusRing::new(&FOCUS_CYCLE_ORDER, Focus::Header),
+            bank_view: BankView::Hex,
+            tile_scroll: (0, 0),
+            bank_disassembles: DisassemblyCache::new(),
+            bank_disassemble_list_state: ListState::default().with_selected(Some(0)),
+            pending_instruction: None,
+            detail_page_rows: 0,
+            expected_checksum: header_checksum(data),
+            expected_global_checksum: global_checksum(data),
+            file_size: data.len(),
+            save_file,
+            pane_areas: PaneAreas::new(),
+            split: Split::default(),
+            disassembles,
+            referenced_banks,
         }
     }
 
-    pub fn handle_command(&mut self, command: InteractiveCommand) {
+    pub fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
         match command {
-            InteractiveCommand::Key(key) => {
-                match key {
-                    KeyCode::Tab => self.move_focus(1),
-                    KeyCode::BackTab => self.move_focus(-1),
-                    KeyCode::Down => {
-                        if self.focus_on == Focus::Banks {
-                            self.bank_list_state.select_next();
+            InteractiveCommand::Action(action) => {
+                match action {
+                    Action::FocusNext => self.focus.next(),
+                    Action::FocusPrev => self.focus.prev(),
+                    Action::WidenFilePane => self.split.widen_file_pane(),
+                    Action::NarrowFilePane => self.split.narrow_file_pane(),
+                    Action::SelectNext => match self.focus.current() {
+                        Some(Focus::Restarts) => self.restart_table_state.select_next(),
+                        Some(Focus::Interrupts) => self.interrupt_table_state.select_next(),
+                        Some(Focus::Banks) => {
+                            let selected = self.bank_list_state.selected().unwrap_or(0);
+                            self.select_bank((selected + 1).min(self.bank_count.saturating_sub(1)));
+                        }
+                        Some(Focus::BankData) => match self.bank_view {
+                            BankView::Hex => self.bank_hex_state.scroll_down(1),
+                            BankView::Disassembly => self.bank_disassemble_list_state.select_next(),
+                            BankView::Tiles => self.tile_scroll.0 += 1,
+                        },
+                        _ => { /* ignore */ }
+                    },
+                    Action::SelectPrev => match self.focus.current() {
+                        Some(Focus::Restarts) => self.restart_table_state.select_previous(),
+                        Some(Focus::Interrupts) => self.interrupt_table_state.select_previous(),
+                        Some(Focus::Banks) => {
+                            let selected = self.bank_list_state.selected().unwrap_or(0);
+                            self.select_bank(selected.saturating_sub(1));
+                        }
+                        Some(Focus::BankData) => match self.bank_view {
+                            BankView::Hex => self.bank_hex_state.scroll_up(1),
+                            BankView::Disassembly => {
+                                self.bank_disassemble_list_state.select_previous()
+                            }
+                            BankView::Tiles => {
+                                self.tile_scroll.0 = self.tile_scroll.0.saturating_sub(1)
+                            }
+                        },
+                        _ => { /* ignore */ }
+                    },
+                    Action::PageDown if self.focus.is(Focus::BankData) => match self.bank_view {
+                        BankView::Hex => self.bank_hex_state.page_down(),
+                        BankView::Disassembly => {
+                            let rows = self.detail_page_rows.max(1) as u16;
+                            self.bank_disassemble_list_state.scroll_down_by(rows);
+                        }
+                        BankView::Tiles => {
+                            self.tile_scroll.0 += (self.detail_page_rows / TILE_LINES).max(1)
+                        }
+                    },
+                    Action::PageUp if self.focus.is(Focus::BankData) => match self.bank_view {
+                        BankView::Hex => self.bank_hex_state.page_up(),
+                        BankView::Disassembly => {
+                            let rows = self.detail_page_rows.max(1) as u16;
+                            self.bank_disassemble_list_state.scroll_up_by(rows);
+                        }
+                        BankView::Tiles => {
+                            let rows = (self.detail_page_rows / TILE_LINES).max(1);
+                            self.tile_scroll.0 = self.tile_scroll.0.saturating_sub(rows);
+                        }
+                    },
+                    Action::Home | Action::End if self.focus.is(Focus::BankData) => {
+                        let end = action == Action::End;
+                        match self.bank_view {
+                            BankView::Hex if end => self.bank_hex_state.scroll_to_end(),
+                            BankView::Hex => self.bank_hex_state.scroll_to_start(),
+                            BankView::Disassembly if end => {
+                                self.bank_disassemble_list_state.select_last()
+                            }
+                            BankView::Disassembly => {
+                                self.bank_disassemble_list_state.select_first()
+                            }
+                            // clamped to the last tile row on render
+                            BankView::Tiles => {
+                                self.tile_scroll.0 = if end { usize::MAX } else { 0 }
+                            }
                         }
                     }
-                    KeyCode::Up => {
-                        if self.focus_on == Focus::Banks {
-                            self.bank_list_state.select_previous();
+                    Action::PageDown | Action::PageUp | Action::Home | Action::End => {
+                        match self.focus.current() {
+                            Some(Focus::Restarts) => page_table(
+                                &mut self.restart_table_state,
+                                action,
+                                self.pane_areas.page_rows(Focus::Restarts),
+                            ),
+                            Some(Focus::Interrupts) => page_table(
+                                &mut self.interrupt_table_state,
+                                action,
+                                self.pane_areas.page_rows(Focus::Interrupts),
+                            ),
+                            Some(Focus::Header) => self.header_scroll.handle_action(action),
+                            Some(Focus::Banks) => {
+                                let last = self.bank_count.saturating_sub(1);
+                                let selected = self.bank_list_state.selected().unwrap_or(0);
+                                let page = self.pane_areas.page_rows(Focus::Banks).max(1);
+                                let bank = match action {
+                                    Action::PageDown => selected.saturating_add(page).min(last),
+                                    Action::PageUp => selected.saturating_sub(page),
+                                    Action::Home => 0,
+                                    _ => last,
+                                };
+                                self.select_bank(bank);
+                            }
+                            _ => { /* ignore */ }
+                        }
+                    }
+                    Action::Left if self.focus.is(Focus::BankData) => match self.bank_view {
+                        BankView::Hex => self.bank_hex_state.scroll_left(),
+                        BankView::Tiles => {
+                            self.tile_scroll.1 = self.tile_scroll.1.saturating_sub(1)
+                        }
+                        BankView::Disassembly => { /* ignore */ }
+                    },
+                    Action::Right if self.focus.is(Focus::BankData) => match self.bank_view {
+                        BankView::Hex => self.bank_hex_state.scroll_right(),
+                        BankView::Tiles => {
+                            self.tile_scroll.1 = (self.tile_scroll.1 + 1).min(TILES_PER_ROW - 1)
                         }
+                        BankView::Disassembly => { /* ignore */ }
+                    },
+                    Action::ToggleDisassemble
+                        if matches!(self.focus.current(), Some(Focus::Banks | Focus::BankData)) =>
+                    {
+                        self.bank_view = match self.bank_view {
+                            BankView::Disassembly => BankView::Hex,
+                            _ => BankView::Disassembly,
+                        };
+                        self.bank_disassemble_list_state.select(Some(0));
                     }
                     _ => { /* ignore */ }
                 }
             }
-            InteractiveCommand::Focus => {
-                self.focus_on = self.previous_focus;
-            }
-            InteractiveCommand::Unfocus => {
-                self.previous_focus = self.focus_on;
-                self.focus_on = Focus::None;
+            InteractiveCommand::Focus => self.focus.focus(),
+            InteractiveCommand::Unfocus => self.focus.unfocus(),
+            InteractiveCommand::Mouse(mouse) => self.handle_mouse(mouse),
+            InteractiveCommand::Toggle(ViewToggle::Tiles) => {
+                let message = if self.toggle_tiles() {
+                    "bank shown as 2bpp tiles, arrows pan, :tiles shows the hex again"
+                } else {
+                    "bank shown as hex"
+                };
+                return Some(message.to_string());
             }
+            InteractiveCommand::Toggle(_) => { /* ignore */ }
         }
+        None
     }
 
-    fn move_focus(&mut self, dir: isize) {
-        let mut ix_focus = 0;
-        for i in 0..FOCUS_CYCLE_ORDER.len() {
-            if FOCUS_CYCLE_ORDER[i] == self.focus_on {
-                ix_focus = i as isize;
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let Some((pane, area)) = self.pane_areas.pane_at(mouse.column, mouse.row) else {
+            return;
+        };
+        self.focus.set(pane);
+        if pane == Focus::Banks {
+            let clicked = list_index_at(area, &self.bank_list_state, mouse.row);
+            if let Some(ix) = clicked.filter(|ix| *ix < self.bank_count)
+                && self.bank_list_state.selected() != Some(ix)
+            {
+                self.select_bank(ix);
             }
         }
-        ix_focus += dir;
-        let ix = if ix_focus < 0 {
-            (FOCUS_CYCLE_ORDER.len() as isize + ix_focus) as usize
-        } else {
-            ix_focus as usize % FOCUS_CYCLE_ORDER.len()
+    }
+
+    /// Selects the bank and shows it from its start.
+    fn select_bank(&mut self, bank: usize) {
+        self.bank_list_state.select(Some(bank));
+        self.bank_hex_state.reset();
+        self.bank_disassemble_list_state.select(Some(0));
+        self.tile_scroll = (0, 0);
+    }
+
+    /// Switches the selected bank between the tiles and the hex view,
+    /// returns true if the tiles are shown now.
+    pub fn toggle_tiles(&mut self) -> bool {
+        self.bank_view = match self.bank_view {
+            BankView::Tiles => BankView::Hex,
+            _ => BankView::Tiles,
         };
+        self.tile_scroll = (0, 0);
+        self.focus.set_active(Focus::BankData);
+        self.bank_view == BankView::Tiles
+    }
+
+    pub fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.bank_hex_state.set_group_bytes(group_bytes);
+    }
+
+    pub fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.bank_hex_state.set_byte_colors(byte_colors);
+    }
+
+    /// Drops the disassembled banks, they are disassembled again from the
+    /// edited bytes when shown. The vectors lie in the header and aren't
+    /// editable in the bank views.
+    pub fn clear_disassemblies(&mut self) {
+        self.bank_disassembles.clear();
+    }
+
+    pub fn relayout(&mut self) {
+        self.bank_hex_state.relayout();
+    }
+
+    /// The hex view of the selected bank, if it is (or was before unfocusing)
+    /// in focus.
+    pub fn focused_hex(&mut self) -> Option<&mut HexState> {
+        match self.focus.active() {
+            Focus::Banks | Focus::BankData if self.bank_view == BankView::Hex => {
+                Some(&mut self.bank_hex_state)
+            }
+            _ => None,
+        }
+    }
+
+    /// The lines of the shown bank disassembly and the state of its list.
+    pub fn shown_disassembly(&mut self) -> Option<(Vec<&str>, &mut ListState)> {
+        match self.focus.active() {
+            Focus::Banks | Focus::BankData if self.bank_view == BankView::Disassembly => {
+                let bank = self.bank_list_state.selected()?;
+                let instructions = self.bank_disassembles.get(&bank)?;
+                let lines = instructions.iter().map(|i| i.text.as_str()).collect();
+                Some((lines, &mut self.bank_disassemble_list_state))
+            }
+            _ => None,
+        }
+    }
+
+    /// The instruction selected in the shown bank disassembly.
+    fn selected_instruction(&self) -> Option<&Instruction> {
+        match self.focus.active() {
+            Focus::Banks | Focus::BankData if self.bank_view == BankView::Disassembly => {
+                let bank = self.bank_list_state.selected()?;
+                let instructions = self.bank_disassembles.get(&bank)?;
+                instructions.get(self.bank_disassemble_list_state.selected()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// File offset of the target of the selected `jp`, `call` or `jr`
+    /// instruction, if it is static and in the ROM. A switchable bank target
+    /// is assumed to be in the bank of the instruction (bank 1 from bank 0).
+    pub fn selected_jump_target(&self) -> Option<usize> {
+        let instruction = self.selected_instruction()?;
+        let target = instruction.target? as usize;
+        if target < BANK_BYTES {
+            return Some(target);
+        }
+        if target >= 2 * BANK_BYTES {
+            return None;
+        }
+        let rom_bank = (instruction.offset / BANK_BYTES).max(1);
+        Some(rom_bank * BANK_BYTES + target - BANK_BYTES)
+    }
+
+    /// True if the header pane is (or was before unfocusing) in focus.
+    pub fn header_focused(&self) -> bool {
+        self.focus.active() == Focus::Header
+    }
 
-        self.focus_on = FOCUS_CYCLE_ORDER[ix];
+    /// Computes the expected checksums again after the ROM bytes changed.
+    pub fn update_checksums(&mut self, data: &[u8]) {
+        self.expected_checksum = header_checksum(data);
+        self.expected_global_checksum = global_checksum(data);
     }
+
+    /// File offset of the start of the bank in the focused hex view.
+    pub fn focused_hex_start(&self) -> Option<usize> {
+        match self.focus.active() {
+            Focus::Banks | Focus::BankData if self.bank_view == BankView::Hex => {
+                self.bank_list_state.selected().map(bank_offset)
+            }
+            _ => None,
+        }
+    }
+
+    /// File offset of the first byte shown in the focused bank hex view, or
+    /// of the selected instruction in the bank disassembly.
+    pub fn shown_offset(&self) -> Option<usize> {
+        if let Some(instruction) = self.selected_instruction() {
+            return Some(instruction.offset);
+        }
+        Some(self.focused_hex_start()? + self.bank_hex_state.top_offset())
+    }
+
+    /// File offset of the edit cursor in the focused bank hex view, or of its
+    /// first shown byte while not editing.
+    pub fn inspected_offset(&self) -> Option<usize> {
+        let position = self
+            .bank_hex_state
+            .cursor()
+            .unwrap_or(self.bank_hex_state.top_offset());
+        Some(self.focused_hex_start()? + position)
+    }
+
+    /// Selects the bank containing the file `offset` and scrolls its hex view
+    /// to it, or selects the instruction at it if the disassembly is shown.
+    /// Returns false if the offset is not inside a bank.
+    pub fn show_offset(&mut self, offset: usize) -> bool {
+        if offset < DATA_START {
+            return false;
+        }
+        let bank = (offset - DATA_START) / BANK_BYTES;
+        if bank >= self.bank_count {
+            return false;
+        }
+
+        self.bank_list_state.select(Some(bank));
+        self.focus.set_active(Focus::BankData);
+        if self.bank_view == BankView::Disassembly {
+            // selected on render, the bank may not be disassembled yet
+            self.pending_instruction = Some(offset);
+            return true;
+        }
+        self.bank_hex_state.reset();
+        self.bank_hex_state
+            .scroll_to_offset(offset - bank_offset(bank));
+        self.bank_view = BankView::Hex;
+        true
+    }
+
+    /// File byte range of the selected bank.
+    pub fn selected_region(&self, binary: &GBBinary) -> Option<Range<usize>> {
+        let bank = self.bank_list_state.selected()?;
+        let data = binary.bank_data.get(bank)?;
+        Some(bank_offset(bank)..(bank_offset(bank) + data.len()))
+    }
+
+    pub fn focus_index(&self) -> usize {
+        self.focus.index()
+    }
+
+    pub fn set_focus_index(&mut self, ix: usize) {
+        self.focus.set_index(ix);
+    }
+}
+
+/// Disassembles the code of a vector at the CPU `address`, each line
+/// prefixed with the address of the instruction.
+fn disassemble(data: &[u8], address: usize) -> Disassembly {
+    let dis = gb::disassemble(data).map_err(|err| format!("Err disassemble: {}", err))?;
+    let mut address = address;
+    let mut lines = Vec::with_capacity(dis.instructions.len());
+    for i in &dis.instructions {
+        lines.push(format!("{:04X}  {}", address, i.instr.text(None)));
+        address += i.len;
+    }
+    Ok(lines)
+}
+
+/// The target address of an absolute `jp` or `call` instruction, e.g.
+/// `call nz, $0150`. Register jumps like `jp hl` have no static target.
+fn jump_target(instr: &str) -> Option<u16> {
+    let instr = instr.trim().to_ascii_lowercase();
+    let (mnemonic, operands) = instr.split_once(char::is_whitespace)?;
+    if mnemonic != "jp" && mnemonic != "call" {
+        return None;
+    }
+    let target = operands.rsplit(',').next()?.trim();
+    let hex = target
+        .strip_prefix('$')
+        .or_else(|| target.strip_prefix("0x"))
+        .or_else(|| target.strip_suffix('h'))
+        .unwrap_or(target);
+    u16::from_str_radix(hex, 16).ok()
+}
+
+/// The target address of a `jp`, `call` or `jr` instruction. `next` is the
+/// CPU address after the instruction, a `jr` printed with a signed
+/// displacement (`jr nz, -5`) is relative to it.
+fn branch_target(instr: &str, next: u16) -> Option<u16> {
+    let lower = instr.trim().to_ascii_lowercase();
+    let Some(operands) = lower.strip_prefix("jr") else {
+        return jump_target(instr);
+    };
+    let target = operands.rsplit(',').next()?.trim();
+    if target.starts_with(['+', '-']) {
+        let displacement = target.parse::<i8>().ok()?;
+        return Some(next.wrapping_add_signed(displacement as i16));
+    }
+    jump_target(&format!("jp {}", target))
+}
+
+/// Banks that contain a `jp`/`call` target of the entry point or one of the
+/// vectors. A heuristic: targets in the switchable area (0x4000-0x7FFF) are
+/// assumed to be in ROM bank 1, the bank mapped at boot, so that the CPU
+/// address equals the file offset.
+fn referenced_banks(disassembles: &GBDisassembles, bank_count: usize) -> BTreeSet<usize> {
+    let mut result = BTreeSet::new();
+    for lines in disassembles.all().into_iter().flatten() {
+        for line in lines {
+            let Some((_, instr)) = line.split_once("  ") else {
+                continue;
+            };
+            let Some(target) = jump_target(instr) else {
+                continue;
+            };
+            let offset = target as usize;
+            if !(DATA_START..2 * BANK_BYTES).contains(&offset) {
+                continue;
+            }
+            let bank = (offset - DATA_START) / BANK_BYTES;
+            if bank < bank_count {
+                result.insert(bank);
+            }
+        }
+    }
+    result
+}
+
+/// A table row with one line per instruction, errors in red.
+fn disassembly_row<'a>(label: &'a str, disassembly: &Disassembly) -> Row<'a> {
+    match disassembly {
+        Ok(lines) => {
+            Row::new(vec![label.to_string(), lines.join("\n")]).height(lines.len().max(1) as u16)
+        }
+        Err(err) => {
+            Row::new(vec![label.to_string(), err.clone()]).style(Style::default().fg(Color::Red))
+        }
+    }
+}
+
+/// An instruction of a bank disassembly.
+struct Instruction {
+    /// File offset of the instruction
+    offset: usize,
+    /// The shown line, prefixed with the ROM bank and the CPU address
+    /// ("01:4000") of the instruction
+    text: String,
+    /// CPU address of the `jp`, `call` or `jr` target, if it is static
+    target: Option<u16>,
 }
 
-fn disassemble(data: &[u8]) -> Vec<String> {
+/// Disassembles the bank data. An error is a single line at the start of the
+/// bank.
+fn disassemble_bank(bank: usize, data: &[u8]) -> Vec<Instruction> {
+    let mut offset = bank_offset(bank);
     match gb::disassemble(data) {
-        Err(err) => vec![format!("Err disassemble: {}", err)],
-        Ok(dis) => dis,
+        Err(err) => vec![Instruction {
+            offset,
+            text: format!("Err disassemble: {}", err),
+            target: None,
+        }],
+        Ok(dis) => {
+            let mut instructions = Vec::with_capacity(dis.instructions.len());
+            for i in &dis.instructions {
+                let text = i.instr.text(None);
+                let next = cpu_address_value(offset + i.len);
+                instructions.push(Instruction {
+                    offset,
+                    target: branch_target(&text, next),
+                    text: format!("{}  {}", cpu_address(offset), text),
+                });
+                offset += i.len;
+            }
+            instructions
+        }
+    }
+}
+
+/// The ROM bank and CPU address of a file offset. Bank 0 is mapped at 0x0000,
+/// the switchable banks at 0x4000.
+fn cpu_address(file_offset: usize) -> String {
+    format!(
+        "{:02X}:{:04X}",
+        file_offset / BANK_BYTES,
+        cpu_address_value(file_offset)
+    )
+}
+
+/// The CPU address of a file offset without the ROM bank.
+fn cpu_address_value(file_offset: usize) -> u16 {
+    if file_offset < BANK_BYTES {
+        file_offset as u16
+    } else {
+        (BANK_BYTES + file_offset % BANK_BYTES) as u16
+    }
+}
+
+/// A GameBoy ROM and the state of its view.
+pub struct GBView {
+    pub gb_binary: GBBinary,
+    pub state: GBInteractiveState,
+}
+
+impl GBView {
+    pub fn new(gb_binary: GBBinary, data: &[u8]) -> GBView {
+        let state = GBInteractiveState::new(&gb_binary, data, None);
+        GBView { gb_binary, state }
+    }
+}
+
+impl BinaryView for GBView {
+    fn type_name(&self) -> &'static str {
+        "GameBoy ROM"
+    }
+
+    fn summary(&self) -> String {
+        let header = &self.gb_binary.header;
+        format!(
+            "GameBoy ROM, {}, {:?}",
+            header.game_title.trim_end_matches('\0'),
+            header.cartridge_type
+        )
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        Some(ByteOrder::Little)
+    }
+
+    fn dump(&self, data: &[u8], out: &mut String) {
+        dump::dump_gb(out, &self.gb_binary, data);
+    }
+
+    fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        data: &[u8],
+        patches: &Patches,
+        theme: &Theme,
+    ) {
+        let mut widget = GBWidget::new(&self.gb_binary, data, patches, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command)
+    }
+
+    /// Parses the ROM again from the edited data, e.g. after its header
+    /// changed.
+    fn reparse(&mut self, data: &[u8]) -> Result<(), String> {
+        self.gb_binary = mule_gb::load(data)?;
+        self.state.update_checksums(data);
+        Ok(())
+    }
+
+    fn header_focused(&self) -> bool {
+        self.state.header_focused()
+    }
+
+    fn set_path(&mut self, path: &Path) {
+        self.state.save_file = companion_save(path);
+    }
+
+    /// A bank by "bank<N>", e.g. "bank3".
+    fn named_region(&self, name: &str) -> Option<Range<usize>> {
+        let bank = name.strip_prefix("bank")?.parse::<usize>().ok()?;
+        let start = bank_offset(bank);
+        Some(start..start.saturating_add(self.gb_binary.bank_data.get(bank)?.len()))
+    }
+
+    fn describe_offset(&self, offset: usize) -> Option<String> {
+        if let Some(field) = header_region_at(offset) {
+            return Some(format!("header, {}", field));
+        }
+        let bank = bank_at(offset).filter(|b| *b < self.gb_binary.bank_data.len())?;
+        Some(format!("bank {} +0x{:X}", bank, offset - bank_offset(bank)))
+    }
+
+    fn default_hash(&self) -> HashAlgorithm {
+        HashAlgorithm::Crc32
+    }
+
+    fn focus_index(&self) -> usize {
+        self.state.focus_index()
+    }
+
+    fn set_focus_index(&mut self, ix: usize) {
+        self.state.set_focus_index(ix);
+    }
+
+    fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.state.set_hex_group_bytes(group_bytes);
+    }
+
+    fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.state.set_hex_byte_colors(byte_colors);
+    }
+
+    fn clear_disassemblies(&mut self) {
+        self.state.clear_disassemblies();
+    }
+
+    fn relayout(&mut self) {
+        self.state.relayout();
+    }
+
+    fn focused_hex(&mut self) -> Option<&mut HexState> {
+        self.state.focused_hex()
+    }
+
+    fn selected_region(&self) -> Option<Range<usize>> {
+        self.state.selected_region(&self.gb_binary)
+    }
+
+    fn focused_hex_start(&self) -> Option<usize> {
+        self.state.focused_hex_start()
+    }
+
+    fn shown_offset(&self) -> Option<usize> {
+        self.state.shown_offset()
+    }
+
+    fn inspected_offset(&self) -> Option<usize> {
+        self.state.inspected_offset()
+    }
+
+    fn shown_disassembly(&mut self) -> Option<(Vec<&str>, &mut ListState)> {
+        self.state.shown_disassembly()
+    }
+
+    fn selected_jump_target(&self) -> Option<usize> {
+        self.state.selected_jump_target()
+    }
+
+    fn show_offset(&mut self, offset: usize) -> bool {
+        self.state.show_offset(offset)
     }
 }
 
 pub struct GBWidget<'a> {
     pub gb_binary: &'a GBBinary,
+    /// The (possibly edited) bytes of the ROM
+    pub data: &'a [u8],
+    pub patches: &'a Patches,
+    pub theme: &'a Theme,
     pub state: &'a mut GBInteractiveState,
 }
 
 impl<'a> GBWidget<'a> {
-    pub fn new(gb_binary: &'a GBBinary, state: &'a mut GBInteractiveState) -> GBWidget<'a> {
-        GBWidget { gb_binary, state }
+    pub fn new(
+        gb_binary: &'a GBBinary,
+        data: &'a [u8],
+        patches: &'a Patches,
+        theme: &'a Theme,
+        state: &'a mut GBInteractiveState,
+    ) -> GBWidget<'a> {
+        GBWidget {
+            gb_binary,
+            data,
+            patches,
+            theme,
+            state,
+        }
     }
 
     fn focus_style(&self, focus: Focus) -> Style {
-        if self.state.focus_on == focus {
-            style_focus()
+        if self.state.focus.is(focus) {
+            self.theme.style_focus()
         } else {
-            style_normal()
+            self.theme.style_normal()
         }
     }
 
-    fn render_detail_view(&self, content_detail: Rect, buf: &mut Buffer) {
+    fn render_detail_view(&mut self, content_detail: Rect, buf: &mut Buffer) {
         let detail_block = Block::bordered()
             .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::BankData))
             .title("Details");
 
-        match self.state.focus_on {
-            Focus::None => { /* do nothing */ }
+        let Some(focus) = self.state.focus.current() else {
+            return;
+        };
+        match focus {
             Focus::Restarts => self.render_restart_detail(detail_block, content_detail, buf),
             Focus::Interrupts => self.render_interrupt_detail(detail_block, content_detail, buf),
             Focus::Header => self.render_header_detail(detail_block, content_detail, buf),
-            Focus::Banks => {
+            Focus::Banks | Focus::BankData => {
                 let selected = self.state.bank_list_state.selected();
-                if let Some(selected_pos) = selected {
-                    if self.state.show_bank_disassemble {
-                        let txt = self.state.disassembles.banks[selected_pos].join("\n");
-                        let p = Paragraph::new(txt);
-                        p.render(content_detail, buf);
-                    } else {
-                        let bank = &self.gb_binary.bank_data[selected_pos];
-                        let hex = &Hex::new(bank).block(detail_block);
-                        hex.render_ref(content_detail, buf);
+                let Some(selected_pos) = selected.filter(|s| *s < self.gb_binary.bank_data.len())
+                else {
+                    detail_block.render(content_detail, buf);
+                    return;
+                };
+                let bank = &self.gb_binary.bank_data[selected_pos];
+                if self.state.bank_view == BankView::Tiles {
+                    let detail_block =
+                        detail_block.title(format!("Bank {} (Tiles, :tiles: Hex)", selected_pos));
+                    let inner = detail_block.inner(content_detail);
+                    self.state.detail_page_rows = inner.height as usize;
+                    let start = bank_offset(selected_pos).min(self.data.len());
+                    let end = (start + bank.len()).min(self.data.len());
+                    let lines = tile_lines(&self.data[start..end]);
+                    // keep the last tile row at the bottom
+                    let page_rows = inner.height as usize / TILE_LINES;
+                    let max_row = (lines.len() / TILE_LINES).saturating_sub(page_rows.max(1));
+                    self.state.tile_scroll.0 = self.state.tile_scroll.0.min(max_row);
+                    let (row, column) = self.state.tile_scroll;
+                    Paragraph::new(lines.join("\n"))
+                        .block(detail_block)
+                        .scroll(((row * TILE_LINES) as u16, (column * TILE_WIDTH) as u16))
+                        .render(content_detail, buf);
+                } else if self.state.bank_view == BankView::Disassembly {
+                    let detail_block = detail_block.title(format!(
+                        "Bank {} (Disasm, d: Hex, Enter: follow jump)",
+                        selected_pos
+                    ));
+                    self.state.detail_page_rows =
+                        detail_block.inner(content_detail).height as usize;
+                    // the edited bytes, not the parsed bank
+                    let start = bank_offset(selected_pos).min(self.data.len());
+                    let end = (start + bank.len()).min(self.data.len());
+                    let bytes = &self.data[start..end];
+                    let instructions = self
+                        .state
+                        .bank_disassembles
+                        .get_or_disassemble(selected_pos, || disassemble_bank(selected_pos, bytes));
+                    if let Some(offset) = self.state.pending_instruction.take() {
+                        // the last instruction starting at or before the offset
+                        let ix = instructions.partition_point(|i| i.offset <= offset);
+                        self.state
+                            .bank_disassemble_list_state
+                            .select(Some(ix.saturating_sub(1)));
                     }
+                    render_lazy_list(
+                        detail_block,
+                        instructions.len(),
+                        |i| instructions[i].text.clone(),
+                        content_detail,
+                        buf,
+                        &mut self.state.bank_disassemble_list_state,
+                        self.theme,
+                    );
+                } else {
+                    let detail_block =
+                        detail_block.title(format!("Bank {} (Hex, d: Disasm)", selected_pos));
+                    let start = bank_offset(selected_pos).min(self.data.len());
+                    let end = (start + bank.len()).min(self.data.len());
+                    let modified: Vec<(Range<usize>, Style)> = self
+                        .patches
+                        .modified_ranges(start..end)
+                        .into_iter()
+                        .map(|range| (range, Style::default().fg(Color::Yellow)))
+                        .collect();
+                    let hex = Hex::new(&self.data[start..end])
+                        .block(detail_block)
+                        .base_offset(start)
+                        .highlights(&modified);
+                    StatefulWidget::render(
+                        &hex,
+                        content_detail,
+                        buf,
+                        &mut self.state.bank_hex_state,
+                    );
                 }
             }
         }
     }
 
-    fn render_restart_detail(&self, block: Block, content_detail: Rect, buf: &mut Buffer) {
-        let rst_0 = self.state.disassembles.rst_0.join("");
-        let rst_1 = self.state.disassembles.rst_1.join("");
-        let rst_2 = self.state.disassembles.rst_2.join("");
-        let rst_3 = self.state.disassembles.rst_3.join("");
-        let rst_4 = self.state.disassembles.rst_4.join("");
-        let rst_5 = self.state.disassembles.rst_5.join("");
-        let rst_6 = self.state.disassembles.rst_6.join("");
-        let rst_7 = self.state.disassembles.rst_7.join("");
+    fn render_restart_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let disassembles = &self.state.disassembles;
         let rows = [
-            Row::new(vec!["RST 0:", &rst_0]),
-            Row::new(vec!["RST 1:", &rst_1]),
-            Row::new(vec!["RST 2:", &rst_2]),
-            Row::new(vec!["RST 3:", &rst_3]),
-            Row::new(vec!["RST 4:", &rst_4]),
-            Row::new(vec!["RST 5:", &rst_5]),
-            Row::new(vec!["RST 6:", &rst_6]),
-            Row::new(vec!["RST 7:", &rst_7]),
+            disassembly_row("RST 0:", &disassembles.rst_0),
+            disassembly_row("RST 1:", &disassembles.rst_1),
+            disassembly_row("RST 2:", &disassembles.rst_2),
+            disassembly_row("RST 3:", &disassembles.rst_3),
+            disassembly_row("RST 4:", &disassembles.rst_4),
+            disassembly_row("RST 5:", &disassembles.rst_5),
+            disassembly_row("RST 6:", &disassembles.rst_6),
+            disassembly_row("RST 7:", &disassembles.rst_7),
         ];
 
         let widths = [Constraint::Length(7), Constraint::Fill(1)];
-        let table = Table::new(rows, widths).block(block);
-        Widget::render(table, content_detail, buf);
+        let table = Table::new(rows, widths)
+            .block(block)
+            .row_highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            table,
+            content_detail,
+            buf,
+            &mut self.state.restart_table_state,
+        );
     }
 
-    fn render_interrupt_detail(&self, block: Block, content_detail: Rect, buf: &mut Buffer) {
-        let v_blank = self.state.disassembles.interrupt_v_blank.join("");
-        let lcd_stat = self.state.disassembles.interrupt_lcd_stat.join("");
-        let timer = self.state.disassembles.interrupt_timer.join("");
-        let serial = self.state.disassembles.interrupt_serial.join("");
-        let joypad = self.state.disassembles.interrupt_joypad.join("");
+    fn render_interrupt_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let disassembles = &self.state.disassembles;
         let rows = [
-            Row::new(vec!["V-Blank:", &v_blank]),
-            Row::new(vec!["LCD-Stat:", &lcd_stat]),
-            Row::new(vec!["Timer:", &timer]),
-            Row::new(vec!["Serial:", &serial]),
-            Row::new(vec!["Joypad:", &joypad]),
+            disassembly_row("V-Blank:", &disassembles.interrupt_v_blank),
+            disassembly_row("LCD-Stat:", &disassembles.interrupt_lcd_stat),
+            disassembly_row("Timer:", &disassembles.interrupt_timer),
+            disassembly_row("Serial:", &disassembles.interrupt_serial),
+            disassembly_row("Joypad:", &disassembles.interrupt_joypad),
         ];
 
         let widths = [Constraint::Length(10), Constraint::Fill(1)];
-        let table = Table::new(rows, widths).block(block);
-        Widget::render(table, content_detail, buf);
+        let table = Table::new(rows, widths)
+            .block(block)
+            .row_highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            table,
+            content_detail,
+            buf,
+            &mut self.state.interrupt_table_state,
+        );
     }
 
-    fn render_header_detail(&self, block: Block, content_detail: Rect, buf: &mut Buffer) {
-        let entry_text = self.state.disassembles.entry_point.join("");
-        let logo_row_0_text = &logo_row(0, &self.gb_binary.header.logo_data);
-        let logo_row_1_text = &logo_row(1, &self.gb_binary.header.logo_data);
-        let logo_row_2_text = &logo_row(2, &self.gb_binary.header.logo_data);
-        let logo_row_3_text = &logo_row(3, &self.gb_binary.header.logo_data);
+    fn render_header_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let logo_lines = block_lines(&logo_pixels(&self.gb_binary.header.logo_data));
         let manufacturer_text = manufacturer_display(&self.gb_binary.header.manufacturer_code);
-        let licensee_text = &format!("{:?}", self.gb_binary.header.licensee_code);
-        let cartridge_text = &format!("{:?}", self.gb_binary.header.cartridge_type);
+        let licensee_text = &self.gb_binary.header.licensee_code.to_string();
+        let cartridge_text = &cartridge_description(self.gb_binary.header.cartridge_type);
         let rom_text = rom_display(self.gb_binary.header.rom_size);
+        let declared_bytes = declared_rom_bytes(self.gb_binary.header.rom_size);
+        let rom_size_matches = declared_bytes == self.state.file_size;
+        let rom_size_check_text = &if rom_size_matches {
+            "matches the file size".to_string()
+        } else {
+            format!(
+                "declared {} KiB but file is {} KiB",
+                declared_bytes / 1024,
+                self.state.file_size / 1024
+            )
+        };
+        let rom_size_check_style = if rom_size_matches {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Red)
+        };
         let ram_text = ram_display(self.gb_binary.header.ram_size);
+        let save = save_bytes(&self.gb_binary.header);
+        let save_text = &save_display(self.gb_binary.header.cartridge_type, save);
+        let (save_file_text, save_file_style) = match (save, &self.state.save_file) {
+            (_, Some((path, len))) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                match save {
+                    Some(expected) if *len != expected as u64 => (
+                        format!(
+                            "{} ({} bytes, expected {}), :sav opens it",
+                            name, len, expected
+                        ),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    _ => (
+                        format!("{} ({} bytes), :sav opens it", name, len),
+                        Style::default(),
+                    ),
+                }
+            }
+            (Some(_), None) => ("none next to the ROM".to_string(), Style::default()),
+            (None, None) => (String::new(), Style::default()),
+        };
+        let warnings = header_warnings(&self.gb_binary.header);
         let dest_text = dest_code_display(self.gb_binary.header.destination_code);
         let rom_version_text = &format!("{}", self.gb_binary.header.rom_version);
-        let checksum_text = &format!("{}", self.gb_binary.header.checksum);
-        let global_checksum_text = &format!("{}", self.gb_binary.header.global_checksum);
-        let rows = [
-            Row::new(vec!["Logo:", logo_row_0_text]),
-            Row::new(vec!["     ", logo_row_1_text]),
-            Row::new(vec!["     ", logo_row_2_text]),
-            Row::new(vec!["     ", logo_row_3_text]),
+        let checksum_text = &checksum_display(
+            self.gb_binary.header.checksum as u16,
+            self.state.expected_checksum as u16,
+            2,
+        );
+        let global_checksum_text = &checksum_display(
+            self.gb_binary.header.global_checksum,
+            self.state.expected_global_checksum,
+            4,
+        );
+        let logo_valid = self.gb_binary.header.is_logo_valid();
+        let logo_style = if logo_valid {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let mut rows = vec![
+            Row::new(vec!["Logo:", &logo_lines[0]]).style(logo_style),
+            Row::new(vec!["     ", &logo_lines[1]]).style(logo_style),
+            Row::new(vec!["     ", &logo_lines[2]]).style(logo_style),
+            Row::new(vec!["     ", &logo_lines[3]]).style(logo_style),
+            Row::new(vec!["     ", if logo_valid { "VALID" } else { "MODIFIED" }])
+                .style(logo_style),
             Row::new(vec!["", ""]),
-            Row::new(vec!["Entry Point:", &entry_text]),
+            disassembly_row("Entry Point:", &self.state.disassembles.entry_point),
             Row::new(vec!["Game Title:", &self.gb_binary.header.game_title]),
             Row::new(vec!["Manufacturer Code:", manufacturer_text]),
             Row::new(vec![
@@ -276,35 +1079,132 @@ impl<'a> GBWidget<'a> {
             ]),
             Row::new(vec!["Cartridge Type:", cartridge_text]),
             Row::new(vec!["ROM Size: ", rom_text]),
+            Row::new(vec!["", rom_size_check_text]).style(rom_size_check_style),
             Row::new(vec!["RAM Size:", ram_text]),
+            Row::new(vec!["Save RAM:", save_text]),
+        ];
+        if !save_file_text.is_empty() {
+            rows.push(Row::new(vec!["Save File:", save_file_text.as_str()]).style(save_file_style));
+        }
+        // inconsistent with what the cartridge type can address
+        rows.extend(
+            warnings
+                .iter()
+                .map(|w| Row::new(vec!["", w.as_str()]).style(Style::default().fg(Color::Yellow))),
+        );
+        rows.extend([
             Row::new(vec!["Destination Code:", dest_text]),
             Row::new(vec!["ROM Version:", rom_version_text]),
             Row::new(vec!["Checksum:", checksum_text]),
             Row::new(vec!["Global Checksum:", global_checksum_text]),
-        ];
+        ]);
         let widths = [Constraint::Length(22), Constraint::Fill(1)];
-        let table = Table::new(rows, widths).block(block);
-        Widget::render(table, content_detail, buf);
-    }
-}
-
-fn logo_row(row: usize, logo_data: &[u8]) -> String {
-    let mut result = String::new();
-    let dis = row % 2;
-    let offset = if row >= 2 { 24 } else { 0 };
-    for i in (0..24).step_by(2) {
-        let b = logo_data[offset + i + dis];
-        let l0 = (b & 0xF0) >> 4;
-        let l1 = b & 0xF;
-        for s in (0..2).rev() {
-            let mask = 0b11 << (s * 2);
-            let l0_r = (l0 & mask) >> (s * 2);
-            let l1_r = (l1 & mask) >> (s * 2);
-            result.push(pixel_char(l0_r, l1_r))
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.header_scroll,
+        );
+    }
+}
+
+/// Moves the selection of a vector table by a page or to its first or last
+/// vector.
+fn page_table(table: &mut TableState, action: Action, page_rows: usize) {
+    let rows = page_rows.max(1) as u16;
+    match action {
+        Action::PageDown => table.scroll_down_by(rows),
+        Action::PageUp => table.scroll_up_by(rows),
+        Action::Home => table.select_first(),
+        Action::End => table.select_last(),
+        _ => { /* ignore */ }
+    }
+}
+
+pub fn checksum_display(checksum: u16, expected: u16, digits: usize) -> String {
+    if checksum == expected {
+        format!("0x{:0digits$X} OK", checksum)
+    } else {
+        format!(
+            "0x{:0digits$X} BAD (expected 0x{:0digits$X})",
+            checksum, expected
+        )
+    }
+}
+
+/// The 48x8 pixels of the logo. The logo consists of 4x4 pixel tiles, the
+/// top half first. Each byte holds two rows of a tile, one per nibble.
+fn logo_pixels(logo_data: &[u8]) -> Vec<Vec<bool>> {
+    let mut pixels = vec![vec![false; 48]; 8];
+    for (y, row) in pixels.iter_mut().enumerate() {
+        let half_offset = if y >= 4 { 24 } else { 0 };
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let b = logo_data
+                .get(half_offset + x / 4 * 2 + y % 4 / 2)
+                .copied()
+                .unwrap_or(0);
+            let nibble = if y % 2 == 0 { b >> 4 } else { b & 0xF };
+            *pixel = nibble & (0b1000 >> (x % 4)) != 0;
         }
     }
+    pixels
+}
 
-    result
+const TILE_BYTES: usize = 16;
+const TILES_PER_ROW: usize = 16;
+// text lines of a tile, each line shows two pixel rows
+const TILE_LINES: usize = 4;
+// 4 block characters and a space to the next tile
+const TILE_WIDTH: usize = 4 + 1;
+
+/// Decodes `data` as 2bpp tiles and lays them out in rows of
+/// `TILES_PER_ROW` tiles. Pixels of color 0 are blank, all others set.
+fn tile_lines(data: &[u8]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for row in data.chunks(TILE_BYTES * TILES_PER_ROW) {
+        let tiles: Vec<Vec<String>> = row
+            .chunks(TILE_BYTES)
+            .map(|tile| block_lines(&tile_pixels(tile)))
+            .collect();
+        for line in 0..TILE_LINES {
+            let texts: Vec<&str> = tiles.iter().map(|t| t[line].as_str()).collect();
+            lines.push(texts.join(" "));
+        }
+    }
+    lines
+}
+
+/// The 8x8 pixels of a 2bpp tile, set if the color isn't 0. Each pixel row
+/// is stored in two bytes, the low bits of the colors first, the leftmost
+/// pixel in the highest bit. A truncated tile is padded with color 0.
+fn tile_pixels(tile: &[u8]) -> Vec<Vec<bool>> {
+    (0..8)
+        .map(|y| {
+            let low = tile.get(y * 2).copied().unwrap_or(0);
+            let high = tile.get(y * 2 + 1).copied().unwrap_or(0);
+            (0..8).map(|x| (low | high) & (0x80 >> x) != 0).collect()
+        })
+        .collect()
+}
+
+/// Renders the pixels with one block character per 2x2 pixels. Width and
+/// height have to be even.
+fn block_lines(pixels: &[Vec<bool>]) -> Vec<String> {
+    pixels
+        .chunks(2)
+        .map(|rows| {
+            (0..rows[0].len())
+                .step_by(2)
+                .map(|x| {
+                    let top = (rows[0][x] as u8) << 1 | rows[0][x + 1] as u8;
+                    let bottom = (rows[1][x] as u8) << 1 | rows[1][x + 1] as u8;
+                    pixel_char(top, bottom)
+                })
+                .collect()
+        })
+        .collect()
 }
 
 fn pixel_char(l0: u8, l1: u8) -> char {
@@ -345,14 +1245,49 @@ fn pixel_char(l0: u8, l1: u8) -> char {
     }
 }
 
-fn dest_code_display(dest_code: DestinationCode) -> &'static str {
+pub fn cartridge_description(cartridge_type: CartridgeType) -> String {
+    let description = match cartridge_type {
+        CartridgeType::ROMOnly => "ROM only",
+        CartridgeType::MBC1 => "MBC1",
+        CartridgeType::MBC1xRAM => "MBC1 + RAM",
+        CartridgeType::MBC1xRAMxBattery => "MBC1 + RAM + Battery",
+        CartridgeType::MBC2 => "MBC2",
+        CartridgeType::MBC2xBattery => "MBC2 + Battery",
+        CartridgeType::ROMxRAM => "ROM + RAM",
+        CartridgeType::ROMxRAMxBattery => "ROM + RAM + Battery",
+        CartridgeType::MMM01 => "MMM01",
+        CartridgeType::MMM01xRAM => "MMM01 + RAM",
+        CartridgeType::MMM01xRAMxBattery => "MMM01 + RAM + Battery",
+        CartridgeType::MBC3xTimerxBattery => "MBC3 + Timer + Battery",
+        CartridgeType::MBC3xTimerxRAMxBattery => "MBC3 + Timer + RAM + Battery",
+        CartridgeType::MBC3 => "MBC3",
+        CartridgeType::MBC3xRAM => "MBC3 + RAM",
+        CartridgeType::MBC3xRAMxBattery => "MBC3 + RAM + Battery",
+        CartridgeType::MBC5 => "MBC5",
+        CartridgeType::MBC5xRAM => "MBC5 + RAM",
+        CartridgeType::MBC5xRAMxBattery => "MBC5 + RAM + Battery",
+        CartridgeType::MBC5xRumble => "MBC5 + Rumble",
+        CartridgeType::MBC5xRumblexRAM => "MBC5 + Rumble + RAM",
+        CartridgeType::MBC5xRumblexRAMxBattery => "MBC5 + Rumble + RAM + Battery",
+        CartridgeType::MBC6 => "MBC6",
+        CartridgeType::MBC7xSensorxRumblexRAMxBattery => "MBC7 + Sensor + Rumble + RAM + Battery",
+        CartridgeType::PocketCamera => "Pocket Camera",
+        CartridgeType::BandaiTama5 => "Bandai TAMA5",
+        CartridgeType::HuC3 => "HuC3",
+        CartridgeType::HuC1xRAMxBattery => "HuC1 + RAM + Battery",
+        CartridgeType::Unknown(t) => return format!("Unknown (0x{:02X})", t),
+    };
+    description.to_string()
+}
+
+pub fn dest_code_display(dest_code: DestinationCode) -> &'static str {
     match dest_code {
         DestinationCode::Japanese => "Japanese",
         DestinationCode::NonJapanese => "No Japanese",
     }
 }
 
-fn ram_display(ram: RAMSize) -> &'static str {
+pub fn ram_display(ram: RAMSize) -> &'static str {
     match ram {
         RAMSize::None => "No RAM",
         RAMSize::KB2 => "2 KiB",
@@ -363,7 +1298,20 @@ fn ram_display(ram: RAMSize) -> &'static str {
     }
 }
 
-fn rom_display(rom: ROMSize) -> &'static str {
+/// The battery-backed save of the cartridge type, `save` is its size in
+/// bytes from `save_bytes`.
+pub fn save_display(cartridge_type: CartridgeType, save: Option<usize>) -> String {
+    match save {
+        None => "No battery".to_string(),
+        Some(0) => "Clock only, no RAM".to_string(),
+        Some(bytes) if matches!(cartridge_type, CartridgeType::MBC2xBattery) => {
+            format!("{} bytes .sav (MBC2 512x4 bit)", bytes)
+        }
+        Some(bytes) => format!("{} bytes .sav", bytes),
+    }
+}
+
+pub fn rom_display(rom: ROMSize) -> &'static str {
     match rom {
         ROMSize::NoBanking => "No Banking (32KiB)",
         ROMSize::Banks4 => "4 Banks (64 KiB)",
@@ -380,11 +1328,11 @@ fn rom_display(rom: ROMSize) -> &'static str {
     }
 }
 
-fn manufacturer_display<'a>(code: &'a str) -> &'a str {
+pub fn manufacturer_display<'a>(code: &'a str) -> &'a str {
     if code.is_empty() { &"-" } else { &code }
 }
 
-fn gbc_flag_display(gbc_flag: GBCFlag) -> &'static str {
+pub fn gbc_flag_display(gbc_flag: GBCFlag) -> &'static str {
     match gbc_flag {
         GBCFlag::GBOnly => "GB only",
         GBCFlag::GBCAndGB => "GB & GBC",
@@ -392,7 +1340,7 @@ fn gbc_flag_display(gbc_flag: GBCFlag) -> &'static str {
     }
 }
 
-fn sgb_flag_display(sgb_flag: SGBFlag) -> &'static str {
+pub fn sgb_flag_display(sgb_flag: SGBFlag) -> &'static str {
     match sgb_flag {
         SGBFlag::NoSGB => "No support",
         SGBFlag::SGBSupport => "Supported",
@@ -401,8 +1349,7 @@ fn sgb_flag_display(sgb_flag: SGBFlag) -> &'static str {
 
 impl<'a> Widget for &mut GBWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let content_layout =
-            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]);
+        let content_layout = Layout::horizontal(self.state.split.constraints());
         let [content_file, content_detail] = content_layout.areas(area);
 
         let file_layout = Layout::vertical([
@@ -413,6 +1360,20 @@ impl<'a> Widget for &mut GBWidget<'a> {
         ]);
         let [gb_restarts, gb_interrupts, gb_header, gb_banks] = file_layout.areas(content_file);
 
+        let pane_areas = &mut self.state.pane_areas;
+        pane_areas.clear();
+        pane_areas.push(Focus::Restarts, gb_restarts);
+        pane_areas.push(Focus::Interrupts, gb_interrupts);
+        pane_areas.push(Focus::Header, gb_header);
+        pane_areas.push(Focus::Banks, gb_banks);
+        if matches!(
+            self.state.focus.current(),
+            Some(Focus::Banks | Focus::BankData)
+        ) {
+            // the detail pane only shows the bank data if a bank pane is focused
+            pane_areas.push(Focus::BankData, content_detail);
+        }
+
         let restart_block = Block::bordered()
             .border_type(BorderType::Plain)
             .style(self.focus_style(Focus::Restarts))
@@ -440,8 +1401,9 @@ impl<'a> Widget for &mut GBWidget<'a> {
             .style(self.focus_style(Focus::Header))
             .title("Header");
         Paragraph::new(format!(
-            "title:{} | type:{:?}",
-            self.gb_binary.header.game_title, self.gb_binary.header.cartridge_type
+            "title:{} | type:{}",
+            self.gb_binary.header.game_title,
+            cartridge_description(self.gb_binary.header.cartridge_type)
         ))
         .block(header_block)
         .render(gb_header, buf);
@@ -449,15 +1411,18 @@ impl<'a> Widget for &mut GBWidget<'a> {
         let bank_block = Block::bordered()
             .border_type(BorderType::Plain)
             .style(self.focus_style(Focus::Banks))
-            .title(format!(
-                "Banks ({})",
-                num_banks(self.gb_binary.header.rom_size)
-            ));
+            .title(format!("Banks ({})", self.gb_binary.bank_data.len()));
 
-        let cmd_list = List::new(bank_list(self.gb_binary))
+        let cmd_list = List::new(bank_list(self.gb_binary, &self.state.referenced_banks))
             .block(bank_block)
-            .highlight_style(Style::new().black().on_white());
+            .highlight_style(self.theme.style_highlight());
         StatefulWidget::render(cmd_list, gb_banks, buf, &mut self.state.bank_list_state);
+        render_list_scrollbar(
+            gb_banks,
+            buf,
+            self.gb_binary.bank_data.len(),
+            &self.state.bank_list_state,
+        );
 
         self.render_detail_view(content_detail, buf);
     }
@@ -521,11 +1486,61 @@ fn default_vector(data: &[u8]) -> bool {
     true
 }
 
-fn bank_list(binary: &GBBinary) -> Vec<String> {
-    let n = num_banks(binary.header.rom_size);
+fn bank_list(binary: &GBBinary, referenced_banks: &BTreeSet<usize>) -> Vec<String> {
+    let n = binary.bank_data.len();
     let mut result = Vec::with_capacity(n);
     for i in 0..n {
-        result.push(format!("Bank {}", i));
+        if referenced_banks.contains(&i) {
+            result.push(format!("Bank {} (referenced)", i));
+        } else {
+            result.push(format!("Bank {}", i));
+        }
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{buffer, buffer_text, gb_rom};
+
+    fn bank_detail_text(binary: &GBBinary, selected: Option<usize>) -> String {
+        let data = gb_rom();
+        let mut state = GBInteractiveState::new(binary, &data, None);
+        state.focus.set(Focus::Banks);
+        state.bank_list_state.select(selected);
+        let patches = Patches::default();
+        let theme = Theme::default();
+        let mut buf = buffer(80, 10);
+        let mut widget = GBWidget::new(binary, &data, &patches, &theme, &mut state);
+        widget.render_detail_view(buf.area, &mut buf);
+        buffer_text(&buf)
+    }
+
+    #[test]
+    fn detail_of_the_last_bank() {
+        let binary = mule_gb::load(&gb_rom()).unwrap();
+        let last = binary.bank_data.len() - 1;
+        let text = bank_detail_text(&binary, Some(last));
+        assert!(text.contains(&format!("Bank {}", last)));
+    }
+
+    #[test]
+    fn detail_past_the_last_bank_is_empty() {
+        let binary = mule_gb::load(&gb_rom()).unwrap();
+        let text = bank_detail_text(&binary, Some(binary.bank_data.len()));
+        assert!(text.contains("Details"));
+        assert!(!text.contains("Bank"));
+    }
+
+    #[test]
+    fn detail_without_banks_is_empty() {
+        let mut binary = mule_gb::load(&gb_rom()).unwrap();
+        binary.bank_data.clear();
+        for selected in [None, Some(0)] {
+            let text = bank_detail_text(&binary, selected);
+            assert!(text.contains("Details"));
+            assert!(!text.contains("Bank"));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::{Display, LowerExp};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    widgets::{Block, BorderType, Paragraph, Row, Table, Widget},
+};
+
+use crate::{binary_view::ByteOrder, hex::ascii_char, theme::Theme};
+
+/// Width of the inspector column, including the border.
+pub const INSPECTOR_WIDTH: u16 = 58;
+
+// floats with an exponent outside of this range are shown in scientific notation
+const MIN_PLAIN_FLOAT: f64 = 1e-4;
+const MAX_PLAIN_FLOAT: f64 = 1e16;
+
+/// Interprets the bytes at `offset` as integers and floats of both
+/// endiannesses, like the inspector of a hex editor. The byte order of the
+/// file (if known) is shown in the first column.
+pub struct InspectorWidget<'a> {
+    data: &'a [u8],
+    offset: Option<usize>,
+    byte_order: Option<ByteOrder>,
+    theme: &'a Theme,
+}
+
+impl<'a> InspectorWidget<'a> {
+    pub fn new(
+        data: &'a [u8],
+        offset: Option<usize>,
+        byte_order: Option<ByteOrder>,
+        theme: &'a Theme,
+    ) -> InspectorWidget<'a> {
+        InspectorWidget {
+            data,
+            offset,
+            byte_order,
+            theme,
+        }
+    }
+}
+
+impl<'a> Widget for InspectorWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.theme.style_normal());
+        let Some(offset) = self.offset.filter(|o| *o < self.data.len()) else {
+            Paragraph::new("no hex view in focus")
+                .block(block.title("Inspector"))
+                .render(area, buf);
+            return;
+        };
+        let bytes = &self.data[offset..];
+
+        let big_first = self.byte_order == Some(ByteOrder::Big);
+        let rows = inspector_rows(bytes).into_iter().map(|(name, le, be)| {
+            let (first, second) = if big_first { (be, le) } else { (le, be) };
+            Row::new(vec![name.to_string(), first, second])
+        });
+        let header = Row::new(vec![
+            String::new(),
+            column_title(self.byte_order, big_first),
+            column_title(self.byte_order, !big_first),
+        ])
+        .style(self.theme.style_header());
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ];
+        Table::new(rows, widths)
+            .header(header)
+            .block(block.title(format!("Inspector 0x{:X}", offset)))
+            .render(area, buf);
+    }
+}
+
+/// Title of the little (or big) endian column, marked if it is the byte order
+/// of the file.
+fn column_title(byte_order: Option<ByteOrder>, big: bool) -> String {
+    let (name, column) = if big {
+        ("big endian", ByteOrder::Big)
+    } else {
+        ("little endian", ByteOrder::Little)
+    };
+    if byte_order == Some(column) {
+        format!("{} (file)", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Name, little and big endian interpretation of the bytes, "-" if there
+/// are not enough bytes left for the type.
+fn inspector_rows(bytes: &[u8]) -> Vec<(&'static str, String, String)> {
+    let byte = bytes[0];
+    vec![
+        ("char", ascii_char(byte).to_string(), String::new()),
+        ("u8", byte.to_string(), String::new()),
+        ("i8", (byte as i8).to_string(), String::new()),
+        interpret("u16", bytes, u16::from_le_bytes, u16::from_be_bytes),
+        interpret("i16", bytes, i16::from_le_bytes, i16::from_be_bytes),
+        interpret("u32", bytes, u32::from_le_bytes, u32::from_be_bytes),
+        interpret("i32", bytes, i32::from_le_bytes, i32::from_be_bytes),
+        interpret("u64", bytes, u64::from_le_bytes, u64::from_be_bytes),
+        interpret("i64", bytes, i64::from_le_bytes, i64::from_be_bytes),
+        interpret_float("f32", bytes, f32::from_le_bytes, f32::from_be_bytes),
+        interpret_float("f64", bytes, f64::from_le_bytes, f64::from_be_bytes),
+    ]
+}
+
+fn interpret<const N: usize, T: ToString>(
+    name: &'static str,
+    bytes: &[u8],
+    le: fn([u8; N]) -> T,
+    be: fn([u8; N]) -> T,
+) -> (&'static str, String, String) {
+    match bytes.first_chunk::<N>() {
+        Some(chunk) => (name, le(*chunk).to_string(), be(*chunk).to_string()),
+        None => (name, "-".to_string(), "-".to_string()),
+    }
+}
+
+fn interpret_float<const N: usize, T: Into<f64> + Display + LowerExp + Copy>(
+    name: &'static str,
+    bytes: &[u8],
+    le: fn([u8; N]) -> T,
+    be: fn([u8; N]) -> T,
+) -> (&'static str, String, String) {
+    match bytes.first_chunk::<N>() {
+        Some(chunk) => (name, float_display(le(*chunk)), float_display(be(*chunk))),
+        None => (name, "-".to_string(), "-".to_string()),
+    }
+}
+
+/// Plain notation for "normal" magnitudes, scientific notation for very
+/// small or large values that would not fit the column otherwise.
+fn float_display<T: Into<f64> + Display + LowerExp + Copy>(value: T) -> String {
+    let wide: f64 = value.into();
+    let magnitude = wide.abs();
+    if wide == 0.0 || !wide.is_finite() || (MIN_PLAIN_FLOAT..MAX_PLAIN_FLOAT).contains(&magnitude) {
+        value.to_string()
+    } else {
+        format!("{:e}", value)
+    }
+}
@@ -1,172 +1,1689 @@
-use crossterm::event::KeyCode;
-use mule_macho::{LoadCommand, Macho, Section64};
+use std::ops::Range;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use mule_macho::{
+    BuildVersionCommand, CPUType, CodeSignature, CodeSignatureCommand, DylibKind, Endianness,
+    EntitlementValue, FileType, HEADER_SIZE, LoadCommand, LoadDylibCommand, Macho, MainCommand,
+    Section64, SourceVersionCommand, Symbol, SymbolType, UuidCommand,
+};
+use psy::dasm::{arm64, x86_64};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::Style,
-    widgets::{Block, BorderType, List, ListState, Paragraph, StatefulWidget, Widget},
+    style::{Color, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, Widget,
+        WidgetRef, Wrap,
+    },
 };
 
 use crate::{
-    InteractiveCommand,
-    view::{style_focus, style_normal},
+    InteractiveCommand, ViewToggle,
+    binary_view::{BinaryView, ByteOrder},
+    disasm_cache::DisassemblyCache,
+    dump,
+    focus::FocusRing,
+    hex::{GroupBytes, Hex, HexState},
+    keymap::Action,
+    open::cpu_type_str,
+    patch::Patches,
+    theme::Theme,
+    view::{
+        PaneAreas, PaneScroll, Split, list_index_at, page_list, render_lazy_list,
+        render_list_scrollbar, render_scrolled_table,
+    },
+    view_memmap::{MemoryMapWidget, segment_at},
 };
 
 #[derive(PartialEq, Copy, Clone)]
 enum Focus {
-    None,
     Header,
     LoadCommands,
+    Detail,
+}
+
+/// What the detail pane shows.
+#[derive(PartialEq, Copy, Clone)]
+enum DetailView {
+    /// The selected load command
+    Command,
+    MemoryMap,
+    /// The rpaths and the dylibs in load order
+    Dylibs,
+    /// The unused space between the sections of the selected segment
+    SectionGaps,
 }
 
-static FOCUS_CYCLE_ORDER: [Focus; 2] = [Focus::Header, Focus::LoadCommands];
+/// What the detail pane shows of the selected section of a segment.
+#[derive(PartialEq, Copy, Clone)]
+enum SectionView {
+    /// The section list with the fields of the selected section
+    Info,
+    Disassembly,
+    /// The file bytes of the selected section
+    Hex,
+}
+
+static FOCUS_CYCLE_ORDER: [Focus; 3] = [Focus::Header, Focus::LoadCommands, Focus::Detail];
 
 pub struct MachoInteractiveState {
-    previous_focus: Focus,
-    focus_on: Focus,
+    focus: FocusRing<Focus>,
     command_list_state: ListState,
+    command_count: usize,
+    detail_list_state: ListState,
+    // updated on render, used for paging in the detail list
+    detail_page_rows: usize,
+    // of the tables in the detail pane, e.g. of a long code signature
+    detail_scroll: PaneScroll,
+    section_view: SectionView,
+    disassemble_list_state: ListState,
+    section_hex_state: HexState,
+    // file byte ranges of the sections by load command index, empty for
+    // other commands and zerofill sections
+    section_ranges: Vec<Vec<Range<usize>>>,
+    /// Cached disassembles of `__text` sections that are only computed once,
+    /// keyed by (load command index, section index)
+    disassembles: DisassemblyCache<(usize, usize)>,
+    pane_areas: PaneAreas<Focus>,
+    split: Split,
+    // of the hex view of unknown commands
+    hex_group_bytes: GroupBytes,
+    hex_byte_colors: bool,
+    detail_view: DetailView,
+    // areas of the segments in the memory map by load command index, updated
+    // on render
+    memory_map_areas: Vec<(usize, Rect)>,
 }
 
 impl MachoInteractiveState {
-    pub fn new() -> MachoInteractiveState {
+    pub fn new(macho: &Macho) -> MachoInteractiveState {
         let mut command_list_state = ListState::default();
         command_list_state.select(Some(0));
+        let mut detail_list_state = ListState::default();
+        detail_list_state.select(Some(0));
         MachoInteractiveState {
             command_list_state,
-            previous_focus: Focus::None,
-            focus_on: Focus::LoadCommands,
+            command_count: macho.load_commands.len(),
+            detail_list_state,
+            detail_page_rows: 0,
+            detail_scroll: PaneScroll::default(),
+            section_view: SectionView::Info,
+            disassemble_list_state: ListState::default(),
+            section_hex_state: HexState::default(),
+            section_ranges: macho
+                .load_commands
+                .iter()
+                .map(|command| match command {
+                    LoadCommand::Segment64(segment) => {
+                        segment.sections.iter().map(section_file_range).collect()
+                    }
+                    _ => Vec::new(),
+                })
+                .collect(),
+            disassembles: DisassemblyCache::new(),
+            pane_areas: PaneAreas::new(),
+            split: Split::default(),
+            focus: FocusRing::new(&FOCUS_CYCLE_ORDER, Focus::LoadCommands),
+            hex_group_bytes: GroupBytes::default(),
+            hex_byte_colors: false,
+            detail_view: DetailView::Command,
+            memory_map_areas: Vec::new(),
         }
     }
 
-    pub fn handle_command(&mut self, command: InteractiveCommand) {
+    pub fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.hex_group_bytes = group_bytes;
+        self.section_hex_state.set_group_bytes(group_bytes);
+    }
+
+    pub fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.hex_byte_colors = byte_colors;
+        self.section_hex_state.set_byte_colors(byte_colors);
+    }
+
+    /// Drops the disassembled sections, they are disassembled again from the
+    /// edited bytes when shown.
+    pub fn clear_disassemblies(&mut self) {
+        self.disassembles.clear();
+    }
+
+    pub fn relayout(&mut self) {
+        self.section_hex_state.relayout();
+    }
+
+    /// Switches the detail pane between the selected command and the memory
+    /// map, returns true if the memory map is shown now.
+    pub fn toggle_memory_map(&mut self) -> bool {
+        self.toggle_detail_view(DetailView::MemoryMap)
+    }
+
+    /// Switches the detail pane between the selected command and the summary
+    /// of rpaths and loaded dylibs, returns true if the summary is shown now.
+    pub fn toggle_dylibs(&mut self) -> bool {
+        self.toggle_detail_view(DetailView::Dylibs)
+    }
+
+    /// Switches the detail pane between the selected command and the gaps
+    /// between the sections of the selected segment, returns true if the
+    /// gaps are shown now.
+    pub fn toggle_section_gaps(&mut self) -> bool {
+        self.toggle_detail_view(DetailView::SectionGaps)
+    }
+
+    fn toggle_detail_view(&mut self, view: DetailView) -> bool {
+        self.detail_view = if self.detail_view == view {
+            DetailView::Command
+        } else {
+            view
+        };
+        self.detail_scroll.reset();
+        self.detail_view == view
+    }
+
+    pub fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
         match command {
-            InteractiveCommand::Key(key) => {
-                match key {
-                    KeyCode::Tab => self.move_focus(1),
-                    KeyCode::BackTab => self.move_focus(-1),
-                    KeyCode::Down => {
-                        if self.focus_on == Focus::LoadCommands {
-                            self.command_list_state.select_next();
+            InteractiveCommand::Action(action) => {
+                match action {
+                    Action::FocusNext => self.focus.next(),
+                    Action::FocusPrev => self.focus.prev(),
+                    Action::WidenFilePane => self.split.widen_file_pane(),
+                    Action::NarrowFilePane => self.split.narrow_file_pane(),
+                    Action::SelectNext => match self.focus.current() {
+                        Some(Focus::LoadCommands) => {
+                            let selected = self.command_list_state.selected().unwrap_or(0);
+                            if selected + 1 < self.command_count {
+                                self.command_list_state.select_next();
+                                self.reset_detail();
+                            }
                         }
-                    }
-                    KeyCode::Up => {
-                        if self.focus_on == Focus::LoadCommands {
+                        Some(Focus::Detail) if self.section_view == SectionView::Hex => {
+                            self.section_hex_state.scroll_down(1)
+                        }
+                        Some(Focus::Detail) => self.detail_list_mut().select_next(),
+                        _ => { /* ignore */ }
+                    },
+                    Action::SelectPrev => match self.focus.current() {
+                        Some(Focus::LoadCommands)
+                            if self.command_list_state.selected().unwrap_or(0) > 0 =>
+                        {
                             self.command_list_state.select_previous();
+                            self.reset_detail();
+                        }
+                        Some(Focus::Detail) if self.section_view == SectionView::Hex => {
+                            self.section_hex_state.scroll_up(1)
+                        }
+                        Some(Focus::Detail) => self.detail_list_mut().select_previous(),
+                        _ => { /* ignore */ }
+                    },
+                    Action::PageDown | Action::PageUp | Action::Home | Action::End => {
+                        match self.focus.current() {
+                            Some(Focus::LoadCommands) => {
+                                let selected = self.command_list_state.selected();
+                                let rows = self.pane_areas.page_rows(Focus::LoadCommands);
+                                page_list(&mut self.command_list_state, action, rows);
+                                // the detail needs a selected command
+                                let last = self.command_count.saturating_sub(1);
+                                let paged = self.command_list_state.selected().map(|s| s.min(last));
+                                self.command_list_state.select(paged);
+                                if paged != selected {
+                                    self.reset_detail();
+                                }
+                            }
+                            Some(Focus::Detail) if self.section_view == SectionView::Hex => {
+                                match action {
+                                    Action::PageDown => self.section_hex_state.page_down(),
+                                    Action::PageUp => self.section_hex_state.page_up(),
+                                    Action::Home => self.section_hex_state.scroll_to_start(),
+                                    _ => self.section_hex_state.scroll_to_end(),
+                                }
+                            }
+                            Some(Focus::Detail) => {
+                                let rows = self.detail_page_rows;
+                                page_list(self.detail_list_mut(), action, rows);
+                                self.detail_scroll.handle_action(action);
+                            }
+                            _ => { /* ignore */ }
+                        }
+                    }
+                    Action::ToggleDisassemble if self.focus.is(Focus::Detail) => {
+                        self.section_view = if self.section_view == SectionView::Disassembly {
+                            SectionView::Info
+                        } else {
+                            SectionView::Disassembly
+                        };
+                        self.disassemble_list_state.select(Some(0));
+                    }
+                    Action::Confirm => {
+                        let section_shown = self.focus.is(Focus::Detail)
+                            && self.detail_view == DetailView::Command
+                            && self.selected_section_range().is_some();
+                        if section_shown {
+                            self.section_view = if self.section_view == SectionView::Hex {
+                                SectionView::Info
+                            } else {
+                                SectionView::Hex
+                            };
+                            self.section_hex_state.reset();
+                        }
+                    }
+                    Action::Left | Action::Right
+                        if self.focus.is(Focus::Detail)
+                            && self.section_view == SectionView::Hex =>
+                    {
+                        if action == Action::Left {
+                            self.section_hex_state.scroll_left()
+                        } else {
+                            self.section_hex_state.scroll_right()
                         }
                     }
                     _ => { /* ignore */ }
                 }
             }
-            InteractiveCommand::Focus => {
-                self.focus_on = self.previous_focus;
+            InteractiveCommand::Focus => self.focus.focus(),
+            InteractiveCommand::Unfocus => self.focus.unfocus(),
+            InteractiveCommand::Mouse(mouse) => self.handle_mouse(mouse),
+            InteractiveCommand::Toggle(toggle) => return self.toggle(toggle),
+        }
+        None
+    }
+
+    fn toggle(&mut self, toggle: ViewToggle) -> Option<String> {
+        let shown = match toggle {
+            ViewToggle::MemoryMap => self.toggle_memory_map(),
+            ViewToggle::Dylibs => self.toggle_dylibs(),
+            ViewToggle::SectionGaps => self.toggle_section_gaps(),
+            ViewToggle::Tiles => return None,
+        };
+        let message = if !shown {
+            "details of the selected command shown"
+        } else if toggle == ViewToggle::MemoryMap {
+            "segments shown as memory map, click one to select it, :memmap shows the details again"
+        } else if toggle == ViewToggle::Dylibs {
+            "rpaths and dylibs in load order shown, :dylibs shows the details again"
+        } else {
+            "gaps between the sections of the selected segment shown, larger than padding in red"
+        };
+        Some(message.to_string())
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let Some((pane, area)) = self.pane_areas.pane_at(mouse.column, mouse.row) else {
+            return;
+        };
+        self.focus.set(pane);
+        match pane {
+            Focus::LoadCommands => {
+                let clicked = list_index_at(area, &self.command_list_state, mouse.row);
+                if let Some(ix) = clicked.filter(|ix| *ix < self.command_count)
+                    && self.command_list_state.selected() != Some(ix)
+                {
+                    self.command_list_state.select(Some(ix));
+                    self.reset_detail();
+                }
+            }
+            Focus::Detail if self.detail_view == DetailView::MemoryMap => {
+                // focuses the clicked segment in the load command list
+                if let Some(ix) = segment_at(&self.memory_map_areas, mouse.column, mouse.row) {
+                    self.command_list_state.select(Some(ix));
+                    self.reset_detail();
+                    self.focus.set(Focus::LoadCommands);
+                }
             }
-            InteractiveCommand::Unfocus => {
-                self.previous_focus = self.focus_on;
-                self.focus_on = Focus::None;
+            Focus::Detail if self.section_view == SectionView::Hex => { /* ignore */ }
+            Focus::Detail => {
+                let list = self.detail_list_mut();
+                if let Some(ix) = list_index_at(area, list, mouse.row) {
+                    list.select(Some(ix));
+                }
             }
+            _ => { /* ignore */ }
         }
     }
 
-    fn move_focus(&mut self, dir: isize) {
-        let mut ix_focus = 0;
-        for i in 0..FOCUS_CYCLE_ORDER.len() {
-            if FOCUS_CYCLE_ORDER[i] == self.focus_on {
-                ix_focus = i as isize;
-            }
+    /// File byte range of the selected section if the detail pane is (or was
+    /// before unfocusing) in focus, otherwise of the selected segment.
+    pub fn selected_region(&self, macho: &Macho) -> Option<Range<usize>> {
+        let command = macho
+            .load_commands
+            .get(self.command_list_state.selected()?)?;
+        let LoadCommand::Segment64(segment) = command else {
+            return None;
+        };
+        if self.focus.active() == Focus::Detail {
+            self.selected_section_range()
+        } else {
+            let start = segment.file_off as usize;
+            Some(start..start.saturating_add(segment.file_size as usize))
         }
-        ix_focus += dir;
-        let ix = if ix_focus < 0 {
-            (FOCUS_CYCLE_ORDER.len() as isize + ix_focus) as usize
+    }
+
+    pub fn focus_index(&self) -> usize {
+        self.focus.index()
+    }
+
+    pub fn set_focus_index(&mut self, ix: usize) {
+        self.focus.set_index(ix);
+    }
+
+    /// The lines of the shown disassembly and the state of its list.
+    pub fn shown_disassembly(&mut self) -> Option<(Vec<&str>, &mut ListState)> {
+        if self.section_view != SectionView::Disassembly {
+            return None;
+        }
+        let key = (
+            self.command_list_state.selected()?,
+            self.detail_list_state.selected()?,
+        );
+        let lines = self.disassembles.get(&key)?;
+        let lines = lines.iter().map(String::as_str).collect();
+        Some((lines, &mut self.disassemble_list_state))
+    }
+
+    fn reset_detail(&mut self) {
+        self.detail_list_state.select(Some(0));
+        self.detail_scroll.reset();
+        self.section_view = SectionView::Info;
+    }
+
+    /// File byte range of the selected section of the selected segment.
+    fn selected_section_range(&self) -> Option<Range<usize>> {
+        let sections = self
+            .section_ranges
+            .get(self.command_list_state.selected()?)?;
+        sections.get(self.detail_list_state.selected()?).cloned()
+    }
+
+    /// The hex view of the selected section, if it is (or was before
+    /// unfocusing) in focus.
+    pub fn focused_hex(&mut self) -> Option<&mut HexState> {
+        self.focused_hex_start()?;
+        Some(&mut self.section_hex_state)
+    }
+
+    /// File offset of the start of the section in the focused hex view.
+    pub fn focused_hex_start(&self) -> Option<usize> {
+        let shown = self.focus.active() == Focus::Detail
+            && self.detail_view == DetailView::Command
+            && self.section_view == SectionView::Hex;
+        if !shown {
+            return None;
+        }
+        Some(self.selected_section_range()?.start)
+    }
+
+    /// File offset of the first byte shown in the focused section hex view.
+    pub fn shown_offset(&self) -> Option<usize> {
+        Some(self.focused_hex_start()? + self.section_hex_state.top_offset())
+    }
+
+    /// File offset of the edit cursor in the focused section hex view, or of
+    /// its first shown byte while not editing.
+    pub fn inspected_offset(&self) -> Option<usize> {
+        let position = self
+            .section_hex_state
+            .cursor()
+            .unwrap_or(self.section_hex_state.top_offset());
+        Some(self.focused_hex_start()? + position)
+    }
+
+    /// Selects the section containing the file `offset` and scrolls its hex
+    /// view to it. Returns false if the offset is not inside a section.
+    pub fn show_offset(&mut self, offset: usize) -> bool {
+        let found = self
+            .section_ranges
+            .iter()
+            .enumerate()
+            .find_map(|(i, sections)| {
+                let section = sections.iter().position(|r| r.contains(&offset))?;
+                Some((i, section))
+            });
+        let Some((command, section)) = found else {
+            return false;
+        };
+        self.command_list_state.select(Some(command));
+        self.reset_detail();
+        self.detail_view = DetailView::Command;
+        self.detail_list_state.select(Some(section));
+        self.section_view = SectionView::Hex;
+        self.section_hex_state.reset();
+        self.section_hex_state
+            .scroll_to_offset(offset - self.section_ranges[command][section].start);
+        self.focus.set_active(Focus::Detail);
+        true
+    }
+
+    fn detail_list_mut(&mut self) -> &mut ListState {
+        if self.section_view == SectionView::Disassembly {
+            &mut self.disassemble_list_state
         } else {
-            ix_focus as usize % FOCUS_CYCLE_ORDER.len()
+            &mut self.detail_list_state
+        }
+    }
+}
+
+/// A Mach-O binary and the state of its view.
+pub struct MachoView {
+    pub macho: Macho,
+    pub state: MachoInteractiveState,
+}
+
+impl MachoView {
+    pub fn new(macho: Macho) -> MachoView {
+        let state = MachoInteractiveState::new(&macho);
+        MachoView { macho, state }
+    }
+}
+
+impl BinaryView for MachoView {
+    fn type_name(&self) -> &'static str {
+        "Mach-O"
+    }
+
+    fn summary(&self) -> String {
+        let arch = cpu_type_str(self.macho.header.cpu_type);
+        let file_type = match self.macho.header.file_type {
+            FileType::MhObject => "object",
+            FileType::MhExecuted => "executable",
+            FileType::MhDSYM => "dSYM",
         };
+        format!("Mach-O, {}, {}", arch, file_type)
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        Some(match self.macho.endianness {
+            Endianness::Little => ByteOrder::Little,
+            Endianness::Big => ByteOrder::Big,
+        })
+    }
+
+    fn dump(&self, _: &[u8], out: &mut String) {
+        dump::dump_macho(out, &self.macho);
+    }
+
+    fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        data: &[u8],
+        patches: &Patches,
+        theme: &Theme,
+    ) {
+        let mut widget = MachoWidget::new(&self.macho, data, patches, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command)
+    }
+
+    /// A section by its name, e.g. "__text".
+    fn named_region(&self, name: &str) -> Option<Range<usize>> {
+        let section = self.macho.load_commands.iter().find_map(|cmd| match cmd {
+            LoadCommand::Segment64(segment) => segment.sections.iter().find(|s| s.name == name),
+            _ => None,
+        })?;
+        let start = section.offset as usize;
+        Some(start..start.saturating_add(section.size as usize))
+    }
+
+    fn describe_offset(&self, offset: usize) -> Option<String> {
+        let offset = offset as u64;
+        let within = |start: u64, len: u64| (start..start.saturating_add(len)).contains(&offset);
+        let commands_end = (HEADER_SIZE + self.macho.header.size_of_cmds) as u64;
+        if offset < HEADER_SIZE as u64 {
+            return Some(format!("Mach-O header +0x{:X}", offset));
+        }
+        if offset < commands_end {
+            return Some(format!(
+                "load commands +0x{:X}",
+                offset - HEADER_SIZE as u64
+            ));
+        }
+        let segments = self.macho.load_commands.iter().filter_map(|cmd| match cmd {
+            LoadCommand::Segment64(segment) => Some(segment),
+            _ => None,
+        });
+        for segment in segments {
+            if !within(segment.file_off, segment.file_size) {
+                continue;
+            }
+            let section = segment
+                .sections
+                .iter()
+                .find(|s| !s.is_zerofill() && within(s.offset as u64, s.size));
+            return Some(match section {
+                Some(section) => format!(
+                    "{},{} +0x{:X}",
+                    display_name(&segment.name),
+                    display_name(&section.name),
+                    offset - section.offset as u64
+                ),
+                None => format!(
+                    "{} +0x{:X}",
+                    display_name(&segment.name),
+                    offset - segment.file_off
+                ),
+            });
+        }
+        None
+    }
+
+    fn focus_index(&self) -> usize {
+        self.state.focus_index()
+    }
+
+    fn set_focus_index(&mut self, ix: usize) {
+        self.state.set_focus_index(ix);
+    }
 
-        self.focus_on = FOCUS_CYCLE_ORDER[ix];
+    fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.state.set_hex_group_bytes(group_bytes);
+    }
+
+    fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.state.set_hex_byte_colors(byte_colors);
+    }
+
+    fn clear_disassemblies(&mut self) {
+        self.state.clear_disassemblies();
+    }
+
+    fn relayout(&mut self) {
+        self.state.relayout();
+    }
+
+    fn focused_hex(&mut self) -> Option<&mut HexState> {
+        self.state.focused_hex()
+    }
+
+    fn selected_region(&self) -> Option<Range<usize>> {
+        self.state.selected_region(&self.macho)
+    }
+
+    fn focused_hex_start(&self) -> Option<usize> {
+        self.state.focused_hex_start()
+    }
+
+    fn shown_offset(&self) -> Option<usize> {
+        self.state.shown_offset()
+    }
+
+    fn inspected_offset(&self) -> Option<usize> {
+        self.state.inspected_offset()
+    }
+
+    fn shown_disassembly(&mut self) -> Option<(Vec<&str>, &mut ListState)> {
+        self.state.shown_disassembly()
+    }
+
+    fn show_offset(&mut self, offset: usize) -> bool {
+        self.state.show_offset(offset)
     }
 }
 
 pub struct MachoWidget<'a> {
     pub macho: &'a Macho,
+    pub data: &'a [u8],
+    pub patches: &'a Patches,
+    pub theme: &'a Theme,
     pub state: &'a mut MachoInteractiveState,
 }
 
 impl<'a> MachoWidget<'a> {
-    pub fn new(macho: &'a Macho, state: &'a mut MachoInteractiveState) -> MachoWidget<'a> {
-        MachoWidget { macho, state }
+    pub fn new(
+        macho: &'a Macho,
+        data: &'a [u8],
+        patches: &'a Patches,
+        theme: &'a Theme,
+        state: &'a mut MachoInteractiveState,
+    ) -> MachoWidget<'a> {
+        MachoWidget {
+            macho,
+            data,
+            patches,
+            theme,
+            state,
+        }
     }
 
     fn focus_style(&self, focus: Focus) -> Style {
-        if self.state.focus_on == focus {
-            style_focus()
+        if self.state.focus.is(focus) {
+            self.theme.style_focus()
         } else {
-            style_normal()
+            self.theme.style_normal()
         }
     }
+
+    fn render_detail(&mut self, content_detail: Rect, buf: &mut Buffer) {
+        let detail_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Detail))
+            .title("Details");
+        self.state.detail_page_rows = detail_block.inner(content_detail).height as usize;
+
+        let macho = self.macho;
+        let selected_pos = self.state.command_list_state.selected().unwrap_or(0);
+        match self.state.detail_view {
+            DetailView::Command => {}
+            DetailView::MemoryMap => {
+                let memory_map =
+                    MemoryMapWidget::new(macho, Some(selected_pos), detail_block, self.theme);
+                memory_map.render(content_detail, buf, &mut self.state.memory_map_areas);
+                return;
+            }
+            DetailView::Dylibs => {
+                render_dylibs_summary(
+                    macho,
+                    detail_block,
+                    content_detail,
+                    buf,
+                    &mut self.state.detail_scroll,
+                );
+                return;
+            }
+            DetailView::SectionGaps => {
+                render_section_gaps(
+                    macho,
+                    selected_pos,
+                    detail_block,
+                    content_detail,
+                    buf,
+                    &mut self.state.detail_scroll,
+                );
+                return;
+            }
+        }
+        let Some(command) = macho.load_commands.get(selected_pos) else {
+            detail_block.render(content_detail, buf);
+            return;
+        };
+        match command {
+            LoadCommand::Segment64(segment) => {
+                let selected_section = self
+                    .state
+                    .detail_list_state
+                    .selected()
+                    .and_then(|i| segment.sections.get(i).map(|s| (i, s)));
+                match (selected_section, self.state.section_view) {
+                    (Some((section_pos, section)), SectionView::Disassembly)
+                        if is_text_section(section) =>
+                    {
+                        self.render_disassemble(
+                            (selected_pos, section_pos),
+                            section,
+                            detail_block,
+                            content_detail,
+                            buf,
+                        );
+                    }
+                    (Some((_, section)), SectionView::Hex) => {
+                        self.render_section_hex(section, detail_block, content_detail, buf);
+                    }
+                    _ => {
+                        self.state.section_view = SectionView::Info;
+                        let section = self
+                            .state
+                            .detail_list_state
+                            .selected()
+                            .and_then(|i| segment.sections.get(i));
+                        let [list_area, section_area] = match section {
+                            Some(_) => Layout::vertical([
+                                Constraint::Min(3),
+                                Constraint::Length(SECTION_DETAIL_HEIGHT),
+                            ])
+                            .areas(content_detail),
+                            None => [content_detail, Rect::default()],
+                        };
+                        let sec_list = if segment.name == DWARF_SEGMENT {
+                            List::new(debug_section_list(&segment.sections)).block(
+                                detail_block.title_bottom(format!(
+                                    "Debug Info: {}",
+                                    debug_info_summary(macho)
+                                )),
+                            )
+                        } else {
+                            List::new(section_list(&segment.sections)).block(detail_block)
+                        }
+                        .highlight_style(self.theme.style_highlight());
+                        StatefulWidget::render(
+                            sec_list,
+                            list_area,
+                            buf,
+                            &mut self.state.detail_list_state,
+                        );
+                        render_list_scrollbar(
+                            list_area,
+                            buf,
+                            segment.sections.len(),
+                            &self.state.detail_list_state,
+                        );
+                        if let Some(section) = section {
+                            let section_block = Block::bordered()
+                                .border_type(BorderType::Plain)
+                                .style(self.focus_style(Focus::Detail))
+                                .title(format!(
+                                    "{},{}",
+                                    display_name(&section.seg_name),
+                                    display_name(&section.name)
+                                ));
+                            render_section_detail(section, section_block, section_area, buf);
+                        }
+                    }
+                }
+            }
+            LoadCommand::Symtab(symtab) => match &symtab.symbols {
+                Ok(symbols) => render_lazy_list(
+                    detail_block.title(format!("Symbols ({})", symbols.len())),
+                    symbols.len(),
+                    |i| symbol_display(&symbols[i]),
+                    content_detail,
+                    buf,
+                    &mut self.state.detail_list_state,
+                    self.theme,
+                ),
+                Err(err) => Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(detail_block.title("Symbols"))
+                    .render(content_detail, buf),
+            },
+            LoadCommand::Uuid(uuid) => render_uuid_detail(uuid, detail_block, content_detail, buf),
+            LoadCommand::BuildVersion(build_version) => render_build_version_detail(
+                build_version,
+                detail_block,
+                content_detail,
+                buf,
+                &mut self.state.detail_scroll,
+            ),
+            LoadCommand::Main(main) => render_main_detail(main, detail_block, content_detail, buf),
+            LoadCommand::Rpath(rpath) => {
+                let path_text = display_name(&rpath.path);
+                let rows = [Row::new(vec!["Path:", path_text.as_str()])];
+                let widths = [Constraint::Length(6), Constraint::Fill(1)];
+                Widget::render(
+                    Table::new(rows, widths).block(detail_block),
+                    content_detail,
+                    buf,
+                );
+            }
+            LoadCommand::LoadDylib(dylib) => {
+                let [dylib_area, imports_area] =
+                    Layout::vertical([Constraint::Length(DYLIB_DETAIL_HEIGHT), Constraint::Min(0)])
+                        .areas(content_detail);
+                render_dylib_detail(dylib, detail_block, dylib_area, buf);
+                let imports_block = Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .style(self.focus_style(Focus::Detail));
+                match macho.dylib_imports(selected_pos) {
+                    Ok(imports) => render_lazy_list(
+                        imports_block.title(format!("Imports ({})", imports.len())),
+                        imports.len(),
+                        |i| imports[i].to_string(),
+                        imports_area,
+                        buf,
+                        &mut self.state.detail_list_state,
+                        self.theme,
+                    ),
+                    Err(err) => Paragraph::new(err)
+                        .style(Style::default().fg(Color::Red))
+                        .block(imports_block.title("Imports"))
+                        .render(imports_area, buf),
+                }
+            }
+            LoadCommand::SourceVersion(source_version) => {
+                render_source_version_detail(source_version, detail_block, content_detail, buf)
+            }
+            LoadCommand::CodeSignature(code_signature) => render_code_signature_detail(
+                code_signature,
+                detail_block,
+                content_detail,
+                buf,
+                &mut self.state.detail_scroll,
+            ),
+            LoadCommand::FunctionStarts(function_starts) => match &function_starts.offsets {
+                Ok(offsets) => {
+                    let text_vm_addr = macho.text_vm_addr();
+                    render_lazy_list(
+                        detail_block.title(format!("Function Starts ({})", offsets.len())),
+                        offsets.len(),
+                        |i| {
+                            format!(
+                                "0x{:X}  (__TEXT+0x{:X})",
+                                text_vm_addr.wrapping_add(offsets[i]),
+                                offsets[i]
+                            )
+                        },
+                        content_detail,
+                        buf,
+                        &mut self.state.detail_list_state,
+                        self.theme,
+                    );
+                }
+                Err(err) => Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(detail_block)
+                    .render(content_detail, buf),
+            },
+            LoadCommand::Unknow(unknown) => {
+                let hex = Hex::new(&unknown.data)
+                    .with_group_bytes(self.state.hex_group_bytes)
+                    .with_byte_colors(self.state.hex_byte_colors)
+                    .block(detail_block.title_bottom(format!(
+                        "cmd 0x{:X}, {} bytes",
+                        unknown.cmd, unknown.cmd_size
+                    )));
+                hex.render_ref(content_detail, buf);
+            }
+            _ => detail_block.render(content_detail, buf),
+        }
+    }
+
+    fn render_section_hex(
+        &mut self,
+        section: &Section64,
+        block: Block,
+        content_detail: Rect,
+        buf: &mut Buffer,
+    ) {
+        let range = section_file_range(section);
+        let end = range.end.min(self.data.len());
+        let start = range.start.min(end);
+        let modified: Vec<(Range<usize>, Style)> = self
+            .patches
+            .modified_ranges(start..end)
+            .into_iter()
+            .map(|range| (range, Style::default().fg(Color::Yellow)))
+            .collect();
+        let block = block.title(format!(
+            "{},{} (Hex, Enter: Sections)",
+            display_name(&section.seg_name),
+            display_name(&section.name)
+        ));
+        let block = if section.is_zerofill() {
+            block.title_bottom("zerofill, no bytes in the file")
+        } else {
+            block
+        };
+        let hex = Hex::new(&self.data[start..end])
+            .block(block)
+            .base_offset(start)
+            .highlights(&modified);
+        StatefulWidget::render(&hex, content_detail, buf, &mut self.state.section_hex_state);
+    }
+
+    fn render_disassemble(
+        &mut self,
+        key: (usize, usize),
+        section: &Section64,
+        block: Block,
+        content_detail: Rect,
+        buf: &mut Buffer,
+    ) {
+        let (cpu_type, data) = (self.macho.header.cpu_type, self.data);
+        let lines = self
+            .state
+            .disassembles
+            .get_or_disassemble(key, || disassemble_section(cpu_type, section, data));
+        render_lazy_list(
+            block.title(format!(
+                "{},{} disassembly ({})",
+                display_name(&section.seg_name),
+                display_name(&section.name),
+                cpu_type_str(cpu_type)
+            )),
+            lines.len(),
+            |i| lines[i].clone(),
+            content_detail,
+            buf,
+            &mut self.state.disassemble_list_state,
+            self.theme,
+        );
+    }
+}
+
+fn is_text_section(section: &Section64) -> bool {
+    section.seg_name == "__TEXT" && section.name == "__text"
+}
+
+/// File byte range of the section, empty for a zerofill section which has
+/// no bytes in the file.
+fn section_file_range(section: &Section64) -> Range<usize> {
+    let start = section.offset as usize;
+    if section.is_zerofill() {
+        start..start
+    } else {
+        start..start.saturating_add(section.size as usize)
+    }
+}
+
+/// Disassembles the file bytes of the section. Stops at the end of the
+/// available data if the section is larger than its bytes in the file.
+fn disassemble_section(cpu_type: CPUType, section: &Section64, data: &[u8]) -> Vec<String> {
+    let start = (section.offset as usize).min(data.len());
+    let end = start.saturating_add(section.size as usize).min(data.len());
+    match disassemble_code(cpu_type, &data[start..end]) {
+        Err(err) => vec![format!("Err disassemble: {}", err)],
+        Ok(instructions) => {
+            let mut address = section.address;
+            let mut lines = Vec::with_capacity(instructions.len());
+            for (len, text) in instructions {
+                lines.push(format!("0x{:016X}: {}", address, text));
+                address += len as u64;
+            }
+            lines
+        }
+    }
+}
+
+/// Decodes the bytes with the disassembler of the architecture, returns the
+/// length and text of each instruction. A new `CPUType` needs its decoder
+/// here, bytes of another architecture would decode to garbage.
+fn disassemble_code(cpu_type: CPUType, bytes: &[u8]) -> Result<Vec<(usize, String)>, String> {
+    match cpu_type {
+        CPUType::ARM64 => arm64::disassemble(bytes)
+            .map(|dis| {
+                dis.instructions
+                    .iter()
+                    .map(|i| (i.len, i.instr.text(None)))
+                    .collect()
+            })
+            .map_err(|err| err.to_string()),
+        CPUType::X86_64 => x86_64::disassemble(bytes)
+            .map(|dis| {
+                dis.instructions
+                    .iter()
+                    .map(|i| (i.len, i.instr.text(None)))
+                    .collect()
+            })
+            .map_err(|err| err.to_string()),
+        CPUType::PowerPC64 => Err("no disassembler for ppc64".to_string()),
+    }
+}
+
+const SECTION_DETAIL_HEIGHT: u16 = 9;
+
+// five rows plus the border
+const DYLIB_DETAIL_HEIGHT: u16 = 7;
+
+fn render_section_detail(section: &Section64, block: Block, area: Rect, buf: &mut Buffer) {
+    let address_text = &format!("0x{:X}", section.address);
+    let size_text = &format!("0x{:X} ({} bytes)", section.size, section.size);
+    let offset_text = &format!("0x{:X}", section.offset);
+    let align_text = &format!("2^{} ({})", section.align, 1u64 << section.align.min(63));
+    let relocations_text = &format!(
+        "{} at 0x{:X}",
+        section.n_relocations, section.relocation_offset
+    );
+    let type_text = &match section.section_type() {
+        Some(name) => name.to_string(),
+        None => format!("unknown (0x{:X})", section.flags & 0xff),
+    };
+    let attributes = section.attributes();
+    let attributes_text = &if attributes.is_empty() {
+        "none".to_string()
+    } else {
+        attributes.join(" | ")
+    };
+    let rows = [
+        Row::new(vec!["Address:", address_text]),
+        Row::new(vec!["Size:", size_text]),
+        Row::new(vec!["Offset:", offset_text]),
+        Row::new(vec!["Alignment:", align_text]),
+        Row::new(vec!["Relocations:", relocations_text]),
+        Row::new(vec!["Type:", type_text]),
+        Row::new(vec!["Attributes:", attributes_text]),
+    ];
+    let widths = [Constraint::Length(13), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, area, buf);
 }
 
+fn render_uuid_detail(uuid: &UuidCommand, block: Block, content_detail: Rect, buf: &mut Buffer) {
+    let uuid_text = &uuid_display(&uuid.uuid);
+    let rows = [Row::new(vec!["UUID:", uuid_text])];
+    let widths = [Constraint::Length(10), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn render_build_version_detail(
+    build_version: &BuildVersionCommand,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+    scroll: &mut PaneScroll,
+) {
+    let mut rows = vec![
+        Row::new(vec![
+            "Platform:".to_string(),
+            platform_display(build_version.platform),
+        ]),
+        Row::new(vec![
+            "Min OS:".to_string(),
+            version_display(build_version.min_os),
+        ]),
+        Row::new(vec!["SDK:".to_string(), version_display(build_version.sdk)]),
+    ];
+    for tool in &build_version.tools {
+        rows.push(Row::new(vec![
+            "Tool:".to_string(),
+            format!(
+                "{} {}",
+                tool_display(tool.tool),
+                version_display(tool.version)
+            ),
+        ]));
+    }
+    let widths = [Constraint::Length(10), Constraint::Fill(1)];
+    render_scrolled_table(rows, &widths, block, content_detail, buf, scroll);
+}
+
+fn render_main_detail(main: &MainCommand, block: Block, content_detail: Rect, buf: &mut Buffer) {
+    let entry_text = &format!("0x{:X}", main.entry_offset);
+    let stack_text = &if main.stack_size == 0 {
+        "default".to_string()
+    } else {
+        format!("0x{:X}", main.stack_size)
+    };
+    let rows = [
+        Row::new(vec!["Entry Offset:", entry_text]),
+        Row::new(vec!["Stack Size:", stack_text]),
+    ];
+    let widths = [Constraint::Length(14), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn render_dylib_detail(
+    dylib: &LoadDylibCommand,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+) {
+    let current_text = &version_display(dylib.current_version);
+    let compat_text = &version_display(dylib.compatibility_version);
+    let name_text = &display_name(&dylib.name);
+    let timestamp_text = &format!("{}", dylib.timestamp);
+    let rows = [
+        Row::new(vec!["Name:", name_text]),
+        Row::new(vec!["Kind:", dylib_kind_display(dylib.kind)]),
+        Row::new(vec!["Current Version:", current_text]),
+        Row::new(vec!["Compatibility Version:", compat_text]),
+        Row::new(vec!["Timestamp:", timestamp_text]),
+    ];
+    let widths = [Constraint::Length(23), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+/// The rpaths and, in load order, the dylibs with their ordinal, i.e. what
+/// dyld will try to load and where it looks for `@rpath`.
+fn render_dylibs_summary(
+    macho: &Macho,
+    block: Block,
+    area: Rect,
+    buf: &mut Buffer,
+    scroll: &mut PaneScroll,
+) {
+    let rpaths: Vec<String> = macho
+        .load_commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            LoadCommand::Rpath(rpath) => Some(display_name(&rpath.path)),
+            _ => None,
+        })
+        .collect();
+    let dylibs: Vec<&LoadDylibCommand> = macho
+        .load_commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            LoadCommand::LoadDylib(dylib) => Some(dylib),
+            _ => None,
+        })
+        .collect();
+
+    let mut rows = vec![
+        Row::new(vec![format!("Rpaths ({})", rpaths.len())])
+            .style(Style::default().fg(Color::Yellow)),
+    ];
+    if rpaths.is_empty() {
+        rows.push(Row::new(vec!["".to_string(), "none".to_string()]));
+    }
+    rows.extend(
+        rpaths
+            .into_iter()
+            .map(|path| Row::new(vec!["".to_string(), path])),
+    );
+    rows.push(Row::new(vec![String::new()]));
+    rows.push(
+        Row::new(vec![format!("Dylibs ({})", dylibs.len())])
+            .style(Style::default().fg(Color::Yellow)),
+    );
+    rows.extend(dylibs.iter().enumerate().map(|(i, dylib)| {
+        Row::new(vec![
+            format!("{:>4}", i + 1),
+            format!(
+                "{:<8} {} (current {}, compatibility {})",
+                dylib_kind_display(dylib.kind),
+                display_name(&dylib.name),
+                version_display(dylib.current_version),
+                version_display(dylib.compatibility_version)
+            ),
+        ])
+    }));
+    let widths = [Constraint::Length(12), Constraint::Fill(1)];
+    render_scrolled_table(
+        rows,
+        &widths,
+        block.title("Rpaths and Dylibs"),
+        area,
+        buf,
+        scroll,
+    );
+}
+
+/// The gaps between the sections of the segment at `cmd_index` by file
+/// offset and by vm address, gaps larger than alignment padding highlighted.
+fn render_section_gaps(
+    macho: &Macho,
+    cmd_index: usize,
+    block: Block,
+    area: Rect,
+    buf: &mut Buffer,
+    scroll: &mut PaneScroll,
+) {
+    let Some(LoadCommand::Segment64(segment)) = macho.load_commands.get(cmd_index) else {
+        Paragraph::new("select a segment to show the gaps between its sections")
+            .block(block.title("Section Gaps"))
+            .render(area, buf);
+        return;
+    };
+    let gaps = macho.section_gaps(cmd_index);
+    let section_name =
+        |ix: Option<usize>, end: &str| match ix.and_then(|ix| segment.sections.get(ix)) {
+            Some(section) => display_name(&section.name),
+            None => end.to_string(),
+        };
+    let header = Row::new(vec![
+        "Between",
+        "File Offset",
+        "File Size",
+        "VM Addr",
+        "VM Size",
+    ])
+    .style(Style::default().fg(Color::Yellow));
+    let rows = gaps.iter().map(|gap| {
+        let style = if gap.is_large(&segment.sections) {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+        Row::new(vec![
+            format!(
+                "{} - {}",
+                section_name(gap.after, "start"),
+                section_name(gap.before, "end")
+            ),
+            format!("0x{:X}", gap.file_offset),
+            format!("0x{:X}", gap.file_size),
+            format!("0x{:X}", gap.vm_addr),
+            format!("0x{:X}", gap.vm_size),
+        ])
+        .style(style)
+    });
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(12),
+        Constraint::Length(10),
+        Constraint::Length(20),
+        Constraint::Length(10),
+    ];
+    let title = format!(
+        "Section Gaps of {} ({})",
+        display_name(&segment.name),
+        gaps.len()
+    );
+    render_scrolled_table(
+        std::iter::once(header).chain(rows),
+        &widths,
+        block.title(title),
+        area,
+        buf,
+        scroll,
+    );
+}
+
+fn dylib_kind_display(kind: DylibKind) -> &'static str {
+    match kind {
+        DylibKind::Load => "load",
+        DylibKind::Weak => "weak",
+        DylibKind::Reexport => "reexport",
+        DylibKind::Lazy => "lazy",
+        DylibKind::Upward => "upward",
+    }
+}
+
+fn render_source_version_detail(
+    source_version: &SourceVersionCommand,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+) {
+    let version_text = &source_version_display(source_version.version);
+    let rows = [Row::new(vec!["Version:", version_text])];
+    let widths = [Constraint::Length(10), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn render_code_signature_detail(
+    code_signature: &CodeSignatureCommand,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+    scroll: &mut PaneScroll,
+) {
+    let mut rows = vec![
+        Row::new(vec![
+            "Offset:".to_string(),
+            format!("0x{:X}", code_signature.data_off),
+        ]),
+        Row::new(vec![
+            "Size:".to_string(),
+            format!("0x{:X}", code_signature.data_size),
+        ]),
+    ];
+    match &code_signature.signature {
+        Ok(signature) => {
+            rows.push(Row::new(vec![
+                "Blobs:".to_string(),
+                signature.blobs.len().to_string(),
+            ]));
+            for blob in &signature.blobs {
+                rows.push(Row::new(vec![
+                    String::new(),
+                    format!(
+                        "0x{:X} {} at +0x{:X}",
+                        blob.slot,
+                        blob.slot_name(),
+                        blob.offset
+                    ),
+                ]));
+            }
+            if let Some(directory) = &signature.code_directory {
+                rows.push(Row::new(vec![
+                    "Identifier:".to_string(),
+                    directory.identifier.clone(),
+                ]));
+                rows.push(Row::new(vec![
+                    "Team ID:".to_string(),
+                    directory
+                        .team_id
+                        .clone()
+                        .unwrap_or_else(|| "none".to_string()),
+                ]));
+                rows.push(Row::new(vec![
+                    "Hash Type:".to_string(),
+                    format!(
+                        "{} ({} bytes)",
+                        directory.hash_type_name(),
+                        directory.hash_size
+                    ),
+                ]));
+                rows.push(Row::new(vec![
+                    "Code Slots:".to_string(),
+                    directory.code_slots.to_string(),
+                ]));
+                rows.push(Row::new(vec![
+                    "Special Slots:".to_string(),
+                    directory.special_slots.to_string(),
+                ]));
+                rows.push(Row::new(vec![
+                    "Code Limit:".to_string(),
+                    format!("0x{:X}", directory.code_limit),
+                ]));
+                rows.push(Row::new(vec![
+                    "Version:".to_string(),
+                    format!("0x{:X}", directory.version),
+                ]));
+                rows.push(Row::new(vec![
+                    "Flags:".to_string(),
+                    format!("0x{:X}", directory.flags),
+                ]));
+            }
+            rows.extend(entitlement_rows(signature));
+        }
+        Err(err) => rows.push(
+            Row::new(vec!["Error:".to_string(), err.clone()])
+                .style(Style::default().fg(Color::Red)),
+        ),
+    }
+    let widths = [Constraint::Length(15), Constraint::Fill(1)];
+    render_scrolled_table(rows, &widths, block, content_detail, buf, scroll);
+}
+
+/// One row per line of the entitlements, the XML plist if the signature has
+/// one, the decoded DER entitlements otherwise.
+fn entitlement_rows(signature: &CodeSignature) -> Vec<Row<'static>> {
+    let lines: Vec<String> = match (&signature.entitlements, &signature.der_entitlements) {
+        (Some(Ok(xml)), _) => xml.trim_end().lines().map(str::to_string).collect(),
+        (_, Some(Ok(value))) => {
+            let mut lines = Vec::new();
+            entitlement_lines(value, 0, &mut lines);
+            lines
+        }
+        (Some(Err(err)), _) | (_, Some(Err(err))) => {
+            return vec![
+                Row::new(vec!["Entitlements:".to_string(), err.clone()])
+                    .style(Style::default().fg(Color::Red)),
+            ];
+        }
+        (None, None) => return Vec::new(),
+    };
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let label = if i == 0 { "Entitlements:" } else { "" };
+            Row::new(vec![label.to_string(), line])
+        })
+        .collect()
+}
+
+/// Appends the value as indented lines like a plist, `key: value` for the
+/// entries of a dictionary.
+fn entitlement_lines(value: &EntitlementValue, indent: usize, lines: &mut Vec<String>) {
+    let pad = "  ".repeat(indent);
+    match value {
+        EntitlementValue::Dict(entries) => {
+            for (key, value) in entries {
+                match value {
+                    EntitlementValue::Dict(_) | EntitlementValue::Array(_) => {
+                        lines.push(format!("{}{}:", pad, key));
+                        entitlement_lines(value, indent + 1, lines);
+                    }
+                    _ => lines.push(format!("{}{}: {}", pad, key, entitlement_scalar(value))),
+                }
+            }
+        }
+        EntitlementValue::Array(values) => {
+            for value in values {
+                match value {
+                    EntitlementValue::Dict(_) | EntitlementValue::Array(_) => {
+                        lines.push(format!("{}-", pad));
+                        entitlement_lines(value, indent + 1, lines);
+                    }
+                    _ => lines.push(format!("{}- {}", pad, entitlement_scalar(value))),
+                }
+            }
+        }
+        _ => lines.push(format!("{}{}", pad, entitlement_scalar(value))),
+    }
+}
+
+fn entitlement_scalar(value: &EntitlementValue) -> String {
+    match value {
+        EntitlementValue::Bool(b) => b.to_string(),
+        EntitlementValue::Integer(i) => i.to_string(),
+        EntitlementValue::String(s) => s.clone(),
+        EntitlementValue::Array(_) | EntitlementValue::Dict(_) => String::new(),
+    }
+}
+
+fn symbol_display(symbol: &Symbol) -> String {
+    let type_text = match symbol.symbol_type() {
+        SymbolType::Undefined => "undef",
+        SymbolType::Absolute => "abs",
+        SymbolType::Section => "sect",
+        SymbolType::Prebound => "pbud",
+        SymbolType::Indirect => "indr",
+        SymbolType::Debug => "debug",
+        SymbolType::Unknown(_) => "?",
+    };
+    let ext_text = if symbol.is_external() { "ext" } else { "" };
+    format!(
+        "0x{:016X} {:<5} {:<3} sect {:>3} {}",
+        symbol.value,
+        type_text,
+        ext_text,
+        symbol.sect,
+        display_name(&symbol.name)
+    )
+}
+
+fn uuid_display(uuid: &[u8; 16]) -> String {
+    let mut result = String::with_capacity(36);
+    for (i, b) in uuid.iter().enumerate() {
+        if i == 4 || i == 6 || i == 8 || i == 10 {
+            result.push('-');
+        }
+        result.push_str(&format!("{:02X}", b));
+    }
+    result
+}
+
+/// Formats a version encoded in nibbles xxxx.yy.zz
+fn version_display(version: u32) -> String {
+    format!(
+        "{}.{}.{}",
+        version >> 16,
+        (version >> 8) & 0xFF,
+        version & 0xFF
+    )
+}
+
+/// Formats a version packed as a24.b10.c10.d10.e10
+fn source_version_display(version: u64) -> String {
+    format!(
+        "{}.{}.{}.{}.{}",
+        version >> 40,
+        (version >> 30) & 0x3FF,
+        (version >> 20) & 0x3FF,
+        (version >> 10) & 0x3FF,
+        version & 0x3FF
+    )
+}
+
+fn platform_display(platform: u32) -> String {
+    let name = match platform {
+        1 => "macOS",
+        2 => "iOS",
+        3 => "tvOS",
+        4 => "watchOS",
+        5 => "bridgeOS",
+        6 => "Mac Catalyst",
+        7 => "iOS Simulator",
+        8 => "tvOS Simulator",
+        9 => "watchOS Simulator",
+        10 => "DriverKit",
+        11 => "visionOS",
+        12 => "visionOS Simulator",
+        _ => return format!("Unknown ({})", platform),
+    };
+    name.to_string()
+}
+
+fn tool_display(tool: u32) -> String {
+    let name = match tool {
+        1 => "clang",
+        2 => "swift",
+        3 => "ld",
+        4 => "lld",
+        _ => return format!("tool {}", tool),
+    };
+    name.to_string()
+}
+
+// four lines of header fields with room for the wrapped flags, plus the border
+const HEADER_HEIGHT: u16 = 7;
+
+// the segment of the DWARF debug sections, e.g. __debug_info and __debug_line
+const DWARF_SEGMENT: &str = "__DWARF";
+
 impl<'a> Widget for &mut MachoWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let content_layout =
-            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]);
+        let content_layout = Layout::horizontal(self.state.split.constraints());
         let [content_file, content_detail] = content_layout.areas(area);
 
-        let file_layout = Layout::vertical([Constraint::Max(3), Constraint::Fill(1)]);
+        let file_layout = Layout::vertical([Constraint::Max(HEADER_HEIGHT), Constraint::Fill(1)]);
         let [mach_header, mach_commands] = file_layout.areas(content_file);
 
+        let pane_areas = &mut self.state.pane_areas;
+        pane_areas.clear();
+        pane_areas.push(Focus::Header, mach_header);
+        pane_areas.push(Focus::LoadCommands, mach_commands);
+        pane_areas.push(Focus::Detail, content_detail);
+
         let header_block = Block::bordered()
             .border_type(BorderType::Plain)
             .style(self.focus_style(Focus::Header))
             .title("Header");
 
-        Paragraph::new(format!(
-            "cpu:{:?} | sub:{:?} | file:{:?}",
-            self.macho.header.cpu_type, self.macho.header.cpu_sub_type, self.macho.header.file_type,
-        ))
+        let header = &self.macho.header;
+        let flags: Vec<String> = header.flags.iter().map(|f| format!("{:?}", f)).collect();
+        Paragraph::new(vec![
+            Line::from(format!(
+                "magic:0x{:X} | cpu:{:?} | sub:{:?}",
+                header.magic, header.cpu_type, header.cpu_sub_type
+            )),
+            Line::from(format!(
+                "file:{:?} | ncmds:{} | sizeofcmds:{}",
+                header.file_type, header.no_cmds, header.size_of_cmds
+            )),
+            Line::from(format!("debug:{}", debug_info_summary(self.macho))),
+            Line::from(format!("flags:{}", flags.join(", "))),
+        ])
+        .wrap(Wrap { trim: false })
         .block(header_block)
         .render(mach_header, buf);
 
         let command_block = Block::bordered()
             .border_type(BorderType::Plain)
             .style(self.focus_style(Focus::LoadCommands))
-            .title(format!("Load Commands ({})", self.macho.header.no_cmds));
+            .title(format!("Load Commands ({})", self.macho.header.no_cmds))
+            .title(Line::from(command_tally(&self.macho.load_commands)).right_aligned());
 
         let cmd_list = List::new(command_list(self.macho))
             .block(command_block)
-            .highlight_style(Style::new().black().on_white());
+            .highlight_style(self.theme.style_highlight());
         StatefulWidget::render(
             cmd_list,
             mach_commands,
             buf,
             &mut self.state.command_list_state,
         );
+        render_list_scrollbar(
+            mach_commands,
+            buf,
+            self.state.command_count,
+            &self.state.command_list_state,
+        );
 
-        let detail_block = Block::bordered()
-            .border_type(BorderType::Plain)
-            .title("Details");
+        self.render_detail(content_detail, buf);
+    }
+}
 
-        let selected = self.state.command_list_state.selected();
-        if let Some(selected_pos) = selected {
-            let load_command = &self.macho.load_commands[selected_pos];
-            if let LoadCommand::Segment64(segment) = load_command {
-                let sec_list = List::new(section_list(&segment.sections))
-                    .block(detail_block)
-                    .highlight_style(Style::new().black().on_white());
-                let mut dummy_state = ListState::default();
-                StatefulWidget::render(sec_list, content_detail, buf, &mut dummy_state);
-            }
+/// A one line count of the kinds of load commands, e.g.
+/// `Segments: 4  Dylibs: 12  Other: 7`. Unknown commands are counted apart.
+fn command_tally(commands: &[LoadCommand]) -> String {
+    let (mut segments, mut dylibs, mut other, mut unknown) = (0, 0, 0, 0);
+    for command in commands {
+        // no catch-all, a new variant has to pick its count
+        match command {
+            LoadCommand::Segment64(_) => segments += 1,
+            LoadCommand::LoadDylib(_) => dylibs += 1,
+            LoadCommand::Unknow(_) => unknown += 1,
+            LoadCommand::Symtab(_)
+            | LoadCommand::Dsymtab(_)
+            | LoadCommand::Dylinker(_)
+            | LoadCommand::Uuid(_)
+            | LoadCommand::Rpath(_)
+            | LoadCommand::CodeSignature(_)
+            | LoadCommand::BuildVersion(_)
+            | LoadCommand::FunctionStarts(_)
+            | LoadCommand::DataInCode(_)
+            | LoadCommand::SourceVersion(_)
+            | LoadCommand::DyldInfoOnly(_)
+            | LoadCommand::Main(_)
+            | LoadCommand::LinkeditData(_) => other += 1,
         }
     }
+    let mut tally = format!(
+        "Segments: {}  Dylibs: {}  Other: {}",
+        segments, dylibs, other
+    );
+    if unknown > 0 {
+        tally.push_str(&format!("  Unknown: {}", unknown));
+    }
+    tally
+}
+
+/// The sections of the `__DWARF` segment, a binary without them is stripped
+/// or keeps its debug info in a dSYM.
+fn debug_sections(macho: &Macho) -> impl Iterator<Item = &Section64> {
+    macho
+        .load_commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            LoadCommand::Segment64(segment) if segment.name == DWARF_SEGMENT => {
+                Some(&segment.sections)
+            }
+            _ => None,
+        })
+        .flatten()
+}
+
+/// The number and total size of the debug sections, e.g. `4 DWARF sections,
+/// 12345 bytes`.
+pub fn debug_info_summary(macho: &Macho) -> String {
+    let (count, bytes) = debug_sections(macho).fold((0, 0), |(count, bytes), section| {
+        (count + 1, bytes + section.size)
+    });
+    if count == 0 {
+        "none".to_string()
+    } else {
+        format!("{} DWARF sections, {} bytes", count, bytes)
+    }
 }
 
-fn section_list(segs: &[Section64]) -> Vec<&str> {
+/// The debug sections with their sizes.
+fn debug_section_list(sections: &[Section64]) -> Vec<String> {
+    sections
+        .iter()
+        .map(|s| format!("{:<20}{:>12} bytes", display_name(&s.name), s.size))
+        .collect()
+}
+
+fn section_list(segs: &[Section64]) -> Vec<String> {
     let mut result = Vec::with_capacity(segs.len());
     for seg in segs {
-        result.push(seg.name.as_str());
+        result.push(display_name(&seg.name));
     }
     result
 }
 
-fn command_list(macho: &Macho) -> Vec<String> {
+/// A name read from the binary made safe to show: cut at the first NUL,
+/// control characters replaced with `·`. Invalid UTF-8 is already replaced
+/// when parsing.
+pub fn display_name(name: &str) -> String {
+    name.split('\0')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_control() { '·' } else { c })
+        .collect()
+}
+
+/// The last path component of an install name, e.g. `Foundation` for
+/// `/System/Library/Frameworks/Foundation.framework/Versions/C/Foundation`.
+fn dylib_basename(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+pub fn command_list(macho: &Macho) -> Vec<String> {
     let mut result = Vec::with_capacity(macho.load_commands.len());
 
     for cmd in &macho.load_commands {
@@ -174,13 +1691,34 @@ fn command_list(macho: &Macho) -> Vec<String> {
             LoadCommand::Symtab(_) => "Symtab".to_string(),
             LoadCommand::Dsymtab(_) => "Dsymtab".to_string(),
             LoadCommand::LoadDylib(dylib) => {
-                format!("LoadDylib | {}", dylib.name)
+                let cmd_name = match dylib.kind {
+                    DylibKind::Load => "LoadDylib",
+                    DylibKind::Weak => "LoadWeakDylib",
+                    DylibKind::Reexport => "ReexportDylib",
+                    DylibKind::Lazy => "LazyLoadDylib",
+                    DylibKind::Upward => "LoadUpwardDylib",
+                };
+                format!(
+                    "{} | {}",
+                    cmd_name,
+                    display_name(dylib_basename(&dylib.name))
+                )
             }
+            LoadCommand::Rpath(rpath) => format!("Rpath | {}", display_name(&rpath.path)),
             LoadCommand::Dylinker(dylink) => {
-                format!("Dylinker | {}", dylink.name)
+                format!("Dylinker | {}", display_name(&dylink.name))
             }
             LoadCommand::Segment64(seg) => {
-                format!("Segment64 | {}", seg.name)
+                let sections = match seg.sections.len() {
+                    1 => "1 section".to_string(),
+                    n => format!("{} sections", n),
+                };
+                format!(
+                    "Segment64 | {} ({}, 0x{:X})",
+                    display_name(&seg.name),
+                    sections,
+                    seg.vm_size
+                )
             }
             LoadCommand::Uuid(_) => "UUID".to_string(),
             LoadCommand::CodeSignature(_) => "CodeSignature".to_string(),
@@ -198,3 +1736,88 @@ fn command_list(macho: &Macho) -> Vec<String> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use mule_macho::test_util::{macho_bytes, uuid_command};
+
+    use super::*;
+    use crate::test_util::{buffer, buffer_text};
+
+    const UUID_A: [u8; 16] = [0xAA; 16];
+    const UUID_B: [u8; 16] = [0xBB; 16];
+
+    fn detail_text(macho: &Macho, selected: Option<usize>) -> String {
+        let mut state = MachoInteractiveState::new(macho);
+        state.command_list_state.select(selected);
+        let patches = Patches::default();
+        let theme = Theme::default();
+        let mut buf = buffer(60, 10);
+        let mut widget = MachoWidget::new(macho, &[], &patches, &theme, &mut state);
+        widget.render_detail(buf.area, &mut buf);
+        buffer_text(&buf)
+    }
+
+    #[test]
+    fn detail_of_the_last_command() {
+        let macho =
+            mule_macho::load(&macho_bytes(&[uuid_command(UUID_A), uuid_command(UUID_B)])).unwrap();
+        let text = detail_text(&macho, Some(1));
+        assert!(text.contains(&uuid_display(&UUID_B)));
+    }
+
+    #[test]
+    fn detail_past_the_last_command_is_empty() {
+        let macho =
+            mule_macho::load(&macho_bytes(&[uuid_command(UUID_A), uuid_command(UUID_B)])).unwrap();
+        let text = detail_text(&macho, Some(2));
+        assert!(text.contains("Details"));
+        assert!(!text.contains("UUID"));
+    }
+
+    #[test]
+    fn detail_without_commands_is_empty() {
+        let macho = mule_macho::load(&macho_bytes(&[])).unwrap();
+        for selected in [None, Some(0)] {
+            let text = detail_text(&macho, selected);
+            assert!(text.contains("Details"));
+            assert!(!text.contains("UUID"));
+        }
+    }
+
+    #[test]
+    fn display_name_is_cut_at_the_first_nul() {
+        assert_eq!(display_name("__TEXT\0junk"), "__TEXT");
+        assert_eq!(display_name("\0__TEXT"), "");
+    }
+
+    #[test]
+    fn display_name_replaces_control_characters() {
+        assert_eq!(display_name("__te\x07xt\n"), "__te·xt·");
+        assert_eq!(display_name("\u{1b}[31m"), "·[31m");
+    }
+
+    #[test]
+    fn display_name_keeps_high_bytes() {
+        let name = String::from_utf8_lossy(b"lib\xFF\xFEz\xC3\xA4").to_string();
+        assert_eq!(display_name(&name), "lib\u{FFFD}\u{FFFD}z\u{e4}");
+    }
+
+    #[test]
+    fn command_tally_in_the_top_border() {
+        let macho =
+            mule_macho::load(&macho_bytes(&[uuid_command(UUID_A), uuid_command(UUID_B)])).unwrap();
+        let mut state = MachoInteractiveState::new(&macho);
+        let patches = Patches::default();
+        let theme = Theme::default();
+        let mut buf = buffer(200, 20);
+        let mut widget = MachoWidget::new(&macho, &[], &patches, &theme, &mut state);
+        Widget::render(&mut widget, buf.area, &mut buf);
+        let text = buffer_text(&buf);
+        let top = text
+            .lines()
+            .find(|line| line.contains("Load Commands (2)"))
+            .unwrap();
+        assert!(top.contains("Segments: 0  Dylibs: 0  Other: 2"));
+    }
+}
@@ -0,0 +1,168 @@
+use mule_macho::{LoadCommand, Macho};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, StatefulWidget, Widget},
+};
+
+use crate::{theme::Theme, view_macho::display_name};
+
+// segments alternate between these colors to tell neighbours apart
+const SEGMENT_COLORS: [Color; 2] = [Color::Blue, Color::Cyan];
+
+/// A block of the memory map, a segment or the unmapped gap between two
+/// segments.
+struct MapBlock {
+    /// Index of the segment's load command, None for a gap
+    command: Option<usize>,
+    name: String,
+    vm_addr: u64,
+    vm_size: u64,
+}
+
+/// The address space of a Mach-O as a vertical bar, each segment a block
+/// with a height in proportion to its vmsize. The state are the areas of the
+/// drawn segments by load command index, used to select a segment by
+/// clicking it.
+pub struct MemoryMapWidget<'a> {
+    macho: &'a Macho,
+    /// Load command index of the highlighted segment
+    selected: Option<usize>,
+    block: Block<'a>,
+    theme: &'a Theme,
+}
+
+impl<'a> MemoryMapWidget<'a> {
+    pub fn new(
+        macho: &'a Macho,
+        selected: Option<usize>,
+        block: Block<'a>,
+        theme: &'a Theme,
+    ) -> MemoryMapWidget<'a> {
+        MemoryMapWidget {
+            macho,
+            selected,
+            block,
+            theme,
+        }
+    }
+}
+
+impl<'a> StatefulWidget for MemoryMapWidget<'a> {
+    type State = Vec<(usize, Rect)>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.clear();
+        let blocks = map_blocks(self.macho);
+        let segments = blocks.iter().filter(|b| b.command.is_some()).count();
+        let block = self
+            .block
+            .title(format!("Memory Map ({} segments)", segments));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let sizes: Vec<u64> = blocks.iter().map(|b| b.vm_size).collect();
+        let mut y = inner.y;
+        let mut segment_ix = 0;
+        for (map_block, height) in blocks.iter().zip(block_heights(&sizes, inner.height)) {
+            if height == 0 {
+                continue;
+            }
+            let block_area = Rect::new(inner.x, y, inner.width, height);
+            y += height;
+
+            let style = match map_block.command {
+                Some(command) if Some(command) == self.selected => self.theme.style_highlight(),
+                Some(_) => Style::default()
+                    .fg(Color::Black)
+                    .bg(SEGMENT_COLORS[segment_ix % SEGMENT_COLORS.len()]),
+                None => self.theme.style_normal().fg(Color::DarkGray),
+            };
+            buf.set_style(block_area, style);
+            let end = map_block.vm_addr.saturating_add(map_block.vm_size);
+            Line::from(format!(
+                "{} 0x{:X}-0x{:X} (0x{:X})",
+                map_block.name, map_block.vm_addr, end, map_block.vm_size
+            ))
+            .style(style)
+            .render(block_area, buf);
+
+            if let Some(command) = map_block.command {
+                segment_ix += 1;
+                state.push((command, block_area));
+            }
+        }
+    }
+}
+
+/// The load command index of the segment drawn at the position.
+pub fn segment_at(areas: &[(usize, Rect)], column: u16, row: u16) -> Option<usize> {
+    areas
+        .iter()
+        .find(|(_, area)| area.contains((column, row).into()))
+        .map(|(command, _)| *command)
+}
+
+/// The segments ordered by vmaddr, with the gaps between them.
+fn map_blocks(macho: &Macho) -> Vec<MapBlock> {
+    let mut segments: Vec<MapBlock> = macho
+        .load_commands
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, cmd)| match cmd {
+            LoadCommand::Segment64(segment) => Some(MapBlock {
+                command: Some(ix),
+                name: display_name(&segment.name),
+                vm_addr: segment.vm_addr,
+                vm_size: segment.vm_size,
+            }),
+            _ => None,
+        })
+        .collect();
+    segments.sort_by_key(|s| s.vm_addr);
+
+    let mut blocks = Vec::with_capacity(segments.len() * 2);
+    let mut end: Option<u64> = None;
+    for segment in segments {
+        if let Some(end) = end.filter(|end| *end < segment.vm_addr) {
+            blocks.push(MapBlock {
+                command: None,
+                name: "gap".to_string(),
+                vm_addr: end,
+                vm_size: segment.vm_addr - end,
+            });
+        }
+        end = Some(segment.vm_addr.saturating_add(segment.vm_size));
+        blocks.push(segment);
+    }
+    blocks
+}
+
+/// The heights of blocks of the given sizes within `height` rows. Every block
+/// gets one row for its label and the remaining rows are shared in proportion
+/// to the sizes. Blocks that don't fit anymore get no row.
+fn block_heights(sizes: &[u64], height: u16) -> Vec<u16> {
+    let mut heights: Vec<u16> = (0..sizes.len())
+        .map(|ix| u16::from(ix < height as usize))
+        .collect();
+    let Some(spare) = height.checked_sub(sizes.len() as u16).filter(|s| *s > 0) else {
+        return heights;
+    };
+    let total: u128 = sizes.iter().map(|s| *s as u128).sum();
+    if total == 0 {
+        return heights;
+    }
+    let mut shared = 0;
+    for (h, size) in heights.iter_mut().zip(sizes) {
+        let share = (*size as u128 * spare as u128 / total) as u16;
+        *h += share;
+        shared += share;
+    }
+    // the rows lost to rounding go to the largest block
+    if let Some(largest) = (0..sizes.len()).max_by_key(|ix| sizes[*ix]) {
+        heights[largest] += spare - shared;
+    }
+    heights
+}
@@ -0,0 +1,143 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Widget},
+};
+
+use crate::{
+    theme::Theme,
+    view_entropy::{chunk_range, shannon_entropy},
+};
+
+/// Width of the minimap column, including the border.
+pub const MINIMAP_WIDTH: u16 = 6;
+
+/// Entropy in bits per byte above which a chunk counts as compressed or
+/// encrypted data.
+const HIGH_ENTROPY: f64 = 7.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum ChunkClass {
+    Zero,
+    Ascii,
+    Code,
+    HighEntropy,
+}
+
+impl ChunkClass {
+    fn color(self) -> Color {
+        match self {
+            ChunkClass::Zero => Color::DarkGray,
+            ChunkClass::Ascii => Color::Green,
+            ChunkClass::Code => Color::Blue,
+            ChunkClass::HighEntropy => Color::Red,
+        }
+    }
+}
+
+fn classify(bytes: &[u8]) -> ChunkClass {
+    if bytes.iter().all(|b| *b == 0) {
+        return ChunkClass::Zero;
+    }
+    let printable = bytes
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        .count();
+    if printable * 10 >= bytes.len() * 9 {
+        return ChunkClass::Ascii;
+    }
+    if shannon_entropy(bytes) > HIGH_ENTROPY {
+        return ChunkClass::HighEntropy;
+    }
+    ChunkClass::Code
+}
+
+pub struct MinimapState {
+    // updated on render, used to map mouse clicks
+    area: Rect,
+}
+
+impl MinimapState {
+    pub fn new() -> MinimapState {
+        MinimapState {
+            area: Rect::default(),
+        }
+    }
+
+    /// The file offset of the cell at `position`, if the position is on the
+    /// minimap.
+    pub fn offset_at(&self, position: Position, data_len: usize) -> Option<usize> {
+        if !self.area.contains(position) {
+            return None;
+        }
+        let cell = (position.y - self.area.y) as usize * self.area.width as usize
+            + (position.x - self.area.x) as usize;
+        let range = chunk_range(cell, self.cells(), data_len);
+        if range.is_empty() {
+            return None;
+        }
+        Some(range.start)
+    }
+
+    fn cells(&self) -> usize {
+        self.area.width as usize * self.area.height as usize
+    }
+}
+
+/// Draws the whole data as a column of cells, one cell per chunk of the
+/// data, colored by what the chunk looks like (zero, ASCII, code or high
+/// entropy). The cell of `marker` is highlighted.
+pub struct MinimapWidget<'a> {
+    pub data: &'a [u8],
+    pub marker: Option<usize>,
+    pub theme: &'a Theme,
+    pub state: &'a mut MinimapState,
+}
+
+impl<'a> MinimapWidget<'a> {
+    pub fn new(
+        data: &'a [u8],
+        marker: Option<usize>,
+        theme: &'a Theme,
+        state: &'a mut MinimapState,
+    ) -> MinimapWidget<'a> {
+        MinimapWidget {
+            data,
+            marker,
+            theme,
+            state,
+        }
+    }
+}
+
+impl<'a> Widget for &mut MinimapWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered().border_type(BorderType::Plain);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        self.state.area = inner;
+        let cells = self.state.cells();
+        if cells == 0 || self.data.is_empty() {
+            return;
+        }
+
+        for cell in 0..cells {
+            let range = chunk_range(cell, cells, self.data.len());
+            let x = inner.x + (cell % inner.width as usize) as u16;
+            let y = inner.y + (cell / inner.width as usize) as u16;
+            if range.is_empty() {
+                continue;
+            }
+            let marked = self.marker.is_some_and(|m| range.contains(&m));
+            let class = classify(&self.data[range]);
+            let (symbol, style) = if marked {
+                ("◆", self.theme.style_highlight())
+            } else {
+                ("█", Style::default().fg(class.color()))
+            };
+            buf[(x, y)].set_symbol(symbol).set_style(style);
+        }
+    }
+}
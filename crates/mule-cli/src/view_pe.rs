@@ -0,0 +1,548 @@
+use std::ops::Range;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use mule_pe::{
+    IMAGE_FILE_DLL, IMAGE_FILE_EXECUTABLE_IMAGE, IMAGE_FILE_LARGE_ADDRESS_AWARE,
+    IMAGE_SCN_CNT_CODE, IMAGE_SCN_CNT_INITIALIZED_DATA, IMAGE_SCN_CNT_UNINITIALIZED_DATA,
+    IMAGE_SCN_MEM_EXECUTE, IMAGE_SCN_MEM_READ, IMAGE_SCN_MEM_WRITE, Import, ImportedFunction, Pe,
+    SectionHeader,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, Widget},
+};
+
+use crate::{
+    InteractiveCommand,
+    binary_view::{BinaryView, ByteOrder},
+    dump,
+    focus::FocusRing,
+    keymap::Action,
+    patch::Patches,
+    theme::Theme,
+    view::{
+        PaneAreas, PaneScroll, Split, list_index_at, page_list, render_lazy_list,
+        render_list_scrollbar, render_scrolled_table,
+    },
+};
+
+#[derive(PartialEq, Copy, Clone)]
+enum Focus {
+    Header,
+    Sections,
+    Imports,
+    /// The functions of the selected import in the detail pane
+    ImportFunctions,
+}
+
+static FOCUS_CYCLE_ORDER: [Focus; 4] = [
+    Focus::Header,
+    Focus::Sections,
+    Focus::Imports,
+    Focus::ImportFunctions,
+];
+
+pub struct PeInteractiveState {
+    focus: FocusRing<Focus>,
+    section_list_state: ListState,
+    import_list_state: ListState,
+    function_list_state: ListState,
+    // of the header table in the detail pane
+    header_scroll: PaneScroll,
+    pane_areas: PaneAreas<Focus>,
+    split: Split,
+}
+
+impl PeInteractiveState {
+    pub fn new() -> PeInteractiveState {
+        let mut section_list_state = ListState::default();
+        section_list_state.select(Some(0));
+        let mut import_list_state = ListState::default();
+        import_list_state.select(Some(0));
+        let mut function_list_state = ListState::default();
+        function_list_state.select(Some(0));
+        PeInteractiveState {
+            section_list_state,
+            import_list_state,
+            function_list_state,
+            header_scroll: PaneScroll::default(),
+            pane_areas: PaneAreas::new(),
+            split: Split::default(),
+            focus: FocusRing::new(&FOCUS_CYCLE_ORDER, Focus::Sections),
+        }
+    }
+
+    pub fn handle_command(&mut self, command: InteractiveCommand) {
+        let selected_import = self.import_list_state.selected();
+        match command {
+            InteractiveCommand::Action(action) => match action {
+                Action::FocusNext => self.focus.next(),
+                Action::FocusPrev => self.focus.prev(),
+                Action::WidenFilePane => self.split.widen_file_pane(),
+                Action::NarrowFilePane => self.split.narrow_file_pane(),
+                Action::SelectNext => match self.focus.current() {
+                    Some(Focus::Sections) => self.section_list_state.select_next(),
+                    Some(Focus::Imports) => self.import_list_state.select_next(),
+                    Some(Focus::ImportFunctions) => self.function_list_state.select_next(),
+                    _ => { /* ignore */ }
+                },
+                Action::SelectPrev => match self.focus.current() {
+                    Some(Focus::Sections) => self.section_list_state.select_previous(),
+                    Some(Focus::Imports) => self.import_list_state.select_previous(),
+                    Some(Focus::ImportFunctions) => self.function_list_state.select_previous(),
+                    _ => { /* ignore */ }
+                },
+                Action::PageDown | Action::PageUp | Action::Home | Action::End => {
+                    match self.focus.current() {
+                        Some(Focus::Header) => self.header_scroll.handle_action(action),
+                        Some(Focus::Sections) => page_list(
+                            &mut self.section_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::Sections),
+                        ),
+                        Some(Focus::Imports) => page_list(
+                            &mut self.import_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::Imports),
+                        ),
+                        Some(Focus::ImportFunctions) => page_list(
+                            &mut self.function_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::ImportFunctions),
+                        ),
+                        None => { /* ignore */ }
+                    }
+                }
+                _ => { /* ignore */ }
+            },
+            InteractiveCommand::Focus => self.focus.focus(),
+            InteractiveCommand::Unfocus => self.focus.unfocus(),
+            InteractiveCommand::Mouse(mouse) => self.handle_mouse(mouse),
+            InteractiveCommand::Toggle(_) => { /* ignore */ }
+        }
+        // the functions of another import are listed from the start
+        if self.import_list_state.selected() != selected_import {
+            self.function_list_state.select(Some(0));
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let Some((pane, area)) = self.pane_areas.pane_at(mouse.column, mouse.row) else {
+            return;
+        };
+        self.focus.set(pane);
+        let list = match pane {
+            Focus::Sections => &mut self.section_list_state,
+            Focus::Imports => &mut self.import_list_state,
+            Focus::ImportFunctions => &mut self.function_list_state,
+            _ => return,
+        };
+        if let Some(ix) = list_index_at(area, list, mouse.row) {
+            list.select(Some(ix));
+        }
+    }
+
+    /// File byte range of the selected section, if the section list is (or
+    /// was before unfocusing) in focus.
+    pub fn selected_region(&self, pe: &Pe) -> Option<Range<usize>> {
+        if self.focus.active() != Focus::Sections {
+            return None;
+        }
+        let section = pe
+            .section_headers
+            .get(self.section_list_state.selected()?)?;
+        let start = section.pointer_to_raw_data as usize;
+        Some(start..start.saturating_add(section.size_of_raw_data as usize))
+    }
+
+    pub fn focus_index(&self) -> usize {
+        self.focus.index()
+    }
+
+    pub fn set_focus_index(&mut self, ix: usize) {
+        self.focus.set_index(ix);
+    }
+}
+
+/// A PE binary and the state of its view.
+pub struct PeView {
+    pub pe: Pe,
+    pub state: PeInteractiveState,
+}
+
+impl PeView {
+    pub fn new(pe: Pe) -> PeView {
+        PeView {
+            pe,
+            state: PeInteractiveState::new(),
+        }
+    }
+}
+
+impl BinaryView for PeView {
+    fn type_name(&self) -> &'static str {
+        "PE"
+    }
+
+    fn summary(&self) -> String {
+        match &self.pe.optional_header {
+            Some(optional) => format!(
+                "PE, {:?}, {:?}, {:?}",
+                optional.format, self.pe.coff_header.machine, optional.subsystem
+            ),
+            None => format!("COFF, {:?}", self.pe.coff_header.machine),
+        }
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        Some(ByteOrder::Little)
+    }
+
+    fn dump(&self, _: &[u8], out: &mut String) {
+        dump::dump_pe(out, &self.pe);
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _: &[u8], _: &Patches, theme: &Theme) {
+        let mut widget = PeWidget::new(&self.pe, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command);
+        None
+    }
+
+    /// A section by its name, e.g. ".text".
+    fn named_region(&self, name: &str) -> Option<Range<usize>> {
+        let section = self.pe.section_headers.iter().find(|s| s.name == name)?;
+        let start = section.pointer_to_raw_data as usize;
+        Some(start..start.saturating_add(section.size_of_raw_data as usize))
+    }
+
+    fn describe_offset(&self, offset: usize) -> Option<String> {
+        let section = self.pe.section_headers.iter().find(|s| {
+            let start = s.pointer_to_raw_data as usize;
+            (start..start.saturating_add(s.size_of_raw_data as usize)).contains(&offset)
+        });
+        if let Some(section) = section {
+            return Some(format!(
+                "{} +0x{:X}",
+                section.name,
+                offset - section.pointer_to_raw_data as usize
+            ));
+        }
+        let headers = self.pe.optional_header.as_ref()?.size_of_headers as usize;
+        (offset < headers).then(|| format!("headers +0x{:X}", offset))
+    }
+
+    fn focus_index(&self) -> usize {
+        self.state.focus_index()
+    }
+
+    fn set_focus_index(&mut self, ix: usize) {
+        self.state.set_focus_index(ix);
+    }
+
+    fn selected_region(&self) -> Option<Range<usize>> {
+        self.state.selected_region(&self.pe)
+    }
+}
+
+pub struct PeWidget<'a> {
+    pub pe: &'a Pe,
+    pub theme: &'a Theme,
+    pub state: &'a mut PeInteractiveState,
+}
+
+impl<'a> PeWidget<'a> {
+    pub fn new(pe: &'a Pe, theme: &'a Theme, state: &'a mut PeInteractiveState) -> PeWidget<'a> {
+        PeWidget { pe, theme, state }
+    }
+
+    fn focus_style(&self, focus: Focus) -> Style {
+        if self.state.focus.is(focus) {
+            self.theme.style_focus()
+        } else {
+            self.theme.style_normal()
+        }
+    }
+
+    fn render_detail_view(&mut self, content_detail: Rect, buf: &mut Buffer) {
+        let detail_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::ImportFunctions))
+            .title("Details");
+
+        match self.state.focus.active() {
+            Focus::Header => self.render_header_detail(detail_block, content_detail, buf),
+            Focus::Sections => {
+                let selected = self.state.section_list_state.selected();
+                if let Some(section) = selected.and_then(|i| self.pe.section_headers.get(i)) {
+                    render_section_detail(section, detail_block, content_detail, buf);
+                }
+            }
+            Focus::Imports | Focus::ImportFunctions => {
+                self.state
+                    .pane_areas
+                    .push(Focus::ImportFunctions, content_detail);
+                let selected = self.state.import_list_state.selected();
+                if let Some(import) = selected.and_then(|i| self.pe.imports.get(i)) {
+                    render_import_detail(
+                        import,
+                        detail_block,
+                        content_detail,
+                        buf,
+                        &mut self.state.function_list_state,
+                        self.theme,
+                    );
+                }
+            }
+        }
+    }
+
+    fn render_header_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let coff = &self.pe.coff_header;
+        let mut fields = vec![
+            ("Machine:", format!("{:?}", coff.machine)),
+            ("Sections:", format!("{}", coff.number_of_sections)),
+            ("Timestamp:", format!("0x{:X}", coff.time_date_stamp)),
+            (
+                "Characteristics:",
+                characteristics_display(coff.characteristics),
+            ),
+        ];
+        if let Some(optional) = &self.pe.optional_header {
+            let (major, minor) = optional.linker_version;
+            fields.extend([
+                ("Format:", format!("{:?}", optional.format)),
+                ("Entry Point:", format!("0x{:X}", optional.entry_point)),
+                ("Image Base:", format!("0x{:X}", optional.image_base)),
+                ("Subsystem:", format!("{:?}", optional.subsystem)),
+                ("Linker:", format!("{}.{}", major, minor)),
+                ("Code Size:", format!("0x{:X}", optional.size_of_code)),
+                ("Image Size:", format!("0x{:X}", optional.size_of_image)),
+                (
+                    "Section Align:",
+                    format!("0x{:X}", optional.section_alignment),
+                ),
+                ("File Align:", format!("0x{:X}", optional.file_alignment)),
+                (
+                    "DLL Flags:",
+                    format!("0x{:X}", optional.dll_characteristics),
+                ),
+            ]);
+        }
+        let mut rows: Vec<Row> = fields
+            .into_iter()
+            .map(|(name, value)| Row::new(vec![name.to_string(), value]))
+            .collect();
+        // parts of the file that could not be read, e.g. the imports
+        rows.extend(self.pe.warnings.iter().map(|w| {
+            Row::new(vec!["Warning:".to_string(), w.clone()])
+                .style(Style::default().fg(Color::Yellow))
+        }));
+        let widths = [Constraint::Length(18), Constraint::Fill(1)];
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.header_scroll,
+        );
+    }
+}
+
+fn render_section_detail(
+    section: &SectionHeader,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+) {
+    let flags_text = &section_flags_display(section.characteristics);
+    let contents_text = &section_contents_display(section.characteristics);
+    let vaddr_text = &format!("0x{:X}", section.virtual_address);
+    let vsize_text = &format!("0x{:X}", section.virtual_size);
+    let offset_text = &format!("0x{:X}", section.pointer_to_raw_data);
+    let size_text = &format!("0x{:X}", section.size_of_raw_data);
+    let characteristics_text = &format!("0x{:X}", section.characteristics);
+    let rows = [
+        Row::new(vec!["Name:", &section.name]),
+        Row::new(vec!["Flags:", flags_text]),
+        Row::new(vec!["Contents:", contents_text]),
+        Row::new(vec!["Virtual Address:", vaddr_text]),
+        Row::new(vec!["Virtual Size:", vsize_text]),
+        Row::new(vec!["Offset:", offset_text]),
+        Row::new(vec!["Size:", size_text]),
+        Row::new(vec!["Characteristics:", characteristics_text]),
+    ];
+    let widths = [Constraint::Length(18), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn render_import_detail(
+    import: &Import,
+    block: Block,
+    content_detail: Rect,
+    buf: &mut Buffer,
+    state: &mut ListState,
+    theme: &Theme,
+) {
+    let functions = &import.functions;
+    render_lazy_list(
+        block.title(format!("{} ({})", import.dll, functions.len())),
+        functions.len(),
+        |i| match &functions[i] {
+            ImportedFunction::Name(name) => name.clone(),
+            ImportedFunction::Ordinal(ordinal) => format!("#{}", ordinal),
+        },
+        content_detail,
+        buf,
+        state,
+        theme,
+    );
+}
+
+pub fn characteristics_display(characteristics: u16) -> String {
+    let mut names = Vec::new();
+    if characteristics & IMAGE_FILE_EXECUTABLE_IMAGE != 0 {
+        names.push("executable");
+    }
+    if characteristics & IMAGE_FILE_DLL != 0 {
+        names.push("dll");
+    }
+    if characteristics & IMAGE_FILE_LARGE_ADDRESS_AWARE != 0 {
+        names.push("large address aware");
+    }
+    format!("0x{:X} {}", characteristics, names.join(", "))
+}
+
+fn section_flags_display(flags: u32) -> String {
+    let mut result = String::with_capacity(3);
+    result.push(if flags & IMAGE_SCN_MEM_READ != 0 {
+        'R'
+    } else {
+        '-'
+    });
+    result.push(if flags & IMAGE_SCN_MEM_WRITE != 0 {
+        'W'
+    } else {
+        '-'
+    });
+    result.push(if flags & IMAGE_SCN_MEM_EXECUTE != 0 {
+        'X'
+    } else {
+        '-'
+    });
+    result
+}
+
+fn section_contents_display(flags: u32) -> String {
+    let mut names = Vec::new();
+    if flags & IMAGE_SCN_CNT_CODE != 0 {
+        names.push("code");
+    }
+    if flags & IMAGE_SCN_CNT_INITIALIZED_DATA != 0 {
+        names.push("initialized data");
+    }
+    if flags & IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0 {
+        names.push("uninitialized data");
+    }
+    names.join(", ")
+}
+
+impl<'a> Widget for &mut PeWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let content_layout = Layout::horizontal(self.state.split.constraints());
+        let [content_file, content_detail] = content_layout.areas(area);
+
+        let file_layout = Layout::vertical([
+            Constraint::Max(3),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ]);
+        let [pe_header, pe_sections, pe_imports] = file_layout.areas(content_file);
+
+        let pane_areas = &mut self.state.pane_areas;
+        pane_areas.clear();
+        pane_areas.push(Focus::Header, pe_header);
+        pane_areas.push(Focus::Sections, pe_sections);
+        pane_areas.push(Focus::Imports, pe_imports);
+
+        let header_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Header))
+            .title("Header");
+
+        let header_text = match &self.pe.optional_header {
+            Some(optional) => format!(
+                "{:?} | {:?} | {:?}",
+                optional.format, self.pe.coff_header.machine, optional.subsystem
+            ),
+            None => format!("COFF | {:?}", self.pe.coff_header.machine),
+        };
+        Paragraph::new(header_text)
+            .block(header_block)
+            .render(pe_header, buf);
+
+        let section_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Sections))
+            .title(format!("Sections ({})", self.pe.section_headers.len()));
+        let section_list = List::new(section_list(&self.pe.section_headers))
+            .block(section_block)
+            .highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            section_list,
+            pe_sections,
+            buf,
+            &mut self.state.section_list_state,
+        );
+        render_list_scrollbar(
+            pe_sections,
+            buf,
+            self.pe.section_headers.len(),
+            &self.state.section_list_state,
+        );
+
+        let import_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Imports))
+            .title(format!("Imports ({})", self.pe.imports.len()));
+        let import_list = List::new(self.pe.imports.iter().map(|i| i.dll.as_str()))
+            .block(import_block)
+            .highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            import_list,
+            pe_imports,
+            buf,
+            &mut self.state.import_list_state,
+        );
+        render_list_scrollbar(
+            pe_imports,
+            buf,
+            self.pe.imports.len(),
+            &self.state.import_list_state,
+        );
+
+        self.render_detail_view(content_detail, buf);
+    }
+}
+
+pub fn section_list(sections: &[SectionHeader]) -> Vec<String> {
+    let mut result = Vec::with_capacity(sections.len());
+    for section in sections {
+        result.push(format!(
+            "{} | {}",
+            section.name,
+            section_flags_display(section.characteristics)
+        ));
+    }
+    result
+}
@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+use crossterm::event::{MouseButton, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, StatefulWidget, Widget},
+};
+
+use crate::{
+    InteractiveCommand,
+    binary_view::{BinaryView, ByteOrder},
+    dump,
+    hex::{GroupBytes, Hex, HexState},
+    keymap::Action,
+    patch::Patches,
+    theme::Theme,
+};
+
+/// State of a file in no known format, shown as hex only.
+pub struct RawInteractiveState {
+    hex_state: HexState,
+    // unfocused while the command line has the focus
+    focused: bool,
+}
+
+impl RawInteractiveState {
+    pub fn new() -> RawInteractiveState {
+        RawInteractiveState {
+            hex_state: HexState::default(),
+            focused: true,
+        }
+    }
+
+    pub fn handle_command(&mut self, command: InteractiveCommand) {
+        match command {
+            InteractiveCommand::Action(action) => match action {
+                Action::SelectNext => self.hex_state.scroll_down(1),
+                Action::SelectPrev => self.hex_state.scroll_up(1),
+                Action::PageDown => self.hex_state.page_down(),
+                Action::PageUp => self.hex_state.page_up(),
+                Action::Home => self.hex_state.scroll_to_start(),
+                Action::End => self.hex_state.scroll_to_end(),
+                Action::Left => self.hex_state.scroll_left(),
+                Action::Right => self.hex_state.scroll_right(),
+                _ => { /* ignore */ }
+            },
+            InteractiveCommand::Focus => self.focused = true,
+            InteractiveCommand::Unfocus => self.focused = false,
+            InteractiveCommand::Mouse(mouse) => {
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                    self.focused = true;
+                }
+            }
+            InteractiveCommand::Toggle(_) => { /* ignore */ }
+        }
+    }
+
+    pub fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.hex_state.set_group_bytes(group_bytes);
+    }
+
+    pub fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.hex_state.set_byte_colors(byte_colors);
+    }
+
+    pub fn relayout(&mut self) {
+        self.hex_state.relayout();
+    }
+
+    /// The hex view of the file, it is always in focus.
+    pub fn focused_hex(&mut self) -> Option<&mut HexState> {
+        Some(&mut self.hex_state)
+    }
+
+    /// File offset of the first byte shown.
+    pub fn shown_offset(&self) -> Option<usize> {
+        Some(self.hex_state.top_offset())
+    }
+
+    /// File offset of the edit cursor, or of the first shown byte while not
+    /// editing.
+    pub fn inspected_offset(&self) -> Option<usize> {
+        Some(
+            self.hex_state
+                .cursor()
+                .unwrap_or(self.hex_state.top_offset()),
+        )
+    }
+
+    /// Scrolls the hex view to the file `offset`.
+    pub fn show_offset(&mut self, offset: usize) -> bool {
+        self.hex_state.scroll_to_offset(offset);
+        true
+    }
+}
+
+/// A file in none of the known formats and the state of its view.
+pub struct RawView {
+    pub state: RawInteractiveState,
+}
+
+impl RawView {
+    pub fn new() -> RawView {
+        RawView {
+            state: RawInteractiveState::new(),
+        }
+    }
+}
+
+impl BinaryView for RawView {
+    fn type_name(&self) -> &'static str {
+        "raw"
+    }
+
+    fn summary(&self) -> String {
+        "unknown format, shown as raw hex".to_string()
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        None
+    }
+
+    fn dump(&self, data: &[u8], out: &mut String) {
+        dump::dump_raw(out, data);
+    }
+
+    fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        data: &[u8],
+        patches: &Patches,
+        theme: &Theme,
+    ) {
+        let mut widget = RawWidget::new(data, patches, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command);
+        None
+    }
+
+    fn set_hex_group_bytes(&mut self, group_bytes: GroupBytes) {
+        self.state.set_hex_group_bytes(group_bytes);
+    }
+
+    fn set_hex_byte_colors(&mut self, byte_colors: bool) {
+        self.state.set_hex_byte_colors(byte_colors);
+    }
+
+    fn relayout(&mut self) {
+        self.state.relayout();
+    }
+
+    fn focused_hex(&mut self) -> Option<&mut HexState> {
+        self.state.focused_hex()
+    }
+
+    // the hex view shows the whole file
+    fn focused_hex_start(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn shown_offset(&self) -> Option<usize> {
+        self.state.shown_offset()
+    }
+
+    fn inspected_offset(&self) -> Option<usize> {
+        self.state.inspected_offset()
+    }
+
+    fn show_offset(&mut self, offset: usize) -> bool {
+        self.state.show_offset(offset)
+    }
+}
+
+pub struct RawWidget<'a> {
+    /// The (possibly edited) bytes of the file
+    pub data: &'a [u8],
+    pub patches: &'a Patches,
+    pub theme: &'a Theme,
+    pub state: &'a mut RawInteractiveState,
+}
+
+impl<'a> RawWidget<'a> {
+    pub fn new(
+        data: &'a [u8],
+        patches: &'a Patches,
+        theme: &'a Theme,
+        state: &'a mut RawInteractiveState,
+    ) -> RawWidget<'a> {
+        RawWidget {
+            data,
+            patches,
+            theme,
+            state,
+        }
+    }
+}
+
+impl<'a> Widget for &mut RawWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let style = if self.state.focused {
+            self.theme.style_focus()
+        } else {
+            self.theme.style_normal()
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(style)
+            .title(format!("Raw ({} bytes, unknown format)", self.data.len()));
+        let modified: Vec<(Range<usize>, Style)> = self
+            .patches
+            .modified_ranges(0..self.data.len())
+            .into_iter()
+            .map(|range| (range, Style::default().fg(Color::Yellow)))
+            .collect();
+        let hex = Hex::new(self.data).block(block).highlights(&modified);
+        StatefulWidget::render(&hex, area, buf, &mut self.state.hex_state);
+    }
+}
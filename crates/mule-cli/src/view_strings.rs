@@ -0,0 +1,113 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{Block, BorderType, ListState, Widget},
+};
+
+use crate::{theme::Theme, view::render_lazy_list};
+
+/// Upper bound of strings collected by a scan, keeps large files responsive.
+pub const MAX_STRINGS: usize = 100_000;
+
+pub struct FoundString {
+    pub offset: usize,
+    pub text: String,
+}
+
+/// Scans `data` for runs of at least `min_len` printable ASCII characters.
+/// `base_offset` is the file offset of `data`. Returns the strings and
+/// whether the scan stopped at `MAX_STRINGS`.
+pub fn find_strings(data: &[u8], base_offset: usize, min_len: usize) -> (Vec<FoundString>, bool) {
+    let mut result = Vec::new();
+    let mut start = None;
+    for (i, b) in data.iter().chain([0u8].iter()).enumerate() {
+        let printable = b.is_ascii_graphic() || *b == b' ' || *b == b'\t';
+        match (start, printable) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                if i - s >= min_len {
+                    if result.len() == MAX_STRINGS {
+                        return (result, true);
+                    }
+                    result.push(FoundString {
+                        offset: base_offset + s,
+                        text: String::from_utf8_lossy(&data[s..i]).to_string(),
+                    });
+                }
+                start = None;
+            }
+            _ => { /* continue the current run */ }
+        }
+    }
+    (result, false)
+}
+
+pub struct StringsState {
+    pub strings: Vec<FoundString>,
+    pub list_state: ListState,
+    title: String,
+    // updated on render, used for paging
+    page_rows: usize,
+}
+
+impl StringsState {
+    pub fn new(strings: Vec<FoundString>, title: String) -> StringsState {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        StringsState {
+            strings,
+            list_state,
+            title,
+            page_rows: 0,
+        }
+    }
+
+    pub fn page_down(&mut self) {
+        self.list_state.scroll_down_by(self.page_rows.max(1) as u16);
+    }
+
+    pub fn page_up(&mut self) {
+        self.list_state.scroll_up_by(self.page_rows.max(1) as u16);
+    }
+
+    pub fn selected(&self) -> Option<&FoundString> {
+        self.strings.get(self.list_state.selected()?)
+    }
+}
+
+pub struct StringsWidget<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a mut StringsState,
+}
+
+impl<'a> StringsWidget<'a> {
+    pub fn new(theme: &'a Theme, state: &'a mut StringsState) -> StringsWidget<'a> {
+        StringsWidget { theme, state }
+    }
+}
+
+impl<'a> Widget for &mut StringsWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.theme.style_focus())
+            .title(format!(
+                "{} ({})",
+                self.state.title,
+                self.state.strings.len()
+            ))
+            .title_bottom("Enter: show in hex view, Esc: close");
+        self.state.page_rows = block.inner(area).height as usize;
+
+        let strings = &self.state.strings;
+        render_lazy_list(
+            block,
+            strings.len(),
+            |i| format!("0x{:08X} {}", strings[i].offset, strings[i].text),
+            area,
+            buf,
+            &mut self.state.list_state,
+            self.theme,
+        );
+    }
+}
@@ -0,0 +1,510 @@
+use std::ops::Range;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use mule_wasm::{ExportKind, ImportKind, Limits, Section, SectionId, Wasm};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Block, BorderType, List, ListState, Paragraph, Row, StatefulWidget, Table, Widget},
+};
+
+use crate::{
+    InteractiveCommand,
+    binary_view::{BinaryView, ByteOrder},
+    dump,
+    focus::FocusRing,
+    keymap::Action,
+    patch::Patches,
+    theme::Theme,
+    view::{
+        PaneAreas, PaneScroll, Split, list_index_at, page_list, render_lazy_list,
+        render_list_scrollbar, render_scrolled_table,
+    },
+};
+
+#[derive(PartialEq, Copy, Clone)]
+enum Focus {
+    Header,
+    Sections,
+    /// The entries of the selected section, e.g. the exports
+    Detail,
+}
+
+static FOCUS_CYCLE_ORDER: [Focus; 3] = [Focus::Header, Focus::Sections, Focus::Detail];
+
+pub struct WasmInteractiveState {
+    focus: FocusRing<Focus>,
+    section_list_state: ListState,
+    // of the header table in the detail pane
+    header_scroll: PaneScroll,
+    // of the types and functions in the detail pane
+    detail_list_state: ListState,
+    // of the import and export tables in the detail pane
+    detail_scroll: PaneScroll,
+    pane_areas: PaneAreas<Focus>,
+    split: Split,
+}
+
+impl WasmInteractiveState {
+    pub fn new() -> WasmInteractiveState {
+        let mut section_list_state = ListState::default();
+        section_list_state.select(Some(0));
+        let mut detail_list_state = ListState::default();
+        detail_list_state.select(Some(0));
+        WasmInteractiveState {
+            section_list_state,
+            header_scroll: PaneScroll::default(),
+            detail_list_state,
+            detail_scroll: PaneScroll::default(),
+            pane_areas: PaneAreas::new(),
+            split: Split::default(),
+            focus: FocusRing::new(&FOCUS_CYCLE_ORDER, Focus::Sections),
+        }
+    }
+
+    pub fn handle_command(&mut self, command: InteractiveCommand) {
+        let selected_section = self.section_list_state.selected();
+        match command {
+            InteractiveCommand::Action(action) => match action {
+                Action::FocusNext => self.focus.next(),
+                Action::FocusPrev => self.focus.prev(),
+                Action::WidenFilePane => self.split.widen_file_pane(),
+                Action::NarrowFilePane => self.split.narrow_file_pane(),
+                Action::SelectNext => match self.focus.current() {
+                    Some(Focus::Sections) => self.section_list_state.select_next(),
+                    Some(Focus::Detail) => self.detail_list_state.select_next(),
+                    _ => { /* ignore */ }
+                },
+                Action::SelectPrev => match self.focus.current() {
+                    Some(Focus::Sections) => self.section_list_state.select_previous(),
+                    Some(Focus::Detail) => self.detail_list_state.select_previous(),
+                    _ => { /* ignore */ }
+                },
+                Action::PageDown | Action::PageUp | Action::Home | Action::End => {
+                    match self.focus.current() {
+                        Some(Focus::Header) => self.header_scroll.handle_action(action),
+                        Some(Focus::Sections) => page_list(
+                            &mut self.section_list_state,
+                            action,
+                            self.pane_areas.page_rows(Focus::Sections),
+                        ),
+                        Some(Focus::Detail) => {
+                            let rows = self.pane_areas.page_rows(Focus::Detail);
+                            page_list(&mut self.detail_list_state, action, rows);
+                            self.detail_scroll.handle_action(action);
+                        }
+                        None => { /* ignore */ }
+                    }
+                }
+                _ => { /* ignore */ }
+            },
+            InteractiveCommand::Focus => self.focus.focus(),
+            InteractiveCommand::Unfocus => self.focus.unfocus(),
+            InteractiveCommand::Mouse(mouse) => self.handle_mouse(mouse),
+            InteractiveCommand::Toggle(_) => { /* ignore */ }
+        }
+        // the entries of another section are shown from the start
+        if self.section_list_state.selected() != selected_section {
+            self.detail_list_state.select(Some(0));
+            self.detail_scroll.reset();
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let Some((pane, area)) = self.pane_areas.pane_at(mouse.column, mouse.row) else {
+            return;
+        };
+        self.focus.set(pane);
+        let list = match pane {
+            Focus::Sections => &mut self.section_list_state,
+            Focus::Detail => &mut self.detail_list_state,
+            _ => return,
+        };
+        if let Some(ix) = list_index_at(area, list, mouse.row) {
+            list.select(Some(ix));
+        }
+    }
+
+    /// File byte range of the contents of the selected section, if the
+    /// section list is (or was before unfocusing) in focus.
+    pub fn selected_region(&self, wasm: &Wasm) -> Option<Range<usize>> {
+        if self.focus.active() != Focus::Sections {
+            return None;
+        }
+        let section = wasm.sections.get(self.section_list_state.selected()?)?;
+        Some(section.offset..section.offset + section.size)
+    }
+
+    pub fn focus_index(&self) -> usize {
+        self.focus.index()
+    }
+
+    pub fn set_focus_index(&mut self, ix: usize) {
+        self.focus.set_index(ix);
+    }
+}
+
+/// A WebAssembly module and the state of its view.
+pub struct WasmView {
+    pub wasm: Wasm,
+    pub state: WasmInteractiveState,
+}
+
+impl WasmView {
+    pub fn new(wasm: Wasm) -> WasmView {
+        WasmView {
+            wasm,
+            state: WasmInteractiveState::new(),
+        }
+    }
+}
+
+impl BinaryView for WasmView {
+    fn type_name(&self) -> &'static str {
+        "WebAssembly"
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "WebAssembly, {} sections, {} exports",
+            self.wasm.sections.len(),
+            self.wasm.exports.len()
+        )
+    }
+
+    fn byte_order(&self) -> Option<ByteOrder> {
+        Some(ByteOrder::Little)
+    }
+
+    fn dump(&self, _: &[u8], out: &mut String) {
+        dump::dump_wasm(out, &self.wasm);
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _: &[u8], _: &Patches, theme: &Theme) {
+        let mut widget = WasmWidget::new(&self.wasm, theme, &mut self.state);
+        widget.render(area, buf);
+    }
+
+    fn handle_command(&mut self, command: InteractiveCommand) -> Option<String> {
+        self.state.handle_command(command);
+        None
+    }
+
+    /// A custom section by its name or a known section by its lower case id,
+    /// e.g. "code".
+    fn named_region(&self, name: &str) -> Option<Range<usize>> {
+        let section = self
+            .wasm
+            .sections
+            .iter()
+            .find(|s| s.name == name || format!("{:?}", s.id).to_ascii_lowercase() == name)?;
+        Some(section.offset..section.offset.saturating_add(section.size))
+    }
+
+    fn describe_offset(&self, offset: usize) -> Option<String> {
+        let section = self
+            .wasm
+            .sections
+            .iter()
+            .find(|s| (s.offset..s.offset.saturating_add(s.size)).contains(&offset))?;
+        let name = if section.name.is_empty() {
+            format!("{:?}", section.id).to_ascii_lowercase()
+        } else {
+            section.name.clone()
+        };
+        Some(format!("{} section +0x{:X}", name, offset - section.offset))
+    }
+
+    fn focus_index(&self) -> usize {
+        self.state.focus_index()
+    }
+
+    fn set_focus_index(&mut self, ix: usize) {
+        self.state.set_focus_index(ix);
+    }
+
+    fn selected_region(&self) -> Option<Range<usize>> {
+        self.state.selected_region(&self.wasm)
+    }
+}
+
+pub struct WasmWidget<'a> {
+    pub wasm: &'a Wasm,
+    pub theme: &'a Theme,
+    pub state: &'a mut WasmInteractiveState,
+}
+
+impl<'a> WasmWidget<'a> {
+    pub fn new(
+        wasm: &'a Wasm,
+        theme: &'a Theme,
+        state: &'a mut WasmInteractiveState,
+    ) -> WasmWidget<'a> {
+        WasmWidget { wasm, theme, state }
+    }
+
+    fn focus_style(&self, focus: Focus) -> Style {
+        if self.state.focus.is(focus) {
+            self.theme.style_focus()
+        } else {
+            self.theme.style_normal()
+        }
+    }
+
+    fn render_detail_view(&mut self, content_detail: Rect, buf: &mut Buffer) {
+        let detail_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Detail))
+            .title("Details");
+
+        match self.state.focus.active() {
+            Focus::Header => self.render_header_detail(detail_block, content_detail, buf),
+            Focus::Sections | Focus::Detail => {
+                self.state.pane_areas.push(Focus::Detail, content_detail);
+                let selected = self.state.section_list_state.selected();
+                let Some(section) = selected.and_then(|i| self.wasm.sections.get(i)) else {
+                    return;
+                };
+                match section.id {
+                    SectionId::Type => self.render_types(detail_block, content_detail, buf),
+                    SectionId::Import => self.render_imports(detail_block, content_detail, buf),
+                    SectionId::Function => self.render_functions(detail_block, content_detail, buf),
+                    SectionId::Export => self.render_exports(detail_block, content_detail, buf),
+                    _ => render_section_detail(section, detail_block, content_detail, buf),
+                }
+            }
+        }
+    }
+
+    fn render_header_detail(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let code_size: usize = self
+            .wasm
+            .sections
+            .iter()
+            .filter(|s| s.id == SectionId::Code)
+            .map(|s| s.size)
+            .sum();
+        let fields = [
+            ("Version:", format!("{}", self.wasm.version)),
+            ("Sections:", format!("{}", self.wasm.sections.len())),
+            ("Types:", format!("{}", self.wasm.types.len())),
+            (
+                "Imports:",
+                format!(
+                    "{} ({} functions)",
+                    self.wasm.imports.len(),
+                    self.wasm.imported_function_count()
+                ),
+            ),
+            ("Functions:", format!("{}", self.wasm.functions.len())),
+            ("Exports:", format!("{}", self.wasm.exports.len())),
+            ("Code Size:", format!("0x{:X}", code_size)),
+        ];
+        let rows = fields
+            .into_iter()
+            .map(|(name, value)| Row::new(vec![name.to_string(), value]));
+        let widths = [Constraint::Length(18), Constraint::Fill(1)];
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.header_scroll,
+        );
+    }
+
+    fn render_types(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let types = &self.wasm.types;
+        render_lazy_list(
+            block.title(format!("Types ({})", types.len())),
+            types.len(),
+            |i| format!("{}: {}", i, types[i]),
+            content_detail,
+            buf,
+            &mut self.state.detail_list_state,
+            self.theme,
+        );
+    }
+
+    fn render_imports(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let rows = self.wasm.imports.iter().map(|import| {
+            let kind = match &import.kind {
+                ImportKind::Function(type_index) => match self.wasm.types.get(*type_index as usize)
+                {
+                    Some(func_type) => format!("func {}", func_type),
+                    None => format!("func type {}", type_index),
+                },
+                ImportKind::Table(val_type, limits) => {
+                    format!("table {} {}", val_type, limits_display(limits))
+                }
+                ImportKind::Memory(limits) => format!("memory {}", limits_display(limits)),
+                ImportKind::Global(val_type, true) => format!("global mut {}", val_type),
+                ImportKind::Global(val_type, false) => format!("global {}", val_type),
+                ImportKind::Tag(type_index) => format!("tag type {}", type_index),
+            };
+            Row::new(vec![format!("{}.{}", import.module, import.name), kind])
+        });
+        let widths = [Constraint::Fill(1), Constraint::Fill(1)];
+        let block = block.title(format!("Imports ({})", self.wasm.imports.len()));
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.detail_scroll,
+        );
+    }
+
+    fn render_functions(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        // the functions of the module follow the imported functions in the
+        // function index space
+        let wasm = self.wasm;
+        let imported = wasm.imported_function_count();
+        render_lazy_list(
+            block.title(format!("Functions ({})", wasm.functions.len())),
+            wasm.functions.len(),
+            |i| {
+                let type_index = wasm.functions[i];
+                match wasm.types.get(type_index as usize) {
+                    Some(func_type) => format!("{}: {}", imported + i, func_type),
+                    None => format!("{}: type {}", imported + i, type_index),
+                }
+            },
+            content_detail,
+            buf,
+            &mut self.state.detail_list_state,
+            self.theme,
+        );
+    }
+
+    fn render_exports(&mut self, block: Block, content_detail: Rect, buf: &mut Buffer) {
+        let rows = self.wasm.exports.iter().map(|export| {
+            let signature = match export.kind {
+                ExportKind::Function => self
+                    .wasm
+                    .function_type(export.index)
+                    .map(|func_type| func_type.to_string())
+                    .unwrap_or_default(),
+                _ => String::new(),
+            };
+            Row::new(vec![
+                export.name.clone(),
+                format!("{:?} {}", export.kind, export.index),
+                signature,
+            ])
+        });
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(14),
+            Constraint::Fill(1),
+        ];
+        let block = block.title(format!("Exports ({})", self.wasm.exports.len()));
+        render_scrolled_table(
+            rows,
+            &widths,
+            block,
+            content_detail,
+            buf,
+            &mut self.state.detail_scroll,
+        );
+    }
+}
+
+fn render_section_detail(section: &Section, block: Block, content_detail: Rect, buf: &mut Buffer) {
+    let id_text = &format!("{:?}", section.id);
+    let offset_text = &format!("0x{:X}", section.offset);
+    let size_text = &format!("0x{:X}", section.size);
+    let count_text = &section.count.map(|c| c.to_string()).unwrap_or_default();
+    let mut rows = vec![
+        Row::new(vec!["Id:", id_text]),
+        Row::new(vec!["Offset:", offset_text]),
+        Row::new(vec!["Size:", size_text]),
+    ];
+    if section.id == SectionId::Custom {
+        rows.insert(1, Row::new(vec!["Name:", &section.name]));
+    }
+    if section.count.is_some() {
+        rows.push(Row::new(vec!["Entries:", count_text]));
+    }
+    let widths = [Constraint::Length(18), Constraint::Fill(1)];
+    let table = Table::new(rows, widths).block(block);
+    Widget::render(table, content_detail, buf);
+}
+
+fn limits_display(limits: &Limits) -> String {
+    match limits.max {
+        Some(max) => format!("{}..{}", limits.min, max),
+        None => format!("{}..", limits.min),
+    }
+}
+
+impl<'a> Widget for &mut WasmWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let content_layout = Layout::horizontal(self.state.split.constraints());
+        let [content_file, content_detail] = content_layout.areas(area);
+
+        let file_layout = Layout::vertical([Constraint::Max(3), Constraint::Fill(1)]);
+        let [wasm_header, wasm_sections] = file_layout.areas(content_file);
+
+        let pane_areas = &mut self.state.pane_areas;
+        pane_areas.clear();
+        pane_areas.push(Focus::Header, wasm_header);
+        pane_areas.push(Focus::Sections, wasm_sections);
+
+        let header_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Header))
+            .title("Header");
+        Paragraph::new(format!(
+            "WebAssembly | version {} | {} functions",
+            self.wasm.version,
+            self.wasm.imported_function_count() + self.wasm.functions.len()
+        ))
+        .block(header_block)
+        .render(wasm_header, buf);
+
+        let section_block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .style(self.focus_style(Focus::Sections))
+            .title(format!("Sections ({})", self.wasm.sections.len()));
+        let section_list = List::new(section_list(&self.wasm.sections))
+            .block(section_block)
+            .highlight_style(self.theme.style_highlight());
+        StatefulWidget::render(
+            section_list,
+            wasm_sections,
+            buf,
+            &mut self.state.section_list_state,
+        );
+        render_list_scrollbar(
+            wasm_sections,
+            buf,
+            self.wasm.sections.len(),
+            &self.state.section_list_state,
+        );
+
+        self.render_detail_view(content_detail, buf);
+    }
+}
+
+pub fn section_list(sections: &[Section]) -> Vec<String> {
+    let mut result = Vec::with_capacity(sections.len());
+    for section in sections {
+        let name = match section.id {
+            SectionId::Custom => format!("Custom \"{}\"", section.name),
+            id => format!("{:?}", id),
+        };
+        let entries = match section.count {
+            Some(count) => format!(" ({})", count),
+            None => String::new(),
+        };
+        result.push(format!("{}{} | 0x{:X}", name, entries, section.size));
+    }
+    result
+}
@@ -0,0 +1,678 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Elf {
+    pub header: Header,
+    pub program_headers: Vec<ProgramHeader>,
+    pub section_headers: Vec<SectionHeader>,
+}
+
+// Header
+
+/// `\x7fELF` read as little-endian u32
+pub const MAGIC_HEADER: u32 = 0x464c457f;
+
+const IDENT_SIZE: usize = 16;
+
+#[derive(Serialize)]
+pub struct Header {
+    pub class: Class,
+    pub endianness: Endianness,
+    pub os_abi: u8,
+    pub file_type: FileType,
+    pub machine: Machine,
+    pub entry_point: u64,
+    pub ph_offset: u64,
+    pub sh_offset: u64,
+    pub flags: u32,
+    pub ph_entry_size: u16,
+    pub ph_num: u16,
+    pub sh_entry_size: u16,
+    pub sh_num: u16,
+    pub sh_str_index: u16,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Class {
+    Elf32,
+    Elf64,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum FileType {
+    None,
+    Relocatable,
+    Executable,
+    SharedObject,
+    Core,
+    Unknown(u16),
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Machine {
+    None,
+    X86,
+    MIPS,
+    PowerPC,
+    PowerPC64,
+    ARM,
+    X86_64,
+    AArch64,
+    RISCV,
+    Unknown(u16),
+}
+
+// Program Headers
+
+#[derive(Serialize)]
+pub struct ProgramHeader {
+    pub segment_type: SegmentType,
+    pub flags: u32,
+    pub offset: u64,
+    pub vm_addr: u64,
+    pub phys_addr: u64,
+    pub file_size: u64,
+    pub mem_size: u64,
+    pub align: u64,
+}
+
+pub const PF_X: u32 = 0x1;
+pub const PF_W: u32 = 0x2;
+pub const PF_R: u32 = 0x4;
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum SegmentType {
+    Null,
+    Load,
+    Dynamic,
+    Interp,
+    Note,
+    Shlib,
+    Phdr,
+    Tls,
+    GnuEhFrame,
+    GnuStack,
+    GnuRelro,
+    GnuProperty,
+    Unknown(u32),
+}
+
+// Section Headers
+
+#[derive(Serialize)]
+pub struct SectionHeader {
+    pub name: String,
+    pub section_type: SectionType,
+    pub flags: u64,
+    pub addr: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub addr_align: u64,
+    pub entry_size: u64,
+}
+
+pub const SHF_WRITE: u64 = 0x1;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum SectionType {
+    Null,
+    ProgBits,
+    SymTab,
+    StrTab,
+    Rela,
+    Hash,
+    Dynamic,
+    Note,
+    NoBits,
+    Rel,
+    Shlib,
+    DynSym,
+    InitArray,
+    FiniArray,
+    PreinitArray,
+    Group,
+    SymTabShndx,
+    GnuHash,
+    GnuVerDef,
+    GnuVerNeed,
+    GnuVerSym,
+    Unknown(u32),
+}
+
+pub fn load(data: &[u8]) -> Result<Elf, String> {
+    let header = parse_header(data)?;
+    let program_headers = parse_program_headers(data, &header)?;
+    let section_headers = parse_section_headers(data, &header)?;
+    Ok(Elf {
+        header,
+        program_headers,
+        section_headers,
+    })
+}
+
+fn parse_header(data: &[u8]) -> Result<Header, String> {
+    if data.len() < IDENT_SIZE {
+        return Err("not an elf file, file too small".to_string());
+    }
+    let magic = u32::from_le_bytes(data[0..4].try_into().unwrap());
+    if magic != MAGIC_HEADER {
+        return Err("not an elf file".to_string());
+    }
+    let class = parse_class(data[4])?;
+    let endianness = parse_endianness(data[5])?;
+    let os_abi = data[7];
+
+    let mut reader = DataReader::new_with_offset(data, IDENT_SIZE, endianness);
+    let header_size = match class {
+        Class::Elf32 => 52,
+        Class::Elf64 => 64,
+    };
+    if data.len() < header_size {
+        return Err(format!(
+            "elf header truncated, file has {} bytes",
+            data.len()
+        ));
+    }
+
+    let file_type = parse_file_type(reader.read_u16());
+    let machine = parse_machine(reader.read_u16());
+    reader.skip(4); // version
+    let entry_point = reader.read_addr(class);
+    let ph_offset = reader.read_addr(class);
+    let sh_offset = reader.read_addr(class);
+    let flags = reader.read_u32();
+    reader.skip(2); // header size
+    let ph_entry_size = reader.read_u16();
+    let ph_num = reader.read_u16();
+    let sh_entry_size = reader.read_u16();
+    let sh_num = reader.read_u16();
+    let sh_str_index = reader.read_u16();
+
+    Ok(Header {
+        class,
+        endianness,
+        os_abi,
+        file_type,
+        machine,
+        entry_point,
+        ph_offset,
+        sh_offset,
+        flags,
+        ph_entry_size,
+        ph_num,
+        sh_entry_size,
+        sh_num,
+        sh_str_index,
+    })
+}
+
+fn parse_class(v: u8) -> Result<Class, String> {
+    match v {
+        1 => Ok(Class::Elf32),
+        2 => Ok(Class::Elf64),
+        _ => Err(format!("unsupported elf class: 0x{:x}", v)),
+    }
+}
+
+fn parse_endianness(v: u8) -> Result<Endianness, String> {
+    match v {
+        1 => Ok(Endianness::Little),
+        2 => Ok(Endianness::Big),
+        _ => Err(format!("unsupported elf data encoding: 0x{:x}", v)),
+    }
+}
+
+fn parse_file_type(v: u16) -> FileType {
+    match v {
+        0 => FileType::None,
+        1 => FileType::Relocatable,
+        2 => FileType::Executable,
+        3 => FileType::SharedObject,
+        4 => FileType::Core,
+        _ => FileType::Unknown(v),
+    }
+}
+
+fn parse_machine(v: u16) -> Machine {
+    match v {
+        0x00 => Machine::None,
+        0x03 => Machine::X86,
+        0x08 => Machine::MIPS,
+        0x14 => Machine::PowerPC,
+        0x15 => Machine::PowerPC64,
+        0x28 => Machine::ARM,
+        0x3e => Machine::X86_64,
+        0xb7 => Machine::AArch64,
+        0xf3 => Machine::RISCV,
+        _ => Machine::Unknown(v),
+    }
+}
+
+/// Checks that `num` table entries of `entry_size` starting at `offset` are within the data.
+fn check_table(
+    data: &[u8],
+    name: &str,
+    offset: u64,
+    entry_size: u16,
+    min_entry_size: u16,
+    num: u16,
+) -> Result<(), String> {
+    if entry_size < min_entry_size {
+        return Err(format!(
+            "{} entry size too small: {}, expected at least {}",
+            name, entry_size, min_entry_size
+        ));
+    }
+    let end = offset.saturating_add(entry_size as u64 * num as u64);
+    if end > data.len() as u64 {
+        return Err(format!(
+            "{} table out of bounds: ends at 0x{:x}, file has 0x{:x} bytes",
+            name,
+            end,
+            data.len()
+        ));
+    }
+    Ok(())
+}
+
+fn parse_program_headers(data: &[u8], header: &Header) -> Result<Vec<ProgramHeader>, String> {
+    if header.ph_offset == 0 || header.ph_num == 0 {
+        return Ok(Vec::new());
+    }
+    check_table(
+        data,
+        "program header",
+        header.ph_offset,
+        header.ph_entry_size,
+        match header.class {
+            Class::Elf32 => 32,
+            Class::Elf64 => 56,
+        },
+        header.ph_num,
+    )?;
+
+    let mut result = Vec::with_capacity(header.ph_num as usize);
+    for i in 0..header.ph_num as usize {
+        let offset = header.ph_offset as usize + i * header.ph_entry_size as usize;
+        let mut reader = DataReader::new_with_offset(data, offset, header.endianness);
+        let program_header = match header.class {
+            Class::Elf32 => {
+                let segment_type = parse_segment_type(reader.read_u32());
+                let offset = reader.read_u32() as u64;
+                let vm_addr = reader.read_u32() as u64;
+                let phys_addr = reader.read_u32() as u64;
+                let file_size = reader.read_u32() as u64;
+                let mem_size = reader.read_u32() as u64;
+                let flags = reader.read_u32();
+                let align = reader.read_u32() as u64;
+                ProgramHeader {
+                    segment_type,
+                    flags,
+                    offset,
+                    vm_addr,
+                    phys_addr,
+                    file_size,
+                    mem_size,
+                    align,
+                }
+            }
+            Class::Elf64 => {
+                let segment_type = parse_segment_type(reader.read_u32());
+                let flags = reader.read_u32();
+                let offset = reader.read_u64();
+                let vm_addr = reader.read_u64();
+                let phys_addr = reader.read_u64();
+                let file_size = reader.read_u64();
+                let mem_size = reader.read_u64();
+                let align = reader.read_u64();
+                ProgramHeader {
+                    segment_type,
+                    flags,
+                    offset,
+                    vm_addr,
+                    phys_addr,
+                    file_size,
+                    mem_size,
+                    align,
+                }
+            }
+        };
+        result.push(program_header);
+    }
+    Ok(result)
+}
+
+fn parse_segment_type(v: u32) -> SegmentType {
+    match v {
+        0 => SegmentType::Null,
+        1 => SegmentType::Load,
+        2 => SegmentType::Dynamic,
+        3 => SegmentType::Interp,
+        4 => SegmentType::Note,
+        5 => SegmentType::Shlib,
+        6 => SegmentType::Phdr,
+        7 => SegmentType::Tls,
+        0x6474e550 => SegmentType::GnuEhFrame,
+        0x6474e551 => SegmentType::GnuStack,
+        0x6474e552 => SegmentType::GnuRelro,
+        0x6474e553 => SegmentType::GnuProperty,
+        _ => SegmentType::Unknown(v),
+    }
+}
+
+fn parse_section_headers(data: &[u8], header: &Header) -> Result<Vec<SectionHeader>, String> {
+    if header.sh_offset == 0 || header.sh_num == 0 {
+        return Ok(Vec::new());
+    }
+    check_table(
+        data,
+        "section header",
+        header.sh_offset,
+        header.sh_entry_size,
+        match header.class {
+            Class::Elf32 => 40,
+            Class::Elf64 => 64,
+        },
+        header.sh_num,
+    )?;
+
+    let mut name_offsets = Vec::with_capacity(header.sh_num as usize);
+    let mut result = Vec::with_capacity(header.sh_num as usize);
+    for i in 0..header.sh_num as usize {
+        let offset = header.sh_offset as usize + i * header.sh_entry_size as usize;
+        let mut reader = DataReader::new_with_offset(data, offset, header.endianness);
+        name_offsets.push(reader.read_u32() as usize);
+        let section_type = parse_section_type(reader.read_u32());
+        let flags = reader.read_addr(header.class);
+        let addr = reader.read_addr(header.class);
+        let offset = reader.read_addr(header.class);
+        let size = reader.read_addr(header.class);
+        let link = reader.read_u32();
+        let info = reader.read_u32();
+        let addr_align = reader.read_addr(header.class);
+        let entry_size = reader.read_addr(header.class);
+        result.push(SectionHeader {
+            name: String::new(),
+            section_type,
+            flags,
+            addr,
+            offset,
+            size,
+            link,
+            info,
+            addr_align,
+            entry_size,
+        });
+    }
+
+    // resolve the names from the section header string table
+    if let Some(str_table) = result.get(header.sh_str_index as usize) {
+        let start = str_table.offset as usize;
+        let end = start
+            .saturating_add(str_table.size as usize)
+            .min(data.len());
+        if start < end {
+            let names = &data[start..end];
+            for (section, name_offset) in result.iter_mut().zip(name_offsets) {
+                section.name = read_c_string(names, name_offset);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_section_type(v: u32) -> SectionType {
+    match v {
+        0 => SectionType::Null,
+        1 => SectionType::ProgBits,
+        2 => SectionType::SymTab,
+        3 => SectionType::StrTab,
+        4 => SectionType::Rela,
+        5 => SectionType::Hash,
+        6 => SectionType::Dynamic,
+        7 => SectionType::Note,
+        8 => SectionType::NoBits,
+        9 => SectionType::Rel,
+        10 => SectionType::Shlib,
+        11 => SectionType::DynSym,
+        14 => SectionType::InitArray,
+        15 => SectionType::FiniArray,
+        16 => SectionType::PreinitArray,
+        17 => SectionType::Group,
+        18 => SectionType::SymTabShndx,
+        0x6ffffff6 => SectionType::GnuHash,
+        0x6ffffffd => SectionType::GnuVerDef,
+        0x6ffffffe => SectionType::GnuVerNeed,
+        0x6fffffff => SectionType::GnuVerSym,
+        _ => SectionType::Unknown(v),
+    }
+}
+
+fn read_c_string(data: &[u8], offset: usize) -> String {
+    if offset >= data.len() {
+        return String::new();
+    }
+    let bytes = &data[offset..];
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+// helper
+
+pub struct DataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    endianness: Endianness,
+}
+
+impl DataReader<'_> {
+    pub fn new(data: &[u8], endianness: Endianness) -> DataReader<'_> {
+        DataReader::new_with_offset(data, 0, endianness)
+    }
+
+    pub fn new_with_offset(data: &[u8], offset: usize, endianness: Endianness) -> DataReader<'_> {
+        DataReader {
+            data,
+            offset,
+            endianness,
+        }
+    }
+}
+
+impl DataReader<'_> {
+    /// Reads an address sized field, which is 4 bytes for 32-bit
+    /// and 8 bytes for 64-bit files.
+    pub fn read_addr(&mut self, class: Class) -> u64 {
+        match class {
+            Class::Elf32 => self.read_u32() as u64,
+            Class::Elf64 => self.read_u64(),
+        }
+    }
+
+    pub fn read_u64(&mut self) -> u64 {
+        let bytes = self.data[self.offset..(self.offset + 8)]
+            .try_into()
+            .unwrap();
+        self.offset += 8;
+        match self.endianness {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn read_u32(&mut self) -> u32 {
+        let bytes = self.data[self.offset..(self.offset + 4)]
+            .try_into()
+            .unwrap();
+        self.offset += 4;
+        match self.endianness {
+            Endianness::Little => u32::from_le_bytes(bytes),
+            Endianness::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn read_u16(&mut self) -> u16 {
+        let bytes = self.data[self.offset..(self.offset + 2)]
+            .try_into()
+            .unwrap();
+        self.offset += 2;
+        match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn read_u8(&mut self) -> u8 {
+        let u = self.data[self.offset];
+        self.offset += 1;
+        u
+    }
+
+    pub fn skip(&mut self, bytes: usize) {
+        self.offset += bytes;
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: &[u8] = b"\0.text\0.shstrtab\0";
+
+    /// A little-endian x86_64 executable with one load segment and the
+    /// sections null, `.text` and `.shstrtab`.
+    fn elf64_bytes() -> Vec<u8> {
+        let ph_offset = 64u64;
+        let names_offset = ph_offset + 56;
+        let sh_offset = 144u64;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"\x7fELF");
+        data.extend_from_slice(&[2, 1, 1, 0]); // 64-bit, little-endian, version, System V
+        data.resize(IDENT_SIZE, 0);
+        data.extend_from_slice(&2u16.to_le_bytes()); // executable
+        data.extend_from_slice(&0x3eu16.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&0x401000u64.to_le_bytes());
+        data.extend_from_slice(&ph_offset.to_le_bytes());
+        data.extend_from_slice(&sh_offset.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        for value in [64u16, 56, 1, 64, 3, 2] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        data.extend_from_slice(&1u32.to_le_bytes()); // load
+        data.extend_from_slice(&(PF_R | PF_X).to_le_bytes());
+        for value in [0u64, 0x400000, 0x400000, 0x1000, 0x1000, 0x1000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        data.extend_from_slice(NAMES);
+        data.resize(sh_offset as usize, 0);
+        let sections = [
+            (0u32, 0u32, 0u64, 0u64),
+            (1, 1, 0x1000, 0x10),
+            (7, 3, names_offset, NAMES.len() as u64),
+        ];
+        for (name, section_type, offset, size) in sections {
+            data.extend_from_slice(&name.to_le_bytes());
+            data.extend_from_slice(&section_type.to_le_bytes());
+            for value in [0u64, 0, offset, size] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(&[0; 24]); // link, info, addr align, entry size
+        }
+        data
+    }
+
+    #[test]
+    fn elf64_little_endian() {
+        let elf = load(&elf64_bytes()).unwrap();
+        let header = &elf.header;
+        assert_eq!(header.class, Class::Elf64);
+        assert_eq!(header.endianness, Endianness::Little);
+        assert_eq!(header.file_type, FileType::Executable);
+        assert_eq!(header.machine, Machine::X86_64);
+        assert_eq!(header.entry_point, 0x401000);
+        assert_eq!(header.ph_num, 1);
+        assert_eq!(header.sh_num, 3);
+
+        assert_eq!(elf.program_headers.len(), 1);
+        let segment = &elf.program_headers[0];
+        assert_eq!(segment.segment_type, SegmentType::Load);
+        assert_eq!(segment.flags, PF_R | PF_X);
+        assert_eq!(segment.vm_addr, 0x400000);
+
+        let names: Vec<&str> = elf
+            .section_headers
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, ["", ".text", ".shstrtab"]);
+        assert_eq!(elf.section_headers[1].section_type, SectionType::ProgBits);
+        assert_eq!(elf.section_headers[2].section_type, SectionType::StrTab);
+    }
+
+    #[test]
+    fn elf32_big_endian_header() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"\x7fELF");
+        data.extend_from_slice(&[1, 2, 1, 0]); // 32-bit, big-endian, version, System V
+        data.resize(IDENT_SIZE, 0);
+        data.extend_from_slice(&3u16.to_be_bytes()); // shared object
+        data.extend_from_slice(&0x14u16.to_be_bytes());
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(&0x10000400u32.to_be_bytes());
+        data.extend_from_slice(&[0; 8]); // no program or section headers
+        data.extend_from_slice(&0x8000_0000u32.to_be_bytes());
+        for value in [52u16, 32, 0, 40, 0, 0] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let elf = load(&data).unwrap();
+        let header = &elf.header;
+        assert_eq!(header.class, Class::Elf32);
+        assert_eq!(header.endianness, Endianness::Big);
+        assert_eq!(header.file_type, FileType::SharedObject);
+        assert_eq!(header.machine, Machine::PowerPC);
+        assert_eq!(header.entry_point, 0x10000400);
+        assert_eq!(header.flags, 0x8000_0000);
+        assert!(elf.program_headers.is_empty());
+        assert!(elf.section_headers.is_empty());
+    }
+
+    #[test]
+    fn section_table_out_of_bounds() {
+        let mut data = elf64_bytes();
+        data.truncate(data.len() - 1);
+        assert_eq!(
+            load(&data).err().unwrap(),
+            "section header table out of bounds: ends at 0x150, file has 0x14f bytes"
+        );
+    }
+
+    #[test]
+    fn program_header_entry_size_too_small() {
+        let mut data = elf64_bytes();
+        data[54..56].copy_from_slice(&32u16.to_le_bytes());
+        assert_eq!(
+            load(&data).err().unwrap(),
+            "program header entry size too small: 32, expected at least 56"
+        );
+    }
+}
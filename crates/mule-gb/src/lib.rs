@@ -1,8 +1,39 @@
+mod licensee;
 mod reader;
 
+use std::fmt;
+
 use reader::DataReader;
 use serde::Serialize;
 
+/// Error of `load`, with the file offset of the malformed data.
+#[derive(Debug)]
+pub struct ParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error at 0x{:X}: {}", self.offset, self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.to_string()
+    }
+}
+
 #[derive(Serialize)]
 pub struct GBBinary {
     pub restart_calls: RestartCalls,
@@ -32,14 +63,33 @@ pub struct Interrupts {
     pub joypad: [u8; 8],
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub enum LicenseeCode {
-    None,
-    Unknown,
-    Nintendo,
-    Capcom,
-    Bandai,
-    Namco,
+    /// The one byte code at 0x14B
+    Old(u8),
+    /// The two character code at 0x144, only used if the old code is 0x33
+    New(String),
+}
+
+impl LicenseeCode {
+    /// Name of the publisher, if the code is known.
+    pub fn publisher(&self) -> Option<&'static str> {
+        match self {
+            LicenseeCode::Old(code) => licensee::old_publisher(*code),
+            LicenseeCode::New(code) => licensee::new_publisher(code),
+        }
+    }
+}
+
+impl fmt::Display for LicenseeCode {
+    /// Formats the code as "Nintendo (01)" or "Unknown (XX)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let publisher = self.publisher().unwrap_or("Unknown");
+        match self {
+            LicenseeCode::Old(code) => write!(f, "{} ({:02X})", publisher, code),
+            LicenseeCode::New(code) => write!(f, "{} ({})", publisher, code),
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Copy)]
@@ -58,7 +108,7 @@ pub enum SGBFlag {
     SGBSupport,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Copy, Clone)]
 pub enum CartridgeType {
     ROMOnly,
     MBC1,
@@ -88,6 +138,8 @@ pub enum CartridgeType {
     BandaiTama5,
     HuC3,
     HuC1xRAMxBattery,
+    /// a reserved or undocumented type byte
+    Unknown(u8),
 }
 
 #[derive(Serialize, Copy, Clone)]
@@ -123,6 +175,11 @@ pub fn num_banks(rom_size: ROMSize) -> usize {
     }
 }
 
+/// The ROM file size in bytes as declared by the ROM size in the header.
+pub fn declared_rom_bytes(rom_size: ROMSize) -> usize {
+    num_banks(rom_size) * BANK_BYTES
+}
+
 #[derive(Serialize, Copy, Clone)]
 pub enum RAMSize {
     None,
@@ -133,6 +190,125 @@ pub enum RAMSize {
     KB128,
 }
 
+/// The external RAM size in KiB as declared by the RAM size in the header.
+pub fn ram_kib(ram_size: RAMSize) -> usize {
+    match ram_size {
+        RAMSize::None => 0,
+        RAMSize::KB2 => 2,
+        RAMSize::KB8 => 8,
+        RAMSize::KB32 => 32,
+        RAMSize::KB64 => 64,
+        RAMSize::KB128 => 128,
+    }
+}
+
+/// What the memory bank controller of a cartridge type can address.
+pub struct MbcCapabilities {
+    pub name: &'static str,
+    pub max_rom_banks: usize,
+    /// 0 if the cartridge has no external RAM, the built-in RAM of the MBC2
+    /// is not declared in the header.
+    pub max_ram_kib: usize,
+}
+
+/// The capabilities of the cartridge type, None for types whose limits are
+/// not documented well enough to check a header against them.
+pub fn mbc_capabilities(cartridge_type: CartridgeType) -> Option<MbcCapabilities> {
+    let (name, max_rom_banks, max_ram_kib) = match cartridge_type {
+        CartridgeType::ROMOnly => ("ROM only", 2, 0),
+        CartridgeType::ROMxRAM | CartridgeType::ROMxRAMxBattery => ("ROM+RAM", 2, 8),
+        CartridgeType::MBC1 => ("MBC1", 128, 0),
+        CartridgeType::MBC1xRAM | CartridgeType::MBC1xRAMxBattery => ("MBC1", 128, 32),
+        CartridgeType::MBC2 | CartridgeType::MBC2xBattery => ("MBC2", 16, 0),
+        CartridgeType::MMM01 => ("MMM01", 512, 0),
+        CartridgeType::MMM01xRAM | CartridgeType::MMM01xRAMxBattery => ("MMM01", 512, 128),
+        // the limits of the MBC30, the MBC3 only addresses 128 banks and 32 KiB
+        CartridgeType::MBC3 | CartridgeType::MBC3xTimerxBattery => ("MBC3", 256, 0),
+        CartridgeType::MBC3xRAM
+        | CartridgeType::MBC3xRAMxBattery
+        | CartridgeType::MBC3xTimerxRAMxBattery => ("MBC3", 256, 64),
+        CartridgeType::MBC5 | CartridgeType::MBC5xRumble => ("MBC5", 512, 0),
+        CartridgeType::MBC5xRAM
+        | CartridgeType::MBC5xRAMxBattery
+        | CartridgeType::MBC5xRumblexRAM
+        | CartridgeType::MBC5xRumblexRAMxBattery => ("MBC5", 512, 128),
+        CartridgeType::PocketCamera => ("Pocket Camera", 64, 128),
+        CartridgeType::HuC1xRAMxBattery => ("HuC1", 64, 32),
+        CartridgeType::MBC6
+        | CartridgeType::MBC7xSensorxRumblexRAMxBattery
+        | CartridgeType::BandaiTama5
+        | CartridgeType::HuC3
+        | CartridgeType::Unknown(_) => return None,
+    };
+    Some(MbcCapabilities {
+        name,
+        max_rom_banks,
+        max_ram_kib,
+    })
+}
+
+/// Combinations of cartridge type, ROM size and RAM size in the header that
+/// the cartridge can't physically have.
+pub fn header_warnings(header: &Header) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let Some(caps) = mbc_capabilities(header.cartridge_type) else {
+        return warnings;
+    };
+    let banks = num_banks(header.rom_size);
+    if banks > caps.max_rom_banks {
+        warnings.push(format!(
+            "{} addresses at most {} ROM banks, declared {}",
+            caps.name, caps.max_rom_banks, banks
+        ));
+    }
+    let ram = ram_kib(header.ram_size);
+    if matches!(
+        header.cartridge_type,
+        CartridgeType::MBC2 | CartridgeType::MBC2xBattery
+    ) {
+        if ram > 0 {
+            warnings.push(format!(
+                "MBC2 has built-in RAM, the RAM size should be 0 but is {} KiB",
+                ram
+            ));
+        }
+    } else if caps.max_ram_kib == 0 && ram > 0 {
+        warnings.push(format!(
+            "the cartridge type has no RAM, declared {} KiB",
+            ram
+        ));
+    } else if ram > caps.max_ram_kib {
+        warnings.push(format!(
+            "{} addresses at most {} KiB RAM, declared {} KiB",
+            caps.name, caps.max_ram_kib, ram
+        ));
+    } else if caps.max_ram_kib > 0 && ram == 0 {
+        warnings.push("the cartridge type has RAM, but the RAM size is 0".to_string());
+    }
+    warnings
+}
+
+/// Size of the battery-backed save in bytes, the size of the `.sav` file an
+/// emulator writes for the cartridge. None if the cartridge type has no
+/// battery. The built-in 512x4 bit RAM of the MBC2 is saved as one byte per
+/// nibble, for the MBC3 with timer but without RAM only the clock is kept.
+pub fn save_bytes(header: &Header) -> Option<usize> {
+    match header.cartridge_type {
+        CartridgeType::MBC2xBattery => Some(MBC2_SAVE_BYTES),
+        CartridgeType::MBC1xRAMxBattery
+        | CartridgeType::ROMxRAMxBattery
+        | CartridgeType::MMM01xRAMxBattery
+        | CartridgeType::MBC3xTimerxBattery
+        | CartridgeType::MBC3xTimerxRAMxBattery
+        | CartridgeType::MBC3xRAMxBattery
+        | CartridgeType::MBC5xRAMxBattery
+        | CartridgeType::MBC5xRumblexRAMxBattery
+        | CartridgeType::MBC7xSensorxRumblexRAMxBattery
+        | CartridgeType::HuC1xRAMxBattery => Some(ram_kib(header.ram_size) * 1024),
+        _ => None,
+    }
+}
+
 #[derive(Serialize, Copy, Clone)]
 pub enum DestinationCode {
     Japanese,
@@ -157,16 +333,197 @@ pub struct Header {
     pub global_checksum: u16,
 }
 
+/// The logo that must be present in the header for the boot ROM to start the game.
+pub const NINTENDO_LOGO: [u8; 48] = [
+    0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
+    0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
+    0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
+];
+
+impl Header {
+    /// True if the logo data matches the Nintendo logo.
+    pub fn is_logo_valid(&self) -> bool {
+        self.logo_data == NINTENDO_LOGO
+    }
+}
+
 pub const NEW_LICENCSEE_CODE_VAL: u8 = 0x33;
 pub const BANK_BYTES: usize = 16 * 1024;
 pub const DATA_START: usize = 0x150;
+/// Save size of the MBC2's built-in RAM of 512 half bytes.
+pub const MBC2_SAVE_BYTES: usize = 512;
+
+const HEADER_CHECKSUM_START: usize = 0x134;
+const HEADER_CHECKSUM_END: usize = 0x14D; // exclusive, also the offset of the checksum
+pub const HEADER_CHECKSUM_OFFSET: usize = HEADER_CHECKSUM_END;
+/// Offset of the big-endian global checksum
+pub const GLOBAL_CHECKSUM_OFFSET: usize = 0x14E;
+
+const TITLE_OFFSET: usize = 0x134;
+const NEW_LICENSEE_CODE_OFFSET: usize = 0x144;
+const CARTRIDGE_TYPE_OFFSET: usize = 0x147;
+const ROM_SIZE_OFFSET: usize = 0x148;
+const RAM_SIZE_OFFSET: usize = 0x149;
+const OLD_LICENSEE_CODE_OFFSET: usize = 0x14B;
+
+/// The header fields `encode_header_field` can set.
+pub const EDITABLE_HEADER_FIELDS: [&str; 5] = ["title", "licensee", "cartridge", "rom", "ram"];
+
+/// The file offset and new bytes of a header `field` (one of
+/// `EDITABLE_HEADER_FIELDS`) set to `value`, `data` is the ROM. The title is
+/// ASCII, padded with zeros. The licensee is a hex byte for the old code or
+/// two characters for the new code (only used if the old code is 0x33). The
+/// cartridge type, ROM and RAM size are hex bytes (e.g. `0x1B`). The
+/// checksums are not updated.
+pub fn encode_header_field(
+    data: &[u8],
+    field: &str,
+    value: &str,
+) -> Result<(usize, Vec<u8>), String> {
+    let old_licensee = *data
+        .get(OLD_LICENSEE_CODE_OFFSET)
+        .ok_or("the ROM is too short for a header")?;
+    match field {
+        "title" => {
+            // newer cartridges use the end of the title for the manufacturer code
+            let max_len = if old_licensee == NEW_LICENCSEE_CODE_VAL {
+                11
+            } else {
+                15
+            };
+            if !value.is_ascii() || value.len() > max_len {
+                return Err(format!(
+                    "the title has to be at most {} ASCII characters",
+                    max_len
+                ));
+            }
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(max_len, 0);
+            Ok((TITLE_OFFSET, bytes))
+        }
+        "licensee" if value.len() == 2 && !value.starts_with("0x") => {
+            if old_licensee != NEW_LICENCSEE_CODE_VAL {
+                return Err(format!(
+                    "the new licensee code is only used if the old code is 0x{:02X}",
+                    NEW_LICENCSEE_CODE_VAL
+                ));
+            }
+            if !value.is_ascii() {
+                return Err("the new licensee code has to be two ASCII characters".to_string());
+            }
+            Ok((NEW_LICENSEE_CODE_OFFSET, value.as_bytes().to_vec()))
+        }
+        "licensee" => Ok((OLD_LICENSEE_CODE_OFFSET, vec![parse_hex_byte(value)?])),
+        "cartridge" => Ok((CARTRIDGE_TYPE_OFFSET, vec![parse_hex_byte(value)?])),
+        "rom" => {
+            let byte = parse_hex_byte(value)?;
+            parse_rom_size(byte)?;
+            Ok((ROM_SIZE_OFFSET, vec![byte]))
+        }
+        "ram" => {
+            let byte = parse_hex_byte(value)?;
+            parse_ram_size(byte)?;
+            Ok((RAM_SIZE_OFFSET, vec![byte]))
+        }
+        _ => Err(format!(
+            "unknown header field '{}', expected one of {}",
+            field,
+            EDITABLE_HEADER_FIELDS.join(", ")
+        )),
+    }
+}
+
+fn parse_hex_byte(value: &str) -> Result<u8, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    u8::from_str_radix(digits, 16).map_err(|_| format!("expected a hex byte, got '{}'", value))
+}
+
+/// Header checksum over the bytes 0x134-0x14C, computed like the boot ROM does.
+pub fn header_checksum(data: &[u8]) -> u8 {
+    let bytes = data
+        .get(HEADER_CHECKSUM_START..HEADER_CHECKSUM_END)
+        .unwrap_or(&[]);
+    let mut checksum: u8 = 0;
+    for b in bytes {
+        checksum = checksum.wrapping_sub(*b).wrapping_sub(1);
+    }
+    checksum
+}
 
-pub fn load(data: &[u8]) -> Result<GBBinary, String> {
+/// Sum of all ROM bytes except the two global checksum bytes.
+pub fn global_checksum(data: &[u8]) -> u16 {
+    let mut checksum: u16 = 0;
+    for (i, b) in data.iter().enumerate() {
+        if i != GLOBAL_CHECKSUM_OFFSET && i != GLOBAL_CHECKSUM_OFFSET + 1 {
+            checksum = checksum.wrapping_add(*b as u16);
+        }
+    }
+    checksum
+}
+
+/// File offset of the first byte in `bank_data[bank]`.
+pub fn bank_offset(bank: usize) -> usize {
+    DATA_START + bank * BANK_BYTES
+}
+
+/// The bank whose data contains the file offset, None for the header.
+pub fn bank_at(offset: usize) -> Option<usize> {
+    Some(offset.checked_sub(DATA_START)? / BANK_BYTES)
+}
+
+/// The vectors and header fields before the bank data as (start, exclusive
+/// end, name).
+const HEADER_REGIONS: [(usize, usize, &str); 18] = [
+    (0x00, 0x40, "restart vectors"),
+    (0x40, 0x68, "interrupt vectors"),
+    (0x68, 0x100, "unused"),
+    (0x100, 0x104, "entry point"),
+    (0x104, 0x134, "Nintendo logo"),
+    (0x134, 0x13F, "game title"),
+    (0x13F, 0x143, "manufacturer code"),
+    (0x143, 0x144, "GBC flag"),
+    (0x144, 0x146, "new licensee code"),
+    (0x146, 0x147, "SGB flag"),
+    (0x147, 0x148, "cartridge type"),
+    (0x148, 0x149, "ROM size"),
+    (0x149, 0x14A, "RAM size"),
+    (0x14A, 0x14B, "destination code"),
+    (0x14B, 0x14C, "old licensee code"),
+    (0x14C, 0x14D, "ROM version"),
+    (0x14D, 0x14E, "header checksum"),
+    (0x14E, 0x150, "global checksum"),
+];
+
+/// Name of the vector table or header field at the file offset, None past
+/// the header.
+pub fn header_region_at(offset: usize) -> Option<&'static str> {
+    HEADER_REGIONS
+        .iter()
+        .find(|(start, end, _)| (*start..*end).contains(&offset))
+        .map(|(_, _, name)| *name)
+}
+
+pub fn load(data: &[u8]) -> Result<GBBinary, ParseError> {
+    if data.len() < DATA_START {
+        return Err(ParseError::new(
+            data.len(),
+            format!(
+                "expected cartridge header, ROM ends before 0x{:X}",
+                DATA_START
+            ),
+        ));
+    }
     let mut reader = DataReader::new(data);
     let restart_calls = parse_restart_calls(&mut reader)?;
     let interrupts = parse_interrupts(&mut reader)?;
     let header = parse_header(&mut reader)?;
-    let bank_data = parse_bank_data(&mut reader, header.rom_size)?;
+    log::debug!(
+        "header: title '{}', cartridge {:?}",
+        header.game_title,
+        header.cartridge_type
+    );
+    let bank_data = parse_bank_data(&mut reader, header.rom_size);
+    log::debug!("{} banks in {} bytes", bank_data.len(), data.len());
 
     Ok(GBBinary {
         restart_calls,
@@ -176,7 +533,7 @@ pub fn load(data: &[u8]) -> Result<GBBinary, String> {
     })
 }
 
-fn parse_restart_calls(reader: &mut DataReader) -> Result<RestartCalls, String> {
+fn parse_restart_calls(reader: &mut DataReader) -> Result<RestartCalls, ParseError> {
     let calls = RestartCalls {
         rst_0: read_8_bytes(reader),
         rst_1: read_8_bytes(reader),
@@ -190,7 +547,7 @@ fn parse_restart_calls(reader: &mut DataReader) -> Result<RestartCalls, String>
     Ok(calls)
 }
 
-fn parse_interrupts(reader: &mut DataReader) -> Result<Interrupts, String> {
+fn parse_interrupts(reader: &mut DataReader) -> Result<Interrupts, ParseError> {
     let interrupts = Interrupts {
         v_blank: read_8_bytes(reader),
         lcd_stat: read_8_bytes(reader),
@@ -211,7 +568,7 @@ fn read_8_bytes(reader: &mut DataReader) -> [u8; 8] {
     result
 }
 
-fn parse_header(reader: &mut DataReader) -> Result<Header, String> {
+fn parse_header(reader: &mut DataReader) -> Result<Header, ParseError> {
     let entry_point = [
         reader.read_u8(),
         reader.read_u8(),
@@ -236,22 +593,24 @@ fn parse_header(reader: &mut DataReader) -> Result<Header, String> {
         manufacturer_code = clean_string(&reader.read_utf8_string(4));
     }
 
-    let gbc_flag = parse_gbc_flag(reader.read_u8())?;
+    let gbc_flag = parse_gbc_flag(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
     let new_licensee_code = [reader.read_u8(), reader.read_u8()];
     let licensee_code = if old_licensee_code == NEW_LICENCSEE_CODE_VAL {
-        parse_new_licensee_code(&new_licensee_code)
+        LicenseeCode::New(String::from_utf8_lossy(&new_licensee_code).to_string())
     } else {
-        parse_old_licensee_code(old_licensee_code)
+        LicenseeCode::Old(old_licensee_code)
     };
-    let sgb_flag = parse_sgb_flag(reader.read_u8())?;
-    let cartridge_type = parse_cartridge_type(reader.read_u8())?;
-    let rom_size = parse_rom_size(reader.read_u8())?;
-    let ram_size = parse_ram_size(reader.read_u8())?;
-    let destination_code = parse_destination_code(reader.read_u8())?;
+    let sgb_flag = parse_sgb_flag(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
+    let cartridge_type =
+        parse_cartridge_type(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
+    let rom_size = parse_rom_size(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
+    let ram_size = parse_ram_size(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
+    let destination_code =
+        parse_destination_code(reader.read_u8()).map_err(|e| reader.error_at_last(1, e))?;
     reader.skip(1); // old licensee code already read above
     let rom_version = reader.read_u8();
     let checksum = reader.read_u8();
-    let global_checksum = reader.read_u16();
+    let global_checksum = u16::from_be_bytes([reader.read_u8(), reader.read_u8()]);
 
     Ok(Header {
         entry_point,
@@ -305,7 +664,7 @@ fn parse_cartridge_type(t: u8) -> Result<CartridgeType, String> {
         0x10 => Ok(CartridgeType::MBC3xTimerxRAMxBattery),
         0x11 => Ok(CartridgeType::MBC3),
         0x12 => Ok(CartridgeType::MBC3xRAM),
-        0x13 => Ok(CartridgeType::MBC1xRAMxBattery),
+        0x13 => Ok(CartridgeType::MBC3xRAMxBattery),
         0x19 => Ok(CartridgeType::MBC5),
         0x1A => Ok(CartridgeType::MBC5xRAM),
         0x1B => Ok(CartridgeType::MBC5xRAMxBattery),
@@ -318,7 +677,7 @@ fn parse_cartridge_type(t: u8) -> Result<CartridgeType, String> {
         0xFD => Ok(CartridgeType::BandaiTama5),
         0xFE => Ok(CartridgeType::HuC3),
         0xFF => Ok(CartridgeType::HuC1xRAMxBattery),
-        _ => Err(format!("unsuported cartridge type: {:x}", t)),
+        _ => Ok(CartridgeType::Unknown(t)),
     }
 }
 
@@ -360,59 +719,84 @@ fn parse_destination_code(v: u8) -> Result<DestinationCode, String> {
     }
 }
 
-fn parse_new_licensee_code(code: &[u8; 2]) -> LicenseeCode {
-    match code {
-        b"00" => LicenseeCode::None,
-        b"01" => LicenseeCode::Nintendo,
-        b"08" => LicenseeCode::Capcom,
-        b"B2" => LicenseeCode::Bandai,
-        b"AF" => LicenseeCode::Namco,
-        _ => LicenseeCode::Unknown,
-    }
-    // TODO complete this mapping
-}
-
-fn parse_old_licensee_code(code: u8) -> LicenseeCode {
-    match code {
-        0x0 => LicenseeCode::None,
-        0x01 => LicenseeCode::Nintendo,
-        0x08 => LicenseeCode::Capcom,
-        0xB2 => LicenseeCode::Bandai,
-        0xAF => LicenseeCode::Namco,
-        _ => LicenseeCode::Unknown,
-    }
-    // TODO complete this mapping
-}
-
 fn clean_string(str: &str) -> String {
     str.replace('\0', "")
 }
 
-fn parse_bank_data(reader: &mut DataReader, rom_size: ROMSize) -> Result<Vec<Vec<u8>>, String> {
+/// Reads the banks declared by the ROM size. A truncated ROM results in
+/// less banks and a shorter last bank, compare with `declared_rom_bytes`.
+fn parse_bank_data(reader: &mut DataReader, rom_size: ROMSize) -> Vec<Vec<u8>> {
     let n = num_banks(rom_size);
 
     let expected_bytes = (BANK_BYTES * n) - DATA_START;
-
-    if reader.unread_bytes().len() < expected_bytes {
-        return Err(format!(
-            "illegal ROM, not enough bank data. Expected {} bytes, got {} bytes in ROM",
-            expected_bytes,
-            reader.unread_bytes().len()
-        ));
-    }
+    let mut available_bytes = reader.unread_bytes().len().min(expected_bytes);
 
     let mut bank_data = Vec::with_capacity(n);
-    for b in 0..n {
-        let bank_size = if b == (n - 1) {
-            BANK_BYTES - DATA_START
-        } else {
-            BANK_BYTES
-        };
+    while available_bytes > 0 {
+        let bank_size = available_bytes.min(BANK_BYTES);
         let mut bank = Vec::with_capacity(bank_size);
         for _ in 0..bank_size {
             bank.push(reader.read_u8());
         }
         bank_data.push(bank);
+        available_bytes -= bank_size;
+    }
+    bank_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(cartridge_type: CartridgeType, rom_size: ROMSize, ram_size: RAMSize) -> Header {
+        Header {
+            entry_point: [0; 4],
+            logo_data: NINTENDO_LOGO.to_vec(),
+            game_title: String::new(),
+            manufacturer_code: String::new(),
+            gbc_flag: GBCFlag::GBOnly,
+            licensee_code: LicenseeCode::Old(0),
+            sgb_flag: SGBFlag::NoSGB,
+            cartridge_type,
+            rom_size,
+            ram_size,
+            destination_code: DestinationCode::Japanese,
+            rom_version: 0,
+            checksum: 0,
+            global_checksum: 0,
+        }
+    }
+
+    #[test]
+    fn mbc2_with_declared_ram() {
+        let warnings = header_warnings(&header(
+            CartridgeType::MBC2xBattery,
+            ROMSize::Banks16,
+            RAMSize::KB8,
+        ));
+        assert_eq!(
+            warnings,
+            ["MBC2 has built-in RAM, the RAM size should be 0 but is 8 KiB"]
+        );
+    }
+
+    #[test]
+    fn rom_only_with_ram() {
+        let warnings = header_warnings(&header(
+            CartridgeType::ROMOnly,
+            ROMSize::NoBanking,
+            RAMSize::KB8,
+        ));
+        assert_eq!(warnings, ["the cartridge type has no RAM, declared 8 KiB"]);
+    }
+
+    #[test]
+    fn consistent_mbc1_with_ram() {
+        let warnings = header_warnings(&header(
+            CartridgeType::MBC1xRAMxBattery,
+            ROMSize::Banks64,
+            RAMSize::KB32,
+        ));
+        assert!(warnings.is_empty(), "{:?}", warnings);
     }
-    Ok(bank_data)
 }
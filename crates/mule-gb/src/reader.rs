@@ -1,3 +1,5 @@
+use crate::ParseError;
+
 pub struct DataReader<'a> {
     data: &'a [u8],
     offset: usize,
@@ -75,6 +77,11 @@ impl DataReader<'_> {
         u
     }
 
+    /// An error for the last `len` bytes read.
+    pub fn error_at_last(&self, len: usize, message: String) -> ParseError {
+        ParseError::new(self.offset - len, message)
+    }
+
     // Reads a byte without updating the current offset.
     pub fn read_u8_at(&self, offset: usize) -> u8 {
         self.data[offset]
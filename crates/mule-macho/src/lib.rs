@@ -1,17 +1,272 @@
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
+use std::fmt;
+
 use serde::Serialize;
 
 #[derive(Serialize)]
 pub struct Macho {
     pub header: Header,
+    /// Byte order of the file, big-endian for a `CIGAM_HEADER` magic.
+    pub endianness: Endianness,
     pub load_commands: Vec<LoadCommand>,
 }
 
+impl Macho {
+    /// The symbols of the symbol table, empty if the binary has no LC_SYMTAB
+    /// command or its symbol table is malformed.
+    pub fn symbols(&self) -> &[Symbol] {
+        for cmd in &self.load_commands {
+            if let LoadCommand::Symtab(symtab) = cmd {
+                return symtab.symbols.as_deref().unwrap_or(&[]);
+            }
+        }
+        &[]
+    }
+
+    /// Names of the symbols bound against the dylib of the load command at
+    /// `cmd_index`, from the bind info of LC_DYLD_INFO_ONLY. Empty if the
+    /// binary has no bind info (e.g. uses chained fixups instead), an error
+    /// if the bind info is malformed.
+    pub fn dylib_imports(&self, cmd_index: usize) -> Result<Vec<&str>, &str> {
+        let Some(ordinal) = self.dylib_ordinal(cmd_index) else {
+            return Ok(Vec::new());
+        };
+        let mut result = Vec::new();
+        for cmd in &self.load_commands {
+            if let LoadCommand::DyldInfoOnly(info) = cmd {
+                let binds = info.binds.as_ref().map_err(String::as_str)?;
+                result.extend(
+                    binds
+                        .iter()
+                        .filter(|bind| bind.dylib_ordinal == ordinal)
+                        .map(|bind| bind.symbol.as_str()),
+                );
+            }
+        }
+        result.sort_unstable();
+        result.dedup();
+        Ok(result)
+    }
+
+    /// The vm address of the `__TEXT` segment, function starts are relative
+    /// to it. 0 if the binary has no `__TEXT` segment.
+    pub fn text_vm_addr(&self) -> u64 {
+        self.load_commands
+            .iter()
+            .find_map(|cmd| match cmd {
+                LoadCommand::Segment64(segment) if segment.name == "__TEXT" => {
+                    Some(segment.vm_addr)
+                }
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// The unused space of the segment of the load command at `cmd_index`
+    /// before, between and after its sections, ordered by address. Empty if
+    /// the command is no segment or has no sections.
+    pub fn section_gaps(&self, cmd_index: usize) -> Vec<SectionGap> {
+        let Some(LoadCommand::Segment64(segment)) = self.load_commands.get(cmd_index) else {
+            return Vec::new();
+        };
+        if segment.sections.is_empty() {
+            return Vec::new();
+        }
+        let mut order: Vec<usize> = (0..segment.sections.len()).collect();
+        order.sort_by_key(|ix| segment.sections[*ix].address);
+
+        // the segment at file offset 0 starts with the header and load commands
+        let mut file_end = if segment.file_off == 0 {
+            (HEADER_SIZE + self.header.size_of_cmds) as u64
+        } else {
+            segment.file_off
+        };
+        let mut vm_end = segment.vm_addr.saturating_add(file_end - segment.file_off);
+        let mut gaps = Vec::new();
+        let mut after = None;
+        for before in order.into_iter().map(Some).chain([None]) {
+            let (file_start, vm_start) = match before {
+                Some(ix) => {
+                    let section = &segment.sections[ix];
+                    // zerofill sections take no space in the file
+                    let file_start = if section.is_zerofill() {
+                        file_end
+                    } else {
+                        section.offset as u64
+                    };
+                    (file_start, section.address)
+                }
+                None => (
+                    segment.file_off.saturating_add(segment.file_size),
+                    segment.vm_addr.saturating_add(segment.vm_size),
+                ),
+            };
+            let gap = SectionGap {
+                after,
+                before,
+                file_offset: file_end,
+                file_size: file_start.saturating_sub(file_end),
+                vm_addr: vm_end,
+                vm_size: vm_start.saturating_sub(vm_end),
+            };
+            if gap.file_size > 0 || gap.vm_size > 0 {
+                gaps.push(gap);
+            }
+            if let Some(ix) = before {
+                let section = &segment.sections[ix];
+                if !section.is_zerofill() {
+                    file_end = file_end.max((section.offset as u64).saturating_add(section.size));
+                }
+                vm_end = vm_end.max(section.address.saturating_add(section.size));
+            }
+            after = before;
+        }
+        gaps
+    }
+
+    /// The 1-based ordinal binds use to refer to the dylib of the load
+    /// command at `cmd_index`, None if the command doesn't load a dylib.
+    fn dylib_ordinal(&self, cmd_index: usize) -> Option<i64> {
+        let mut ordinal = 0;
+        for (i, cmd) in self.load_commands.iter().enumerate() {
+            if matches!(cmd, LoadCommand::LoadDylib(_)) {
+                ordinal += 1;
+                if i == cmd_index {
+                    return Some(ordinal);
+                }
+            } else if i == cmd_index {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+/// Error of `load`, with the file offset of the malformed data.
+#[derive(Debug)]
+pub struct ParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error at 0x{:X}: {}", self.offset, self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.to_string()
+    }
+}
+
+// Fat (universal) binaries
+
+/// Big-endian magic of a fat binary with 32-bit slice offsets
+pub const FAT_MAGIC: u32 = 0xcafebabe;
+/// Big-endian magic of a fat binary with 64-bit slice offsets
+pub const FAT_MAGIC_64: u32 = 0xcafebabf;
+
+// Java class files share the fat magic, they have a (much) larger version
+// number where the fat header has the number of architectures.
+const MAX_FAT_ARCHS: u32 = 32;
+
+/// An architecture slice of a fat binary, a complete Mach-O file
+/// at `offset` in the fat file.
+#[derive(Serialize)]
+pub struct FatArch {
+    pub cpu_type: i32,
+    pub cpu_sub_type: i32,
+    pub offset: u64,
+    pub size: u64,
+    pub align: u32,
+}
+
+impl FatArch {
+    /// Whether the slice can be loaded with `load`.
+    pub fn is_supported(&self) -> bool {
+        parse_cpu_type(self.cpu_type).is_ok()
+    }
+}
+
+/// Parses the architecture list of a fat binary. The fat header and
+/// fat_arch (or fat_arch_64) entries are big-endian.
+pub fn load_fat(data: &[u8]) -> Result<Vec<FatArch>, ParseError> {
+    let read_u32 = |offset: usize| -> Result<u32, ParseError> {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| ParseError::new(offset, "expected fat header, file ends"))
+    };
+    let read_u64 = |offset: usize| -> Result<u64, ParseError> {
+        Ok(((read_u32(offset)? as u64) << 32) | read_u32(offset + 4)? as u64)
+    };
+
+    let magic = read_u32(0)?;
+    let is_64 = match magic {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return Err(ParseError::new(0, "expected fat magic")),
+    };
+    let n_archs = read_u32(4)?;
+    if n_archs > MAX_FAT_ARCHS {
+        return Err(ParseError::new(
+            4,
+            format!("expected at most {} architectures", MAX_FAT_ARCHS),
+        ));
+    }
+
+    let arch_size = if is_64 { 32 } else { 20 };
+    let mut archs = Vec::with_capacity(n_archs as usize);
+    for i in 0..n_archs as usize {
+        let base = 8 + i * arch_size;
+        let cpu_type = read_u32(base)? as i32;
+        let cpu_sub_type = read_u32(base + 4)? as i32;
+        let (offset, size, align) = if is_64 {
+            // fat_arch_64 has a trailing reserved field
+            (
+                read_u64(base + 8)?,
+                read_u64(base + 16)?,
+                read_u32(base + 24)?,
+            )
+        } else {
+            (
+                read_u32(base + 8)? as u64,
+                read_u32(base + 12)? as u64,
+                read_u32(base + 16)?,
+            )
+        };
+        archs.push(FatArch {
+            cpu_type,
+            cpu_sub_type,
+            offset,
+            size,
+            align,
+        });
+    }
+    Ok(archs)
+}
+
 // Header
 
 pub const MAGIC_HEADER: u32 = 0xfeedfacf;
+/// `MAGIC_HEADER` of a big-endian (e.g. PowerPC) Mach-O read as little-endian
+pub const CIGAM_HEADER: u32 = 0xcffaedfe;
 
 #[derive(Serialize)]
 pub struct Header {
+    pub magic: u32,
     pub cpu_type: CPUType,
     pub cpu_sub_type: CPUSubType,
     pub file_type: FileType,
@@ -27,6 +282,7 @@ const CPU_ARCH_ABI64: i32 = 0x01000000;
 pub enum CPUType {
     X86_64 = 7 | CPU_ARCH_ABI64,
     ARM64 = 12 | CPU_ARCH_ABI64,
+    PowerPC64 = 18 | CPU_ARCH_ABI64,
 }
 
 #[repr(i32)]
@@ -34,6 +290,7 @@ pub enum CPUType {
 pub enum CPUSubType {
     ARM(CPUARMSubType),
     X86(CPUX86SubType),
+    PowerPC(CPUPowerPCSubType),
 }
 
 #[repr(i32)]
@@ -49,6 +306,18 @@ pub enum CPUX86SubType {
     All64 = 3,
 }
 
+#[repr(i32)]
+#[derive(Serialize, Debug)]
+pub enum CPUPowerPCSubType {
+    All = 0,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
 #[repr(u32)]
 #[derive(Serialize, Debug)]
 pub enum FileType {
@@ -58,7 +327,7 @@ pub enum FileType {
 }
 
 #[repr(u32)]
-#[derive(Serialize, Copy, Clone)]
+#[derive(Serialize, Copy, Clone, Debug)]
 pub enum HeaderFlag {
     MH_NOUNDEFS = 0x01, /* the object file has no undefined references */
     MH_INCRLINK = 0x02, /* the object file is the output of an incremental link against a base file
@@ -125,9 +394,78 @@ const LC_MAIN: u32 = 0x28 | LC_REQ_DYLD;
 const LC_DYLD_EXPORTS_TRIE: u32 = 0x33 | LC_REQ_DYLD;
 const LC_DYLD_CHAINED_FIXUPS: u32 = 0x34 | LC_REQ_DYLD;
 
+const LC_LOAD_DYLIB: u32 = 0xc;
+const LC_LOAD_WEAK_DYLIB: u32 = 0x18 | LC_REQ_DYLD;
+const LC_RPATH: u32 = 0x1c | LC_REQ_DYLD;
+const LC_REEXPORT_DYLIB: u32 = 0x1f | LC_REQ_DYLD;
+const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+const LC_LOAD_UPWARD_DYLIB: u32 = 0x23 | LC_REQ_DYLD;
+
 #[derive(Serialize)]
 pub struct SymtabCommand {
     cmd_size: usize,
+    pub sym_off: u32,
+    pub n_syms: u32,
+    pub str_off: u32,
+    pub str_size: u32,
+    /// The symbols, an error if the symbol or string table is malformed
+    pub symbols: Result<Vec<Symbol>, String>,
+}
+
+const NLIST_64_SIZE: usize = 16;
+/// Size of a segment_64 command without its sections and of one section_64.
+const SEGMENT_64_SIZE: usize = 72;
+const SECTION_64_SIZE: usize = 80;
+
+const N_STAB: u8 = 0xe0;
+const N_PEXT: u8 = 0x10;
+const N_TYPE: u8 = 0x0e;
+const N_EXT: u8 = 0x01;
+
+/// A nlist_64 entry of the symbol table
+#[derive(Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub n_type: u8,
+    /// section number or NO_SECT (0)
+    pub sect: u8,
+    pub desc: u16,
+    pub value: u64,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum SymbolType {
+    Undefined,
+    Absolute,
+    Section,
+    Prebound,
+    Indirect,
+    Debug,
+    Unknown(u8),
+}
+
+impl Symbol {
+    pub fn symbol_type(&self) -> SymbolType {
+        if self.n_type & N_STAB != 0 {
+            return SymbolType::Debug;
+        }
+        match self.n_type & N_TYPE {
+            0x0 => SymbolType::Undefined,
+            0x2 => SymbolType::Absolute,
+            0xe => SymbolType::Section,
+            0xc => SymbolType::Prebound,
+            0xa => SymbolType::Indirect,
+            t => SymbolType::Unknown(t),
+        }
+    }
+
+    pub fn is_external(&self) -> bool {
+        self.n_type & N_EXT != 0
+    }
+
+    pub fn is_private_external(&self) -> bool {
+        self.n_type & N_PEXT != 0
+    }
 }
 
 #[derive(Serialize)]
@@ -135,15 +473,39 @@ pub struct DsymtabCommand {
     cmd_size: usize,
 }
 
+/// How a dylib is loaded, by the command that loads it. All of them count
+/// for the dylib ordinals of the binds.
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum DylibKind {
+    /// LC_LOAD_DYLIB
+    Load,
+    /// LC_LOAD_WEAK_DYLIB, a missing dylib is no error
+    Weak,
+    /// LC_REEXPORT_DYLIB
+    Reexport,
+    /// LC_LAZY_LOAD_DYLIB
+    Lazy,
+    /// LC_LOAD_UPWARD_DYLIB
+    Upward,
+}
+
 #[derive(Serialize)]
 pub struct LoadDylibCommand {
     pub cmd_size: usize,
+    pub kind: DylibKind,
     pub name: String,
     pub timestamp: u32,
     pub current_version: u32,
     pub compatibility_version: u32,
 }
 
+/// A path dyld substitutes for `@rpath` in the install names of dylibs.
+#[derive(Serialize)]
+pub struct RpathCommand {
+    cmd_size: usize,
+    pub path: String,
+}
+
 #[derive(Serialize)]
 pub struct DylinkerCommand {
     cmd_size: usize,
@@ -175,27 +537,231 @@ pub struct Section64 {
     pub align: u32,
     pub relocation_offset: u32,
     pub n_relocations: u32,
-    pub flags: u32, // TODO convert to Section Flag Vec!
+    pub flags: u32,
+}
+
+/// Unused space in a segment before, between or after its sections.
+#[derive(Serialize, Debug)]
+pub struct SectionGap {
+    /// Index of the section before the gap, None at the start of the segment
+    pub after: Option<usize>,
+    /// Index of the section after the gap, None at the end of the segment
+    pub before: Option<usize>,
+    pub file_offset: u64,
+    pub file_size: u64,
+    pub vm_addr: u64,
+    pub vm_size: u64,
+}
+
+/// Gaps at the end of a segment up to this size are page padding.
+const PAGE_BYTES: u64 = 0x4000;
+
+impl SectionGap {
+    /// The gap is larger than the alignment of the following section (or the
+    /// page size at the end of the segment) explains, e.g. unused space left
+    /// by the linker.
+    pub fn is_large(&self, sections: &[Section64]) -> bool {
+        let padding = match self.before.and_then(|ix| sections.get(ix)) {
+            Some(section) => 1u64 << section.align.min(63),
+            None => PAGE_BYTES,
+        };
+        self.file_size.max(self.vm_size) >= padding
+    }
+}
+
+const SECTION_TYPE: u32 = 0x000000ff;
+// S_ZEROFILL, S_GB_ZEROFILL and S_THREAD_LOCAL_ZEROFILL
+const ZEROFILL_SECTION_TYPES: [u32; 3] = [0x1, 0xc, 0x12];
+
+const SECTION_TYPES: [&str; 23] = [
+    "S_REGULAR",
+    "S_ZEROFILL",
+    "S_CSTRING_LITERALS",
+    "S_4BYTE_LITERALS",
+    "S_8BYTE_LITERALS",
+    "S_LITERAL_POINTERS",
+    "S_NON_LAZY_SYMBOL_POINTERS",
+    "S_LAZY_SYMBOL_POINTERS",
+    "S_SYMBOL_STUBS",
+    "S_MOD_INIT_FUNC_POINTERS",
+    "S_MOD_TERM_FUNC_POINTERS",
+    "S_COALESCED",
+    "S_GB_ZEROFILL",
+    "S_INTERPOSING",
+    "S_16BYTE_LITERALS",
+    "S_DTRACE_DOF",
+    "S_LAZY_DYLIB_SYMBOL_POINTERS",
+    "S_THREAD_LOCAL_REGULAR",
+    "S_THREAD_LOCAL_ZEROFILL",
+    "S_THREAD_LOCAL_VARIABLES",
+    "S_THREAD_LOCAL_VARIABLE_POINTERS",
+    "S_THREAD_LOCAL_INIT_FUNCTION_POINTERS",
+    "S_INIT_FUNC_OFFSETS",
+];
+
+const SECTION_ATTRIBUTES: [(u32, &str); 10] = [
+    (0x80000000, "S_ATTR_PURE_INSTRUCTIONS"),
+    (0x40000000, "S_ATTR_NO_TOC"),
+    (0x20000000, "S_ATTR_STRIP_STATIC_SYMS"),
+    (0x10000000, "S_ATTR_NO_DEAD_STRIP"),
+    (0x08000000, "S_ATTR_LIVE_SUPPORT"),
+    (0x04000000, "S_ATTR_SELF_MODIFYING_CODE"),
+    (0x02000000, "S_ATTR_DEBUG"),
+    (0x00000400, "S_ATTR_SOME_INSTRUCTIONS"),
+    (0x00000200, "S_ATTR_EXT_RELOC"),
+    (0x00000100, "S_ATTR_LOC_RELOC"),
+];
+
+impl Section64 {
+    /// Name of the section type (the low byte of the flags), None if unknown.
+    pub fn section_type(&self) -> Option<&'static str> {
+        SECTION_TYPES
+            .get((self.flags & SECTION_TYPE) as usize)
+            .copied()
+    }
+
+    /// The section has no data in the file, it is zeroed memory.
+    pub fn is_zerofill(&self) -> bool {
+        ZEROFILL_SECTION_TYPES.contains(&(self.flags & SECTION_TYPE))
+    }
+
+    /// Names of the attribute bits set in the flags.
+    pub fn attributes(&self) -> Vec<&'static str> {
+        SECTION_ATTRIBUTES
+            .iter()
+            .filter(|(bit, _)| self.flags & bit != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
 }
 
 #[derive(Serialize)]
 pub struct UuidCommand {
     cmd_size: usize,
+    pub uuid: [u8; 16],
 }
 
 #[derive(Serialize)]
 pub struct CodeSignatureCommand {
     cmd_size: usize,
+    /// File offset of the signature in the __LINKEDIT segment
+    pub data_off: u32,
+    pub data_size: u32,
+    /// The decoded signature, an error if it is malformed
+    pub signature: Result<CodeSignature, String>,
+}
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade0c02;
+const CSMAGIC_EMBEDDED_ENTITLEMENTS: u32 = 0xfade7171;
+const CSMAGIC_EMBEDDED_DER_ENTITLEMENTS: u32 = 0xfade7172;
+const CSSLOT_CODEDIRECTORY: u32 = 0;
+const CSSLOT_ENTITLEMENTS: u32 = 5;
+const CSSLOT_DER_ENTITLEMENTS: u32 = 7;
+const CS_SUPPORTSTEAMID: u32 = 0x20200;
+
+/// The embedded signature (a SuperBlob), all values are big endian.
+#[derive(Serialize)]
+pub struct CodeSignature {
+    pub length: u32,
+    pub blobs: Vec<BlobIndex>,
+    /// The code directory of slot 0, None if the slot is missing
+    pub code_directory: Option<CodeDirectory>,
+    /// The XML plist of slot 5, None if the slot is missing
+    pub entitlements: Option<Result<String, String>>,
+    /// The DER encoded entitlements of slot 7 (the dictionary), None if the
+    /// slot is missing
+    pub der_entitlements: Option<Result<EntitlementValue, String>>,
+}
+
+/// A value of the DER encoded entitlements, the types of a plist.
+#[derive(Serialize, Debug)]
+pub enum EntitlementValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    Array(Vec<EntitlementValue>),
+    Dict(Vec<(String, EntitlementValue)>),
+}
+
+#[derive(Serialize)]
+pub struct BlobIndex {
+    pub slot: u32,
+    /// Offset from the start of the signature
+    pub offset: u32,
+}
+
+impl BlobIndex {
+    pub fn slot_name(&self) -> &'static str {
+        match self.slot {
+            0 => "CodeDirectory",
+            1 => "Info",
+            2 => "Requirements",
+            3 => "ResourceDir",
+            4 => "Application",
+            5 => "Entitlements",
+            7 => "DER Entitlements",
+            0x1000..=0x1004 => "Alternate CodeDirectory",
+            0x10000 => "CMS Signature",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CodeDirectory {
+    pub version: u32,
+    pub flags: u32,
+    pub identifier: String,
+    /// Only present since version 0x20200
+    pub team_id: Option<String>,
+    pub hash_type: u8,
+    pub hash_size: u8,
+    pub special_slots: u32,
+    pub code_slots: u32,
+    pub code_limit: u32,
+    /// log2 of the page size, 0 for a single page
+    pub page_size: u8,
+}
+
+impl CodeDirectory {
+    pub fn hash_type_name(&self) -> &'static str {
+        match self.hash_type {
+            1 => "SHA-1",
+            2 => "SHA-256",
+            3 => "SHA-256 (truncated)",
+            4 => "SHA-384",
+            _ => "unknown",
+        }
+    }
 }
 
 #[derive(Serialize)]
 pub struct BuildVersionCommand {
     cmd_size: usize,
+    pub platform: u32,
+    /// X.Y.Z encoded in nibbles xxxx.yy.zz
+    pub min_os: u32,
+    /// X.Y.Z encoded in nibbles xxxx.yy.zz
+    pub sdk: u32,
+    pub tools: Vec<BuildToolVersion>,
+}
+
+#[derive(Serialize)]
+pub struct BuildToolVersion {
+    pub tool: u32,
+    pub version: u32,
 }
 
 #[derive(Serialize)]
 pub struct FunctionStartsCommand {
     cmd_size: usize,
+    /// File offset of the function starts in the __LINKEDIT segment
+    pub data_off: u32,
+    pub data_size: u32,
+    /// Offsets of the functions from the start of the `__TEXT` segment (see
+    /// `Macho::text_vm_addr`), an error if the data is malformed
+    pub offsets: Result<Vec<u64>, String>,
 }
 
 #[derive(Serialize)]
@@ -206,16 +772,67 @@ pub struct DataInCodeCommand {
 #[derive(Serialize)]
 pub struct SourceVersionCommand {
     cmd_size: usize,
+    /// A.B.C.D.E packed as a24.b10.c10.d10.e10
+    pub version: u64,
 }
 
 #[derive(Serialize)]
 pub struct DyldInfoOnlyCommand {
     cmd_size: usize,
+    pub rebase_off: u32,
+    pub rebase_size: u32,
+    pub bind_off: u32,
+    pub bind_size: u32,
+    pub weak_bind_off: u32,
+    pub weak_bind_size: u32,
+    pub lazy_bind_off: u32,
+    pub lazy_bind_size: u32,
+    pub export_off: u32,
+    pub export_size: u32,
+    /// The binds of the bind and lazy bind info, an error if the bind
+    /// opcodes are malformed
+    pub binds: Result<Vec<Bind>, String>,
+}
+
+/// A symbol bound to a pointer by dyld.
+#[derive(Serialize)]
+pub struct Bind {
+    pub symbol: String,
+    /// 1-based index of the dylib load command, 0 for the binary itself and
+    /// negative for the special lookups (-1 main executable, -2 flat lookup)
+    pub dylib_ordinal: i64,
+    pub segment: u8,
+    /// offset in the segment
+    pub offset: u64,
+    pub lazy: bool,
 }
 
+const BIND_OPCODE_MASK: u8 = 0xF0;
+const BIND_IMMEDIATE_MASK: u8 = 0x0F;
+const BIND_OPCODE_DONE: u8 = 0x00;
+const BIND_OPCODE_SET_DYLIB_ORDINAL_IMM: u8 = 0x10;
+const BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB: u8 = 0x20;
+const BIND_OPCODE_SET_DYLIB_SPECIAL_IMM: u8 = 0x30;
+const BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM: u8 = 0x40;
+const BIND_OPCODE_SET_TYPE_IMM: u8 = 0x50;
+const BIND_OPCODE_SET_ADDEND_SLEB: u8 = 0x60;
+const BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x70;
+const BIND_OPCODE_ADD_ADDR_ULEB: u8 = 0x80;
+const BIND_OPCODE_DO_BIND: u8 = 0x90;
+const BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB: u8 = 0xA0;
+const BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED: u8 = 0xB0;
+const BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB: u8 = 0xC0;
+const BIND_OPCODE_THREADED: u8 = 0xD0;
+
+const POINTER_SIZE: u64 = 8;
+
 #[derive(Serialize)]
 pub struct MainCommand {
     cmd_size: usize,
+    /// file (__TEXT) offset of main()
+    pub entry_offset: u64,
+    /// if not zero, initial stack size
+    pub stack_size: u64,
 }
 
 #[derive(Serialize)]
@@ -223,13 +840,21 @@ pub struct LinkeditDataCommand {
     cmd_size: usize,
 }
 
+#[derive(Serialize)]
+pub struct UnknownCommand {
+    pub cmd: u32,
+    pub cmd_size: usize,
+    /// the complete raw bytes of the command, including cmd and cmd_size
+    pub data: Vec<u8>,
+}
+
 #[derive(Serialize)]
 pub enum LoadCommand {
     // 0x2
     Symtab(SymtabCommand),
     // 0xb
     Dsymtab(DsymtabCommand),
-    // 0xc
+    // 0xc, (0x18|LC_REQ_DYLD), (0x1f|LC_REQ_DYLD), 0x20, (0x23|LC_REQ_DYLD)
     LoadDylib(LoadDylibCommand),
     // 0xe
     Dylinker(DylinkerCommand),
@@ -237,6 +862,8 @@ pub enum LoadCommand {
     Segment64(Segment64Command),
     // 0x1b
     Uuid(UuidCommand),
+    // (0x1c|LC_REQ_DYLD)
+    Rpath(RpathCommand),
     // 0x1d
     CodeSignature(CodeSignatureCommand),
     // 0x32
@@ -252,33 +879,56 @@ pub enum LoadCommand {
     // (0x28|LC_REQ_DYLD)
     Main(MainCommand),
     LinkeditData(LinkeditDataCommand),
-    Unknow(u32),
+    Unknow(UnknownCommand),
 }
 
-pub fn load(data: &[u8]) -> Result<Macho, String> {
-    let mut reader = DataReader::new(data);
+/// Size of the mach_header_64, the load commands follow it.
+pub const HEADER_SIZE: usize = 32;
+
+pub fn load(data: &[u8]) -> Result<Macho, ParseError> {
+    if data.len() < HEADER_SIZE {
+        return Err(ParseError::new(
+            data.len(),
+            format!("expected mach-o header of {} bytes", HEADER_SIZE),
+        ));
+    }
+    let endianness = if u32::from_le_bytes(data[0..4].try_into().unwrap()) == CIGAM_HEADER {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    };
+    let mut reader = DataReader::new(data, endianness);
     let header = parse_header(&mut reader)?;
+    log::debug!(
+        "header: cpu {:?}, file type {:?}, {} load commands",
+        header.cpu_type,
+        header.file_type,
+        header.no_cmds
+    );
     let load_commands = parse_load_commands(&mut reader, header.no_cmds)?;
     Ok(Macho {
         header,
+        endianness,
         load_commands,
     })
 }
 
-fn parse_header(reader: &mut DataReader) -> Result<Header, String> {
+fn parse_header(reader: &mut DataReader) -> Result<Header, ParseError> {
     let magic = reader.read_u32();
     if magic != MAGIC_HEADER {
-        return Err("not a mach-o 64 file".to_string());
+        return Err(ParseError::new(0, "expected mach-o 64 magic"));
     }
-    let cpu_type = parse_cpu_type(reader.read_i32())?;
-    let cpu_sub_type = parse_cpu_sub_type(cpu_type, reader.read_i32())?;
-    let file_type = parse_file_type(reader.read_u32())?;
+    let cpu_type = parse_cpu_type(reader.read_i32()).map_err(|e| reader.error_at_last(4, e))?;
+    let cpu_sub_type =
+        parse_cpu_sub_type(cpu_type, reader.read_i32()).map_err(|e| reader.error_at_last(4, e))?;
+    let file_type = parse_file_type(reader.read_u32()).map_err(|e| reader.error_at_last(4, e))?;
     let no_cmds = reader.read_u32() as usize;
     let size_of_cmds = reader.read_u32() as usize;
-    let flags = parse_header_flags(reader.read_u32())?;
+    let flags = parse_header_flags(reader.read_u32()).map_err(|e| reader.error_at_last(4, e))?;
     reader.skip(4); // reserved
 
     Ok(Header {
+        magic,
         cpu_type,
         cpu_sub_type,
         file_type,
@@ -293,6 +943,8 @@ fn parse_cpu_type(v: i32) -> Result<CPUType, String> {
         Ok(CPUType::X86_64)
     } else if v == CPUType::ARM64 as i32 {
         Ok(CPUType::ARM64)
+    } else if v == CPUType::PowerPC64 as i32 {
+        Ok(CPUType::PowerPC64)
     } else {
         Err(format!("unsupported cpu_type: 0x{:x}", v))
     }
@@ -309,6 +961,10 @@ fn parse_cpu_sub_type(cpu_type: CPUType, v: i32) -> Result<CPUSubType, String> {
             3 => Ok(CPUSubType::X86(CPUX86SubType::All64)),
             _ => Err(format!("unsupported X86_64 cpu_sub_type: 0x{:x}", v)),
         },
+        CPUType::PowerPC64 => match v {
+            0 => Ok(CPUSubType::PowerPC(CPUPowerPCSubType::All)),
+            _ => Err(format!("unsupported PowerPC64 cpu_sub_type: 0x{:x}", v)),
+        },
     }
 }
 
@@ -366,15 +1022,49 @@ fn h_flag(v: u32, flag: HeaderFlag, result: &mut Vec<HeaderFlag>) {
 fn parse_load_commands(
     reader: &mut DataReader,
     no_cmds: usize,
-) -> Result<Vec<LoadCommand>, String> {
+) -> Result<Vec<LoadCommand>, ParseError> {
     let mut commands = Vec::with_capacity(no_cmds);
     for _ in 0..no_cmds {
+        let start = reader.offset();
+        if start + 8 > reader.data.len() {
+            return Err(ParseError::new(start, "expected load command, file ends"));
+        }
         let cmd = reader.read_u32();
         let cmd_size = reader.read_u32() as usize;
+        log::trace!(
+            "load command 0x{:X} at 0x{:X}, size 0x{:X}",
+            cmd,
+            start,
+            cmd_size
+        );
+        let min_size = min_cmd_size(cmd);
+        if cmd_size < min_size {
+            return Err(ParseError::new(
+                start + 4,
+                format!(
+                    "expected load command size of at least 0x{:X}, got 0x{:X}",
+                    min_size, cmd_size
+                ),
+            ));
+        }
+        if start + cmd_size > reader.data.len() {
+            return Err(ParseError::new(
+                start + 4,
+                format!(
+                    "expected load command size within the file, got 0x{:X}",
+                    cmd_size
+                ),
+            ));
+        }
         let command = match cmd {
             0x2 => parse_cmd_symtab(reader, cmd_size),
             0xb => parse_cmd_dsymtab(reader, cmd_size),
-            0xc => parse_cmd_load_dylib(reader, cmd_size),
+            LC_LOAD_DYLIB => parse_cmd_load_dylib(reader, cmd_size, DylibKind::Load),
+            LC_LOAD_WEAK_DYLIB => parse_cmd_load_dylib(reader, cmd_size, DylibKind::Weak),
+            LC_REEXPORT_DYLIB => parse_cmd_load_dylib(reader, cmd_size, DylibKind::Reexport),
+            LC_LAZY_LOAD_DYLIB => parse_cmd_load_dylib(reader, cmd_size, DylibKind::Lazy),
+            LC_LOAD_UPWARD_DYLIB => parse_cmd_load_dylib(reader, cmd_size, DylibKind::Upward),
+            LC_RPATH => parse_cmd_rpath(reader, cmd_size),
             0xe => parse_cmd_dylinker(reader, cmd_size),
             0x19 => parse_cmd_segment_64(reader, cmd_size),
             0x1b => parse_cmd_uuid(reader, cmd_size),
@@ -387,34 +1077,117 @@ fn parse_load_commands(
             LC_MAIN => parse_cmd_main(reader, cmd_size),
             LC_DYLD_EXPORTS_TRIE => parse_cmd_dyld_exports_trie(reader, cmd_size),
             LC_DYLD_CHAINED_FIXUPS => parse_cmd_dyld_chained_fixups(reader, cmd_size),
-            _ => {
-                reader.skip(cmd_size - 8);
-                Ok(LoadCommand::Unknow(cmd))
-            }
-        }?;
+            _ => parse_cmd_unknown(reader, cmd, cmd_size),
+        }
+        .map_err(|e| ParseError::new(start, e))?;
         commands.push(command);
     }
     Ok(commands)
 }
 
+/// Size of the fixed fields of a load command including `cmd` and `cmdsize`,
+/// the parsers read them without further checks.
+fn min_cmd_size(cmd: u32) -> usize {
+    match cmd {
+        0x2 => 24,
+        LC_LOAD_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB | LC_LAZY_LOAD_DYLIB
+        | LC_LOAD_UPWARD_DYLIB => 24,
+        LC_RPATH | 0xe => 12,
+        0x19 => SEGMENT_64_SIZE,
+        0x1b => 24,
+        0x1d | 0x26 | 0x2a => 16,
+        0x32 => 24,
+        LC_DYLD_INFO_ONLY => 48,
+        LC_MAIN => 24,
+        _ => 8,
+    }
+}
+
 fn parse_cmd_symtab(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
-    Ok(LoadCommand::Symtab(SymtabCommand { cmd_size }))
+    let sym_off = reader.read_u32();
+    let n_syms = reader.read_u32();
+    let str_off = reader.read_u32();
+    let str_size = reader.read_u32();
+    reader.skip(remaining_cmd_bytes(cmd_size, 24)?);
+
+    let symbols = parse_symbols(
+        reader.data,
+        reader.endianness,
+        sym_off,
+        n_syms,
+        str_off,
+        str_size,
+    );
+    Ok(LoadCommand::Symtab(SymtabCommand {
+        cmd_size,
+        sym_off,
+        n_syms,
+        str_off,
+        str_size,
+        symbols,
+    }))
+}
+
+fn parse_symbols(
+    data: &[u8],
+    endianness: Endianness,
+    sym_off: u32,
+    n_syms: u32,
+    str_off: u32,
+    str_size: u32,
+) -> Result<Vec<Symbol>, String> {
+    let sym_end = sym_off as usize + n_syms as usize * NLIST_64_SIZE;
+    if sym_end > data.len() {
+        return Err(format!(
+            "symbol table out of bounds: ends at 0x{:x}, file has 0x{:x} bytes",
+            sym_end,
+            data.len()
+        ));
+    }
+    let str_end = (str_off as usize + str_size as usize).min(data.len());
+    let str_table = if (str_off as usize) < str_end {
+        &data[(str_off as usize)..str_end]
+    } else {
+        &[]
+    };
+
+    let mut reader = DataReader::new_with_offset(data, sym_off as usize, endianness);
+    let mut symbols = Vec::with_capacity(n_syms as usize);
+    for _ in 0..n_syms {
+        let n_strx = reader.read_u32() as usize;
+        let n_type = reader.read_u8();
+        let sect = reader.read_u8();
+        let desc = reader.read_u16();
+        let value = reader.read_u64();
+        symbols.push(Symbol {
+            name: read_c_string(str_table, n_strx),
+            n_type,
+            sect,
+            desc,
+            value,
+        });
+    }
+    Ok(symbols)
 }
 
 fn parse_cmd_dsymtab(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    reader.skip(remaining_cmd_bytes(cmd_size, 8)?);
     Ok(LoadCommand::Dsymtab(DsymtabCommand { cmd_size }))
 }
 
-fn parse_cmd_load_dylib(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
+fn parse_cmd_load_dylib(
+    reader: &mut DataReader,
+    cmd_size: usize,
+    kind: DylibKind,
+) -> Result<LoadCommand, String> {
     reader.skip(4); //name offset, derived from cmd_size
     let timestamp = reader.read_u32();
     let current_version = reader.read_u32();
     let compatibility_version = reader.read_u32();
-    let name = clean_string(&reader.read_utf8_string(cmd_size - (6 * 4)));
+    let name = clean_string(&reader.read_utf8_string(remaining_cmd_bytes(cmd_size, 6 * 4)?));
     Ok(LoadCommand::LoadDylib(LoadDylibCommand {
         cmd_size,
+        kind,
         name,
         timestamp,
         current_version,
@@ -422,10 +1195,21 @@ fn parse_cmd_load_dylib(reader: &mut DataReader, cmd_size: usize) -> Result<Load
     }))
 }
 
+fn parse_cmd_rpath(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
+    let path_offset = reader.read_u32() as usize;
+    if path_offset != 12 {
+        return Err(format!("expected rpath at 12, got {}", path_offset));
+    }
+    let path = clean_string(&reader.read_utf8_string(cmd_size.saturating_sub(12)));
+    Ok(LoadCommand::Rpath(RpathCommand { cmd_size, path }))
+}
+
 fn parse_cmd_dylinker(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
     let name_offset = reader.read_i32() as usize;
-    assert_eq!(12, name_offset);
-    let name = clean_string(&reader.read_utf8_string(cmd_size - 12));
+    if name_offset != 12 {
+        return Err(format!("expected dylinker name at 12, got {}", name_offset));
+    }
+    let name = clean_string(&reader.read_utf8_string(remaining_cmd_bytes(cmd_size, 12)?));
     Ok(LoadCommand::Dylinker(DylinkerCommand { cmd_size, name }))
 }
 
@@ -442,6 +1226,15 @@ fn parse_cmd_segment_64(reader: &mut DataReader, cmd_size: usize) -> Result<Load
     let n_sects = reader.read_u32();
     let flags = reader.read_u32();
 
+    let sections_fit = (n_sects as usize)
+        .checked_mul(SECTION_64_SIZE)
+        .is_some_and(|size| SEGMENT_64_SIZE + size <= cmd_size);
+    if !sections_fit {
+        return Err(format!(
+            "expected {} sections in the segment command of 0x{:X} bytes",
+            n_sects, cmd_size
+        ));
+    }
     let mut sections = Vec::with_capacity(n_sects as usize);
     for _ in 0..n_sects {
         let sec = parse_section_64(reader)?;
@@ -449,7 +1242,7 @@ fn parse_cmd_segment_64(reader: &mut DataReader, cmd_size: usize) -> Result<Load
     }
 
     //assure reader is at the end of the load command
-    reader.skip(cmd_size - (reader.offset() - start) - 8);
+    reader.skip(remaining_cmd_bytes(cmd_size, reader.offset() - start + 8)?);
 
     Ok(LoadCommand::Segment64(Segment64Command {
         cmd_size,
@@ -466,6 +1259,16 @@ fn parse_cmd_segment_64(reader: &mut DataReader, cmd_size: usize) -> Result<Load
     }))
 }
 
+/// The bytes of the command after the first `read` bytes, `cmd` and `cmdsize` included.
+fn remaining_cmd_bytes(cmd_size: usize, read: usize) -> Result<usize, String> {
+    cmd_size.checked_sub(read).ok_or_else(|| {
+        format!(
+            "expected load command size of at least 0x{:X}, got 0x{:X}",
+            read, cmd_size
+        )
+    })
+}
+
 fn parse_section_64(reader: &mut DataReader) -> Result<Section64, String> {
     let name = clean_string(&reader.read_utf8_string(16));
     let seg_name = clean_string(&reader.read_utf8_string(16));
@@ -491,50 +1294,326 @@ fn parse_section_64(reader: &mut DataReader) -> Result<Section64, String> {
 }
 
 fn parse_cmd_uuid(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
-    Ok(LoadCommand::Uuid(UuidCommand { cmd_size }))
+    let mut uuid = [0; 16];
+    for b in uuid.iter_mut() {
+        *b = reader.read_u8();
+    }
+    reader.skip(remaining_cmd_bytes(cmd_size, 24)?);
+    Ok(LoadCommand::Uuid(UuidCommand { cmd_size, uuid }))
 }
 
 fn parse_cmd_code_signature(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    let data_off = reader.read_u32();
+    let data_size = reader.read_u32();
+    reader.skip(remaining_cmd_bytes(cmd_size, 16)?);
+    let signature = parse_code_signature(reader.data, data_off, data_size);
     Ok(LoadCommand::CodeSignature(CodeSignatureCommand {
         cmd_size,
+        data_off,
+        data_size,
+        signature,
     }))
 }
 
+fn parse_code_signature(data: &[u8], off: u32, size: u32) -> Result<CodeSignature, String> {
+    let start = off as usize;
+    let end = start + size as usize;
+    if end > data.len() {
+        return Err(format!(
+            "code signature out of bounds: ends at 0x{:x}, file has 0x{:x} bytes",
+            end,
+            data.len()
+        ));
+    }
+    let blob = &data[start..end];
+
+    let magic = read_be_u32(blob, 0)?;
+    if magic != CSMAGIC_EMBEDDED_SIGNATURE {
+        return Err(format!("unexpected signature magic 0x{:x}", magic));
+    }
+    let length = read_be_u32(blob, 4)?;
+    let count = read_be_u32(blob, 8)?;
+    let mut blobs = Vec::new();
+    for i in 0..count as usize {
+        blobs.push(BlobIndex {
+            slot: read_be_u32(blob, 12 + i * 8)?,
+            offset: read_be_u32(blob, 16 + i * 8)?,
+        });
+    }
+
+    let code_directory = match blobs.iter().find(|b| b.slot == CSSLOT_CODEDIRECTORY) {
+        Some(index) => Some(parse_code_directory(blob, index.offset as usize)?),
+        None => None,
+    };
+    let blob_data = |slot: u32, expected_magic: u32| {
+        let index = blobs.iter().find(|b| b.slot == slot)?;
+        Some(entitlements_blob(
+            blob,
+            index.offset as usize,
+            expected_magic,
+        ))
+    };
+    let entitlements = blob_data(CSSLOT_ENTITLEMENTS, CSMAGIC_EMBEDDED_ENTITLEMENTS)
+        .map(|data| data.map(|xml| String::from_utf8_lossy(xml).to_string()));
+    let der_entitlements = blob_data(CSSLOT_DER_ENTITLEMENTS, CSMAGIC_EMBEDDED_DER_ENTITLEMENTS)
+        .map(|data| data.and_then(parse_der_entitlements));
+    Ok(CodeSignature {
+        length,
+        blobs,
+        code_directory,
+        entitlements,
+        der_entitlements,
+    })
+}
+
+/// The data of an entitlements blob, after its magic and length.
+fn entitlements_blob(blob: &[u8], off: usize, expected_magic: u32) -> Result<&[u8], String> {
+    let magic = read_be_u32(blob, off)?;
+    if magic != expected_magic {
+        return Err(format!("unexpected entitlements magic 0x{:x}", magic));
+    }
+    let length = read_be_u32(blob, off + 4)? as usize;
+    blob.get(off + 8..off + length.max(8))
+        .ok_or_else(|| format!("signature truncated at 0x{:x}", off + length))
+}
+
+const DER_BOOLEAN: u8 = 0x01;
+const DER_INTEGER: u8 = 0x02;
+const DER_UTF8_STRING: u8 = 0x0c;
+const DER_SEQUENCE: u8 = 0x30;
+// [APPLICATION 16], the version and the dictionary
+const DER_ENTITLEMENTS: u8 = 0x70;
+// [CONTEXT 16], a sequence of key value sequences
+const DER_DICT: u8 = 0xb0;
+
+/// Decodes the DER entitlements, `[APPLICATION 16] { version, dict }`.
+fn parse_der_entitlements(data: &[u8]) -> Result<EntitlementValue, String> {
+    let (tag, content, _) = read_der(data, 0)?;
+    if tag != DER_ENTITLEMENTS {
+        return Err(format!("unexpected DER entitlements tag 0x{:x}", tag));
+    }
+    let (tag, _, next) = read_der(content, 0)?;
+    if tag != DER_INTEGER {
+        return Err(format!(
+            "expected the DER entitlements version, got tag 0x{:x}",
+            tag
+        ));
+    }
+    let (tag, dict, _) = read_der(content, next)?;
+    parse_der_value(tag, dict)
+}
+
+fn parse_der_value(tag: u8, content: &[u8]) -> Result<EntitlementValue, String> {
+    match tag {
+        DER_BOOLEAN => Ok(EntitlementValue::Bool(
+            content.first().is_some_and(|b| *b != 0),
+        )),
+        DER_INTEGER => {
+            if content.is_empty() || content.len() > 8 {
+                return Err(format!(
+                    "unsupported DER integer of {} bytes",
+                    content.len()
+                ));
+            }
+            // two's complement, sign extended from the first byte
+            let init = if content[0] & 0x80 != 0 { -1 } else { 0 };
+            let value = content
+                .iter()
+                .fold(init, |value: i64, b| (value << 8) | *b as i64);
+            Ok(EntitlementValue::Integer(value))
+        }
+        DER_UTF8_STRING => Ok(EntitlementValue::String(
+            String::from_utf8_lossy(content).to_string(),
+        )),
+        DER_SEQUENCE => {
+            let mut values = Vec::new();
+            let mut offset = 0;
+            while offset < content.len() {
+                let (tag, element, next) = read_der(content, offset)?;
+                values.push(parse_der_value(tag, element)?);
+                offset = next;
+            }
+            Ok(EntitlementValue::Array(values))
+        }
+        DER_DICT => {
+            let mut entries = Vec::new();
+            let mut offset = 0;
+            while offset < content.len() {
+                let (tag, entry, next) = read_der(content, offset)?;
+                if tag != DER_SEQUENCE {
+                    return Err(format!(
+                        "expected a DER dictionary entry, got tag 0x{:x}",
+                        tag
+                    ));
+                }
+                let (tag, key, value_offset) = read_der(entry, 0)?;
+                if tag != DER_UTF8_STRING {
+                    return Err(format!(
+                        "expected a DER dictionary key, got tag 0x{:x}",
+                        tag
+                    ));
+                }
+                let (tag, value, _) = read_der(entry, value_offset)?;
+                entries.push((
+                    String::from_utf8_lossy(key).to_string(),
+                    parse_der_value(tag, value)?,
+                ));
+                offset = next;
+            }
+            Ok(EntitlementValue::Dict(entries))
+        }
+        _ => Err(format!("unsupported DER tag 0x{:x}", tag)),
+    }
+}
+
+/// Reads the DER element at `offset`, returns its tag, its content and the
+/// offset after it.
+fn read_der(data: &[u8], offset: usize) -> Result<(u8, &[u8], usize), String> {
+    let truncated = || format!("DER entitlements truncated at 0x{:x}", offset);
+    let tag = *data.get(offset).ok_or_else(truncated)?;
+    let first = *data.get(offset + 1).ok_or_else(truncated)?;
+    let (length, start) = if first & 0x80 == 0 {
+        (first as usize, offset + 2)
+    } else {
+        // long form, the low bits are the number of length bytes
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 {
+            return Err(format!("unsupported DER length at 0x{:x}", offset));
+        }
+        let bytes = data.get(offset + 2..offset + 2 + n).ok_or_else(truncated)?;
+        let length = bytes
+            .iter()
+            .fold(0, |length, b| (length << 8) | *b as usize);
+        (length, offset + 2 + n)
+    };
+    let content = data.get(start..start + length).ok_or_else(truncated)?;
+    Ok((tag, content, start + length))
+}
+
+fn parse_code_directory(blob: &[u8], off: usize) -> Result<CodeDirectory, String> {
+    let magic = read_be_u32(blob, off)?;
+    if magic != CSMAGIC_CODEDIRECTORY {
+        return Err(format!("unexpected code directory magic 0x{:x}", magic));
+    }
+    let version = read_be_u32(blob, off + 8)?;
+    let ident_offset = read_be_u32(blob, off + 20)? as usize;
+    let team_id = if version >= CS_SUPPORTSTEAMID {
+        match read_be_u32(blob, off + 48)? {
+            0 => None,
+            team_offset => Some(read_c_string(blob, off + team_offset as usize)),
+        }
+    } else {
+        None
+    };
+    Ok(CodeDirectory {
+        version,
+        flags: read_be_u32(blob, off + 12)?,
+        identifier: read_c_string(blob, off + ident_offset),
+        team_id,
+        hash_size: read_byte(blob, off + 36)?,
+        hash_type: read_byte(blob, off + 37)?,
+        special_slots: read_be_u32(blob, off + 24)?,
+        code_slots: read_be_u32(blob, off + 28)?,
+        code_limit: read_be_u32(blob, off + 32)?,
+        page_size: read_byte(blob, off + 39)?,
+    })
+}
+
 fn parse_cmd_build_version(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
-    Ok(LoadCommand::BuildVersion(BuildVersionCommand { cmd_size }))
+    let start = reader.offset();
+    let platform = reader.read_u32();
+    let min_os = reader.read_u32();
+    let sdk = reader.read_u32();
+    let n_tools = reader.read_u32();
+    let tools_fit = (n_tools as usize)
+        .checked_mul(8)
+        .is_some_and(|size| 24 + size <= cmd_size);
+    if !tools_fit {
+        return Err(format!(
+            "expected {} tools in the build version command of 0x{:X} bytes",
+            n_tools, cmd_size
+        ));
+    }
+    let mut tools = Vec::with_capacity(n_tools as usize);
+    for _ in 0..n_tools {
+        let tool = reader.read_u32();
+        let version = reader.read_u32();
+        tools.push(BuildToolVersion { tool, version });
+    }
+    reader.skip(remaining_cmd_bytes(cmd_size, reader.offset() - start + 8)?);
+    Ok(LoadCommand::BuildVersion(BuildVersionCommand {
+        cmd_size,
+        platform,
+        min_os,
+        sdk,
+        tools,
+    }))
 }
 
 fn parse_cmd_function_starts(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    let data_off = reader.read_u32();
+    let data_size = reader.read_u32();
+    reader.skip(remaining_cmd_bytes(cmd_size, 16)?);
+    let offsets = parse_function_starts(reader.data, data_off, data_size);
     Ok(LoadCommand::FunctionStarts(FunctionStartsCommand {
         cmd_size,
+        data_off,
+        data_size,
+        offsets,
     }))
 }
 
+/// Decodes the ULEB128 deltas between the function starts, the first delta is
+/// from the start of `__TEXT`. A 0 delta (or the padding) ends the list.
+fn parse_function_starts(data: &[u8], off: u32, size: u32) -> Result<Vec<u64>, String> {
+    let start = off as usize;
+    let end = start + size as usize;
+    if end > data.len() {
+        return Err(format!(
+            "function starts out of bounds: ends at 0x{:x}, file has 0x{:x} bytes",
+            end,
+            data.len()
+        ));
+    }
+    let deltas = &data[start..end];
+
+    let mut result = Vec::new();
+    let mut pos = 0;
+    let mut offset: u64 = 0;
+    while pos < deltas.len() {
+        let delta = read_uleb128(deltas, &mut pos)?;
+        if delta == 0 {
+            break;
+        }
+        offset = offset.wrapping_add(delta);
+        result.push(offset);
+    }
+    Ok(result)
+}
+
 fn parse_cmd_source_version(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    let version = reader.read_u64();
+    reader.skip(remaining_cmd_bytes(cmd_size, 16)?);
     Ok(LoadCommand::SourceVersion(SourceVersionCommand {
         cmd_size,
+        version,
     }))
 }
 
 fn parse_cmd_data_in_code(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    reader.skip(remaining_cmd_bytes(cmd_size, 8)?);
     Ok(LoadCommand::DataInCode(DataInCodeCommand { cmd_size }))
 }
 
@@ -542,20 +1621,205 @@ fn parse_cmd_dyld_info_only(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
-    Ok(LoadCommand::DyldInfoOnly(DyldInfoOnlyCommand { cmd_size }))
+    let rebase_off = reader.read_u32();
+    let rebase_size = reader.read_u32();
+    let bind_off = reader.read_u32();
+    let bind_size = reader.read_u32();
+    let weak_bind_off = reader.read_u32();
+    let weak_bind_size = reader.read_u32();
+    let lazy_bind_off = reader.read_u32();
+    let lazy_bind_size = reader.read_u32();
+    let export_off = reader.read_u32();
+    let export_size = reader.read_u32();
+    reader.skip(remaining_cmd_bytes(cmd_size, 48)?);
+
+    let binds = parse_binds(reader.data, bind_off, bind_size, false).and_then(|mut binds| {
+        binds.extend(parse_binds(
+            reader.data,
+            lazy_bind_off,
+            lazy_bind_size,
+            true,
+        )?);
+        Ok(binds)
+    });
+    Ok(LoadCommand::DyldInfoOnly(DyldInfoOnlyCommand {
+        cmd_size,
+        rebase_off,
+        rebase_size,
+        bind_off,
+        bind_size,
+        weak_bind_off,
+        weak_bind_size,
+        lazy_bind_off,
+        lazy_bind_size,
+        export_off,
+        export_size,
+        binds,
+    }))
+}
+
+/// Runs the bind opcodes at `off` and collects the binds. In the lazy bind
+/// info BIND_OPCODE_DONE only ends one entry, not the whole stream.
+/// Threaded binds are not supported.
+fn parse_binds(data: &[u8], off: u32, size: u32, lazy: bool) -> Result<Vec<Bind>, String> {
+    let start = off as usize;
+    let end = start + size as usize;
+    if end > data.len() {
+        return Err(format!(
+            "bind info out of bounds: ends at 0x{:x}, file has 0x{:x} bytes",
+            end,
+            data.len()
+        ));
+    }
+    let opcodes = &data[start..end];
+
+    let mut result = Vec::new();
+    let mut pos = 0;
+    let mut symbol = String::new();
+    let mut dylib_ordinal = 0;
+    let mut segment = 0;
+    let mut offset: u64 = 0;
+    while pos < opcodes.len() {
+        let byte = opcodes[pos];
+        pos += 1;
+        let immediate = byte & BIND_IMMEDIATE_MASK;
+        // the offset of the bind done by the opcode, if any
+        let bind_offset = match byte & BIND_OPCODE_MASK {
+            BIND_OPCODE_DONE if lazy => None, // next entry
+            BIND_OPCODE_DONE => break,
+            BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => {
+                dylib_ordinal = immediate as i64;
+                None
+            }
+            BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                dylib_ordinal = read_uleb128(opcodes, &mut pos)? as i64;
+                None
+            }
+            BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                // sign extended 4 bit value, 0 stays 0
+                dylib_ordinal = if immediate == 0 {
+                    0
+                } else {
+                    (immediate | BIND_OPCODE_MASK) as i8 as i64
+                };
+                None
+            }
+            BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                let name_end = opcodes[pos..]
+                    .iter()
+                    .position(|b| *b == 0)
+                    .map_or(opcodes.len(), |i| pos + i);
+                symbol = String::from_utf8_lossy(&opcodes[pos..name_end]).to_string();
+                pos = name_end + 1;
+                None
+            }
+            BIND_OPCODE_SET_TYPE_IMM => None, // only pointers are bound
+            BIND_OPCODE_SET_ADDEND_SLEB => {
+                read_uleb128(opcodes, &mut pos)?;
+                None
+            }
+            BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                segment = immediate;
+                offset = read_uleb128(opcodes, &mut pos)?;
+                None
+            }
+            BIND_OPCODE_ADD_ADDR_ULEB => {
+                offset = offset.wrapping_add(read_uleb128(opcodes, &mut pos)?);
+                None
+            }
+            BIND_OPCODE_DO_BIND => {
+                let bind_offset = offset;
+                offset = offset.wrapping_add(POINTER_SIZE);
+                Some(bind_offset)
+            }
+            BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => {
+                let bind_offset = offset;
+                let add = read_uleb128(opcodes, &mut pos)?;
+                offset = offset.wrapping_add(POINTER_SIZE).wrapping_add(add);
+                Some(bind_offset)
+            }
+            BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => {
+                let bind_offset = offset;
+                let add = immediate as u64 * POINTER_SIZE;
+                offset = offset.wrapping_add(POINTER_SIZE).wrapping_add(add);
+                Some(bind_offset)
+            }
+            BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                // one bind per symbol is enough, the single pointers are not of interest
+                let bind_offset = offset;
+                let count = read_uleb128(opcodes, &mut pos)?;
+                let skip = read_uleb128(opcodes, &mut pos)?;
+                let step = POINTER_SIZE.wrapping_add(skip);
+                offset = offset.wrapping_add(step.wrapping_mul(count));
+                Some(bind_offset)
+            }
+            // threaded binds (arm64e) and unknown opcodes end the scan, the
+            // binds found so far are kept
+            BIND_OPCODE_THREADED => break,
+            _ => break,
+        };
+        if let Some(offset) = bind_offset {
+            result.push(Bind {
+                symbol: symbol.clone(),
+                dylib_ordinal,
+                segment,
+                offset,
+                lazy,
+            });
+        }
+    }
+    Ok(result)
+}
+
+fn read_uleb128(data: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let Some(byte) = data.get(*pos) else {
+            return Err("truncated uleb128".to_string());
+        };
+        *pos += 1;
+        if shift < 64 {
+            result |= ((byte & 0x7f) as u64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
 }
 
 fn parse_cmd_main(reader: &mut DataReader, cmd_size: usize) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
-    Ok(LoadCommand::Main(MainCommand { cmd_size }))
+    let entry_offset = reader.read_u64();
+    let stack_size = reader.read_u64();
+    reader.skip(remaining_cmd_bytes(cmd_size, 24)?);
+    Ok(LoadCommand::Main(MainCommand {
+        cmd_size,
+        entry_offset,
+        stack_size,
+    }))
+}
+
+fn parse_cmd_unknown(
+    reader: &mut DataReader,
+    cmd: u32,
+    cmd_size: usize,
+) -> Result<LoadCommand, String> {
+    let start = reader.offset() - 8;
+    let data = reader.slice(start, start + cmd_size).to_vec();
+    reader.skip(remaining_cmd_bytes(cmd_size, 8)?);
+    Ok(LoadCommand::Unknow(UnknownCommand {
+        cmd,
+        cmd_size,
+        data,
+    }))
 }
 
 fn parse_cmd_dyld_exports_trie(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    reader.skip(remaining_cmd_bytes(cmd_size, 8)?);
     Ok(LoadCommand::LinkeditData(LinkeditDataCommand { cmd_size }))
 }
 
@@ -563,7 +1827,7 @@ fn parse_cmd_dyld_chained_fixups(
     reader: &mut DataReader,
     cmd_size: usize,
 ) -> Result<LoadCommand, String> {
-    reader.skip(cmd_size - 8);
+    reader.skip(remaining_cmd_bytes(cmd_size, 8)?);
     Ok(LoadCommand::LinkeditData(LinkeditDataCommand { cmd_size }))
 }
 
@@ -572,15 +1836,20 @@ fn parse_cmd_dyld_chained_fixups(
 pub struct DataReader<'a> {
     data: &'a [u8],
     offset: usize,
+    endianness: Endianness,
 }
 
 impl DataReader<'_> {
-    pub fn new(data: &[u8]) -> DataReader<'_> {
-        DataReader::new_with_offset(data, 0)
+    pub fn new(data: &[u8], endianness: Endianness) -> DataReader<'_> {
+        DataReader::new_with_offset(data, 0, endianness)
     }
 
-    pub fn new_with_offset(data: &[u8], offset: usize) -> DataReader<'_> {
-        DataReader { data, offset }
+    pub fn new_with_offset(data: &[u8], offset: usize, endianness: Endianness) -> DataReader<'_> {
+        DataReader {
+            data,
+            offset,
+            endianness,
+        }
     }
 }
 
@@ -593,53 +1862,58 @@ impl DataReader<'_> {
     }
 
     pub fn read_u64(&mut self) -> u64 {
-        let u = u64::from_le_bytes(
-            self.data[self.offset..(self.offset + 8)]
-                .try_into()
-                .unwrap(),
-        );
+        let bytes = self.data[self.offset..(self.offset + 8)]
+            .try_into()
+            .unwrap();
         self.offset += 8;
-        u
+        match self.endianness {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes),
+        }
     }
 
     pub fn read_u32(&mut self) -> u32 {
-        let u = u32::from_le_bytes(
-            self.data[self.offset..(self.offset + 4)]
-                .try_into()
-                .unwrap(),
-        );
+        let bytes = self.data[self.offset..(self.offset + 4)]
+            .try_into()
+            .unwrap();
         self.offset += 4;
-        u
+        match self.endianness {
+            Endianness::Little => u32::from_le_bytes(bytes),
+            Endianness::Big => u32::from_be_bytes(bytes),
+        }
     }
 
     pub fn read_i32(&mut self) -> i32 {
-        let i = i32::from_le_bytes(
-            self.data[self.offset..(self.offset + 4)]
-                .try_into()
-                .unwrap(),
-        );
+        let bytes = self.data[self.offset..(self.offset + 4)]
+            .try_into()
+            .unwrap();
         self.offset += 4;
-        i
+        match self.endianness {
+            Endianness::Little => i32::from_le_bytes(bytes),
+            Endianness::Big => i32::from_be_bytes(bytes),
+        }
     }
 
     pub fn read_u16(&mut self) -> u16 {
-        let u = u16::from_le_bytes(
-            self.data[self.offset..(self.offset + 2)]
-                .try_into()
-                .unwrap(),
-        );
+        let bytes = self.data[self.offset..(self.offset + 2)]
+            .try_into()
+            .unwrap();
         self.offset += 2;
-        u
+        match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        }
     }
 
     pub fn read_i16(&mut self) -> i16 {
-        let i = i16::from_le_bytes(
-            self.data[self.offset..(self.offset + 2)]
-                .try_into()
-                .unwrap(),
-        );
+        let bytes = self.data[self.offset..(self.offset + 2)]
+            .try_into()
+            .unwrap();
         self.offset += 2;
-        i
+        match self.endianness {
+            Endianness::Little => i16::from_le_bytes(bytes),
+            Endianness::Big => i16::from_be_bytes(bytes),
+        }
     }
 
     pub fn read_u8(&mut self) -> u8 {
@@ -669,8 +1943,205 @@ impl DataReader<'_> {
     pub fn offset(&self) -> usize {
         return self.offset;
     }
+
+    /// An error for the last `len` bytes read.
+    pub fn error_at_last(&self, len: usize, message: String) -> ParseError {
+        ParseError::new(self.offset - len, message)
+    }
+}
+
+fn read_be_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| format!("signature truncated at 0x{:x}", offset))
 }
 
+fn read_byte(data: &[u8], offset: usize) -> Result<u8, String> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| format!("signature truncated at 0x{:x}", offset))
+}
+
+// fixed size names are padded with NULs, anything after the first NUL is junk
 fn clean_string(str: &str) -> String {
-    str.replace('\0', "")
+    match str.find('\0') {
+        Some(end) => str[..end].to_string(),
+        None => str.to_string(),
+    }
+}
+
+fn read_c_string(data: &[u8], offset: usize) -> String {
+    if offset >= data.len() {
+        return String::new();
+    }
+    let bytes = &data[offset..];
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::macho_bytes;
+
+    const LC_SEGMENT_64: u32 = 0x19;
+    const SEGMENT_COMMAND_SIZE: u32 = 72;
+
+    fn segment_command(name: &[u8; 16]) -> Vec<u8> {
+        let mut command = Vec::new();
+        command.extend_from_slice(&LC_SEGMENT_64.to_le_bytes());
+        command.extend_from_slice(&SEGMENT_COMMAND_SIZE.to_le_bytes());
+        command.extend_from_slice(name);
+        command.resize(SEGMENT_COMMAND_SIZE as usize, 0);
+        command
+    }
+
+    fn segment_name(name: &[u8; 16]) -> String {
+        let macho = load(&macho_bytes(&[segment_command(name)])).unwrap();
+        match &macho.load_commands[0] {
+            LoadCommand::Segment64(segment) => segment.name.clone(),
+            _ => panic!("expected a segment"),
+        }
+    }
+
+    #[test]
+    fn name_is_cut_at_the_first_nul() {
+        assert_eq!(segment_name(b"__TEXT\0junk\0\0\0\0\0"), "__TEXT");
+        assert_eq!(segment_name(b"\0__TEXT\0\0\0\0\0\0\0\0\0"), "");
+    }
+
+    #[test]
+    fn name_without_nul() {
+        assert_eq!(segment_name(b"0123456789ABCDEF"), "0123456789ABCDEF");
+    }
+
+    #[test]
+    fn name_with_high_bytes() {
+        assert_eq!(
+            segment_name(b"__T\xFFXT\0\0\0\0\0\0\0\0\0\0"),
+            "__T\u{FFFD}XT"
+        );
+    }
+
+    #[test]
+    fn big_endian_header_and_load_commands() {
+        let mut data = Vec::new();
+        for value in [
+            MAGIC_HEADER,
+            CPUType::PowerPC64 as u32,
+            0,
+            2,
+            1,
+            SEGMENT_COMMAND_SIZE,
+            0x1,
+            0,
+        ] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&LC_SEGMENT_64.to_be_bytes());
+        data.extend_from_slice(&SEGMENT_COMMAND_SIZE.to_be_bytes());
+        data.extend_from_slice(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+        data.extend_from_slice(&0x1_0000_0000u64.to_be_bytes());
+        data.extend_from_slice(&0x4000u64.to_be_bytes());
+        data.resize(HEADER_SIZE + SEGMENT_COMMAND_SIZE as usize, 0);
+        assert_eq!(
+            u32::from_le_bytes(data[0..4].try_into().unwrap()),
+            CIGAM_HEADER
+        );
+
+        let macho = load(&data).unwrap();
+        assert_eq!(macho.endianness, Endianness::Big);
+        assert_eq!(macho.header.magic, MAGIC_HEADER);
+        assert_eq!(macho.header.cpu_type, CPUType::PowerPC64);
+        assert!(matches!(macho.header.file_type, FileType::MhExecuted));
+        assert_eq!(macho.header.no_cmds, 1);
+        assert_eq!(macho.header.size_of_cmds, SEGMENT_COMMAND_SIZE as usize);
+        assert!(matches!(
+            macho.header.flags.as_slice(),
+            [HeaderFlag::MH_NOUNDEFS]
+        ));
+        match &macho.load_commands[0] {
+            LoadCommand::Segment64(segment) => {
+                assert_eq!(segment.name, "__TEXT");
+                assert_eq!(segment.vm_addr, 0x1_0000_0000);
+                assert_eq!(segment.vm_size, 0x4000);
+            }
+            _ => panic!("expected a segment"),
+        }
+    }
+
+    #[test]
+    fn little_endian_header() {
+        let macho = load(&macho_bytes(&[segment_command(
+            b"__TEXT\0\0\0\0\0\0\0\0\0\0",
+        )]))
+        .unwrap();
+        assert_eq!(macho.endianness, Endianness::Little);
+        assert_eq!(macho.header.cpu_type, CPUType::ARM64);
+    }
+
+    #[test]
+    fn load_command_smaller_than_its_fields() {
+        let mut uuid = crate::test_util::uuid_command([0xAB; 16]);
+        uuid[4..8].copy_from_slice(&16u32.to_le_bytes());
+        uuid.truncate(16);
+        let err = load(&macho_bytes(&[uuid])).err().unwrap();
+        assert_eq!(err.offset, HEADER_SIZE + 4);
+        assert_eq!(
+            err.message,
+            "expected load command size of at least 0x18, got 0x10"
+        );
+
+        let mut segment = segment_command(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+        segment[4..8].copy_from_slice(&8u32.to_le_bytes());
+        segment.truncate(8);
+        let err = load(&macho_bytes(&[segment])).err().unwrap();
+        assert_eq!(err.offset, HEADER_SIZE + 4);
+    }
+
+    #[test]
+    fn segment_with_more_sections_than_its_size() {
+        let mut segment = segment_command(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+        segment[64..68].copy_from_slice(&1u32.to_le_bytes());
+        let err = load(&macho_bytes(&[segment])).err().unwrap();
+        assert_eq!(err.offset, HEADER_SIZE);
+        assert_eq!(
+            err.message,
+            "expected 1 sections in the segment command of 0x48 bytes"
+        );
+    }
+
+    #[test]
+    fn malformed_bind_info_keeps_the_command() {
+        let mut command = Vec::new();
+        for value in [LC_DYLD_INFO_ONLY, 48, 0, 0, 0x1000, 0x10] {
+            command.extend_from_slice(&value.to_le_bytes());
+        }
+        command.resize(48, 0);
+        let macho = load(&macho_bytes(&[command])).unwrap();
+        match &macho.load_commands[0] {
+            LoadCommand::DyldInfoOnly(info) => {
+                assert_eq!(info.bind_off, 0x1000);
+                assert!(info.binds.is_err());
+            }
+            _ => panic!("expected dyld info"),
+        }
+    }
+
+    #[test]
+    fn symbol_table_out_of_bounds_keeps_the_command() {
+        let mut command = Vec::new();
+        for value in [0x2u32, 24, 0x1000, 4, 0, 0] {
+            command.extend_from_slice(&value.to_le_bytes());
+        }
+        let macho = load(&macho_bytes(&[command])).unwrap();
+        match &macho.load_commands[0] {
+            LoadCommand::Symtab(symtab) => {
+                assert_eq!(symtab.n_syms, 4);
+                assert!(symtab.symbols.is_err());
+            }
+            _ => panic!("expected a symbol table"),
+        }
+        assert!(macho.symbols().is_empty());
+    }
 }
@@ -0,0 +1,39 @@
+//! Mach-O files for the unit tests, of this crate and of the crates using it
+//! with the `test-util` feature.
+
+use crate::{CPUType, MAGIC_HEADER};
+
+const LC_UUID: u32 = 0x1b;
+const UUID_COMMAND_SIZE: u32 = 24;
+
+/// A little-endian arm64 executable with the load commands, each given as
+/// its bytes.
+pub fn macho_bytes(commands: &[Vec<u8>]) -> Vec<u8> {
+    let size_of_cmds: usize = commands.iter().map(Vec::len).sum();
+    let mut data = Vec::new();
+    for value in [
+        MAGIC_HEADER,
+        CPUType::ARM64 as u32,
+        0, // all subtypes
+        2, // executable
+        commands.len() as u32,
+        size_of_cmds as u32,
+        0,
+        0,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    for command in commands {
+        data.extend_from_slice(command);
+    }
+    data
+}
+
+/// An LC_UUID command.
+pub fn uuid_command(uuid: [u8; 16]) -> Vec<u8> {
+    let mut command = Vec::new();
+    command.extend_from_slice(&LC_UUID.to_le_bytes());
+    command.extend_from_slice(&UUID_COMMAND_SIZE.to_le_bytes());
+    command.extend_from_slice(&uuid);
+    command
+}
@@ -0,0 +1,707 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Pe {
+    pub coff_header: CoffHeader,
+    pub optional_header: Option<OptionalHeader>,
+    pub section_headers: Vec<SectionHeader>,
+    pub imports: Vec<Import>,
+    /// Malformed parts that were skipped, e.g. an import table outside of
+    /// the sections.
+    pub warnings: Vec<String>,
+}
+
+/// Error of `load`, with the file offset of the malformed data.
+#[derive(Debug)]
+pub struct ParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error at 0x{:X}: {}", self.offset, self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.to_string()
+    }
+}
+
+// Headers
+
+/// `MZ` of the DOS header read as little-endian u16
+pub const DOS_MAGIC: u16 = 0x5a4d;
+
+/// `PE\0\0` read as little-endian u32
+pub const PE_SIGNATURE: u32 = 0x00004550;
+
+/// Offset of `e_lfanew` in the DOS header, the file offset of the PE signature.
+pub const E_LFANEW_OFFSET: usize = 0x3c;
+
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+const IMPORT_DESCRIPTOR_SIZE: usize = 20;
+
+/// Index of the import table in the data directories.
+const IMPORT_DIRECTORY: usize = 1;
+
+/// Upper bounds for the import table, a malformed file could otherwise
+/// make the parser walk through the whole file.
+const MAX_IMPORT_DLLS: usize = 4096;
+const MAX_IMPORT_FUNCTIONS: usize = 65536;
+
+#[derive(Serialize)]
+pub struct CoffHeader {
+    pub machine: Machine,
+    pub number_of_sections: u16,
+    pub time_date_stamp: u32,
+    pub pointer_to_symbol_table: u32,
+    pub number_of_symbols: u32,
+    pub size_of_optional_header: u16,
+    pub characteristics: u16,
+}
+
+pub const IMAGE_FILE_EXECUTABLE_IMAGE: u16 = 0x0002;
+pub const IMAGE_FILE_LARGE_ADDRESS_AWARE: u16 = 0x0020;
+pub const IMAGE_FILE_DLL: u16 = 0x2000;
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Machine {
+    Unknown,
+    I386,
+    AMD64,
+    ARM,
+    ARMNT,
+    ARM64,
+    IA64,
+    Other(u16),
+}
+
+#[derive(Serialize)]
+pub struct OptionalHeader {
+    pub format: Format,
+    pub linker_version: (u8, u8),
+    pub size_of_code: u32,
+    pub entry_point: u32,
+    pub image_base: u64,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub subsystem: Subsystem,
+    pub dll_characteristics: u16,
+    pub data_directories: Vec<DataDirectory>,
+}
+
+/// PE32 has 32-bit, PE32+ 64-bit image base and stack/heap sizes.
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Format {
+    Pe32,
+    Pe32Plus,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum Subsystem {
+    Unknown,
+    Native,
+    WindowsGui,
+    WindowsCui,
+    Os2Cui,
+    PosixCui,
+    WindowsCeGui,
+    EfiApplication,
+    EfiBootServiceDriver,
+    EfiRuntimeDriver,
+    EfiRom,
+    Xbox,
+    WindowsBootApplication,
+    Other(u16),
+}
+
+#[derive(Serialize, Copy, Clone)]
+pub struct DataDirectory {
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+// Section Headers
+
+#[derive(Serialize)]
+pub struct SectionHeader {
+    pub name: String,
+    pub virtual_size: u32,
+    pub virtual_address: u32,
+    pub size_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub characteristics: u32,
+}
+
+pub const IMAGE_SCN_CNT_CODE: u32 = 0x00000020;
+pub const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x00000040;
+pub const IMAGE_SCN_CNT_UNINITIALIZED_DATA: u32 = 0x00000080;
+pub const IMAGE_SCN_MEM_EXECUTE: u32 = 0x20000000;
+pub const IMAGE_SCN_MEM_READ: u32 = 0x40000000;
+pub const IMAGE_SCN_MEM_WRITE: u32 = 0x80000000;
+
+// Imports
+
+/// The functions imported from one DLL.
+#[derive(Serialize)]
+pub struct Import {
+    pub dll: String,
+    pub functions: Vec<ImportedFunction>,
+}
+
+#[derive(Serialize)]
+pub enum ImportedFunction {
+    Name(String),
+    Ordinal(u16),
+}
+
+pub fn load(data: &[u8]) -> Result<Pe, ParseError> {
+    let pe_offset = parse_dos_header(data)?;
+    let coff_header = parse_coff_header(data, pe_offset)?;
+    let optional_offset = pe_offset + 4 + COFF_HEADER_SIZE;
+    let optional_header = parse_optional_header(data, optional_offset, &coff_header)?;
+    let section_offset = optional_offset + coff_header.size_of_optional_header as usize;
+    let section_headers = parse_section_headers(data, section_offset, &coff_header)?;
+    let mut warnings = Vec::new();
+    let imports = match &optional_header {
+        Some(optional_header) => {
+            parse_imports(data, optional_header, &section_headers, &mut warnings)
+        }
+        None => Vec::new(),
+    };
+    Ok(Pe {
+        coff_header,
+        optional_header,
+        section_headers,
+        imports,
+        warnings,
+    })
+}
+
+/// Checks the DOS header and returns the offset of the PE signature.
+fn parse_dos_header(data: &[u8]) -> Result<usize, ParseError> {
+    if data.len() < E_LFANEW_OFFSET + 4 {
+        return Err(ParseError::new(
+            data.len(),
+            format!("expected dos header of {} bytes", E_LFANEW_OFFSET + 4),
+        ));
+    }
+    let mut reader = DataReader::new(data);
+    if reader.read_u16() != DOS_MAGIC {
+        return Err(ParseError::new(0, "expected MZ magic"));
+    }
+    reader.skip(E_LFANEW_OFFSET - 2);
+    let pe_offset = reader.read_u32() as usize;
+    if pe_offset.saturating_add(4 + COFF_HEADER_SIZE) > data.len() {
+        return Err(ParseError::new(
+            E_LFANEW_OFFSET,
+            format!(
+                "expected pe header at 0x{:X} inside the file of 0x{:X} bytes",
+                pe_offset,
+                data.len()
+            ),
+        ));
+    }
+    let mut reader = DataReader::new_with_offset(data, pe_offset);
+    if reader.read_u32() != PE_SIGNATURE {
+        return Err(ParseError::new(pe_offset, "expected PE signature"));
+    }
+    Ok(pe_offset)
+}
+
+fn parse_coff_header(data: &[u8], pe_offset: usize) -> Result<CoffHeader, ParseError> {
+    let mut reader = DataReader::new_with_offset(data, pe_offset + 4);
+    Ok(CoffHeader {
+        machine: parse_machine(reader.read_u16()),
+        number_of_sections: reader.read_u16(),
+        time_date_stamp: reader.read_u32(),
+        pointer_to_symbol_table: reader.read_u32(),
+        number_of_symbols: reader.read_u32(),
+        size_of_optional_header: reader.read_u16(),
+        characteristics: reader.read_u16(),
+    })
+}
+
+fn parse_machine(v: u16) -> Machine {
+    match v {
+        0x0000 => Machine::Unknown,
+        0x014c => Machine::I386,
+        0x8664 => Machine::AMD64,
+        0x01c0 => Machine::ARM,
+        0x01c4 => Machine::ARMNT,
+        0xaa64 => Machine::ARM64,
+        0x0200 => Machine::IA64,
+        _ => Machine::Other(v),
+    }
+}
+
+/// Parses the optional header, which is only missing in object files.
+fn parse_optional_header(
+    data: &[u8],
+    offset: usize,
+    coff_header: &CoffHeader,
+) -> Result<Option<OptionalHeader>, ParseError> {
+    let size = coff_header.size_of_optional_header as usize;
+    if size == 0 {
+        return Ok(None);
+    }
+    if offset.saturating_add(size) > data.len() {
+        return Err(ParseError::new(
+            data.len(),
+            format!("expected optional header of {} bytes, file ends", size),
+        ));
+    }
+
+    let mut reader = DataReader::new_with_offset(data, offset);
+    let format = match reader.read_u16() {
+        0x10b => Format::Pe32,
+        0x20b => Format::Pe32Plus,
+        v => {
+            return Err(ParseError::new(
+                offset,
+                format!(
+                    "expected optional header magic 0x10b or 0x20b, is 0x{:x}",
+                    v
+                ),
+            ));
+        }
+    };
+    // the fixed part up to and including the number of data directories
+    let fixed_size = match format {
+        Format::Pe32 => 96,
+        Format::Pe32Plus => 112,
+    };
+    if size < fixed_size {
+        return Err(ParseError::new(
+            offset,
+            format!(
+                "expected optional header of at least {} bytes, declared {}",
+                fixed_size, size
+            ),
+        ));
+    }
+
+    let linker_version = (reader.read_u8(), reader.read_u8());
+    let size_of_code = reader.read_u32();
+    reader.skip(8); // size of initialized and uninitialized data
+    let entry_point = reader.read_u32();
+    reader.skip(4); // base of code
+    let image_base = match format {
+        Format::Pe32 => {
+            reader.skip(4); // base of data, PE32 only
+            reader.read_u32() as u64
+        }
+        Format::Pe32Plus => reader.read_u64(),
+    };
+    let section_alignment = reader.read_u32();
+    let file_alignment = reader.read_u32();
+    reader.skip(12); // os, image and subsystem versions
+    reader.skip(4); // win32 version value
+    let size_of_image = reader.read_u32();
+    let size_of_headers = reader.read_u32();
+    reader.skip(4); // checksum
+    let subsystem = parse_subsystem(reader.read_u16());
+    let dll_characteristics = reader.read_u16();
+    reader.skip(match format {
+        Format::Pe32 => 16,
+        Format::Pe32Plus => 32,
+    }); // stack and heap sizes
+    reader.skip(4); // loader flags
+    let number_of_directories = reader.read_u32() as usize;
+
+    let available = (size - fixed_size) / 8;
+    let mut data_directories = Vec::with_capacity(number_of_directories.min(available));
+    for _ in 0..number_of_directories.min(available) {
+        data_directories.push(DataDirectory {
+            virtual_address: reader.read_u32(),
+            size: reader.read_u32(),
+        });
+    }
+
+    Ok(Some(OptionalHeader {
+        format,
+        linker_version,
+        size_of_code,
+        entry_point,
+        image_base,
+        section_alignment,
+        file_alignment,
+        size_of_image,
+        size_of_headers,
+        subsystem,
+        dll_characteristics,
+        data_directories,
+    }))
+}
+
+fn parse_subsystem(v: u16) -> Subsystem {
+    match v {
+        0 => Subsystem::Unknown,
+        1 => Subsystem::Native,
+        2 => Subsystem::WindowsGui,
+        3 => Subsystem::WindowsCui,
+        5 => Subsystem::Os2Cui,
+        7 => Subsystem::PosixCui,
+        9 => Subsystem::WindowsCeGui,
+        10 => Subsystem::EfiApplication,
+        11 => Subsystem::EfiBootServiceDriver,
+        12 => Subsystem::EfiRuntimeDriver,
+        13 => Subsystem::EfiRom,
+        14 => Subsystem::Xbox,
+        16 => Subsystem::WindowsBootApplication,
+        _ => Subsystem::Other(v),
+    }
+}
+
+fn parse_section_headers(
+    data: &[u8],
+    offset: usize,
+    coff_header: &CoffHeader,
+) -> Result<Vec<SectionHeader>, ParseError> {
+    let num = coff_header.number_of_sections as usize;
+    let end = offset.saturating_add(num * SECTION_HEADER_SIZE);
+    if end > data.len() {
+        return Err(ParseError::new(
+            data.len(),
+            format!("expected {} section headers, file ends", num),
+        ));
+    }
+
+    let mut result = Vec::with_capacity(num);
+    for i in 0..num {
+        let start = offset + i * SECTION_HEADER_SIZE;
+        let name = read_c_string(&data[start..start + 8], 0);
+        let mut reader = DataReader::new_with_offset(data, start + 8);
+        let virtual_size = reader.read_u32();
+        let virtual_address = reader.read_u32();
+        let size_of_raw_data = reader.read_u32();
+        let pointer_to_raw_data = reader.read_u32();
+        reader.skip(12); // relocations and line numbers
+        let characteristics = reader.read_u32();
+        result.push(SectionHeader {
+            name,
+            virtual_size,
+            virtual_address,
+            size_of_raw_data,
+            pointer_to_raw_data,
+            characteristics,
+        });
+    }
+    Ok(result)
+}
+
+/// Maps a relative virtual address to a file offset via the section that
+/// contains it.
+pub fn rva_to_offset(sections: &[SectionHeader], rva: u32) -> Option<usize> {
+    sections.iter().find_map(|section| {
+        let size = section.virtual_size.max(section.size_of_raw_data);
+        let start = section.virtual_address;
+        if rva >= start && rva - start < size {
+            let delta = rva - start;
+            if delta < section.size_of_raw_data {
+                return Some(section.pointer_to_raw_data as usize + delta as usize);
+            }
+        }
+        None
+    })
+}
+
+/// Parses the import table. One that is not inside a section, as in packed
+/// or damaged files, is left empty with a warning instead of failing the load.
+fn parse_imports(
+    data: &[u8],
+    optional_header: &OptionalHeader,
+    sections: &[SectionHeader],
+    warnings: &mut Vec<String>,
+) -> Vec<Import> {
+    let Some(directory) = optional_header.data_directories.get(IMPORT_DIRECTORY) else {
+        return Vec::new();
+    };
+    if directory.virtual_address == 0 || directory.size == 0 {
+        return Vec::new();
+    }
+    let Some(mut offset) = rva_to_offset(sections, directory.virtual_address) else {
+        warnings.push(format!(
+            "import table at RVA 0x{:X} is not in a section",
+            directory.virtual_address
+        ));
+        return Vec::new();
+    };
+
+    let mut result = Vec::new();
+    while offset + IMPORT_DESCRIPTOR_SIZE <= data.len() && result.len() < MAX_IMPORT_DLLS {
+        let mut reader = DataReader::new_with_offset(data, offset);
+        let lookup_table = reader.read_u32();
+        reader.skip(8); // time date stamp and forwarder chain
+        let name_rva = reader.read_u32();
+        let address_table = reader.read_u32();
+        if lookup_table == 0 && name_rva == 0 && address_table == 0 {
+            break;
+        }
+
+        let dll = rva_to_offset(sections, name_rva)
+            .map(|o| read_c_string(data, o))
+            .unwrap_or_default();
+        // the lookup table is missing in some linkers output, the address
+        // table has the same content in the file
+        let table = if lookup_table != 0 {
+            lookup_table
+        } else {
+            address_table
+        };
+        let functions = match rva_to_offset(sections, table) {
+            Some(table_offset) => {
+                parse_import_functions(data, table_offset, optional_header.format, sections)
+            }
+            None => Vec::new(),
+        };
+        result.push(Import { dll, functions });
+        offset += IMPORT_DESCRIPTOR_SIZE;
+    }
+    result
+}
+
+fn parse_import_functions(
+    data: &[u8],
+    table_offset: usize,
+    format: Format,
+    sections: &[SectionHeader],
+) -> Vec<ImportedFunction> {
+    let entry_size = match format {
+        Format::Pe32 => 4,
+        Format::Pe32Plus => 8,
+    };
+    let mut result = Vec::new();
+    let mut offset = table_offset;
+    while offset + entry_size <= data.len() && result.len() < MAX_IMPORT_FUNCTIONS {
+        let mut reader = DataReader::new_with_offset(data, offset);
+        let (entry, by_ordinal) = match format {
+            Format::Pe32 => {
+                let v = reader.read_u32();
+                (v as u64, v & 0x80000000 != 0)
+            }
+            Format::Pe32Plus => {
+                let v = reader.read_u64();
+                (v, v & 0x8000000000000000 != 0)
+            }
+        };
+        if entry == 0 {
+            break;
+        }
+        let function = if by_ordinal {
+            ImportedFunction::Ordinal(entry as u16)
+        } else {
+            // hint/name entry: 2 bytes hint followed by the name
+            let name = rva_to_offset(sections, entry as u32)
+                .map(|o| read_c_string(data, o + 2))
+                .unwrap_or_default();
+            ImportedFunction::Name(name)
+        };
+        result.push(function);
+        offset += entry_size;
+    }
+    result
+}
+
+fn read_c_string(data: &[u8], offset: usize) -> String {
+    if offset >= data.len() {
+        return String::new();
+    }
+    let bytes = &data[offset..];
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+// helper
+
+/// Reads little-endian values, PE files are always little-endian.
+pub struct DataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl DataReader<'_> {
+    pub fn new(data: &[u8]) -> DataReader<'_> {
+        DataReader::new_with_offset(data, 0)
+    }
+
+    pub fn new_with_offset(data: &[u8], offset: usize) -> DataReader<'_> {
+        DataReader { data, offset }
+    }
+}
+
+impl DataReader<'_> {
+    pub fn read_u64(&mut self) -> u64 {
+        let bytes = self.data[self.offset..(self.offset + 8)]
+            .try_into()
+            .unwrap();
+        self.offset += 8;
+        u64::from_le_bytes(bytes)
+    }
+
+    pub fn read_u32(&mut self) -> u32 {
+        let bytes = self.data[self.offset..(self.offset + 4)]
+            .try_into()
+            .unwrap();
+        self.offset += 4;
+        u32::from_le_bytes(bytes)
+    }
+
+    pub fn read_u16(&mut self) -> u16 {
+        let bytes = self.data[self.offset..(self.offset + 2)]
+            .try_into()
+            .unwrap();
+        self.offset += 2;
+        u16::from_le_bytes(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> u8 {
+        let u = self.data[self.offset];
+        self.offset += 1;
+        u
+    }
+
+    pub fn skip(&mut self, bytes: usize) {
+        self.offset += bytes;
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PE_OFFSET: usize = 0x40;
+    const TEXT_RVA: u32 = 0x1000;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// An executable with a `.text` section and the export and import data
+    /// directories, no imports if `import_rva` is 0.
+    fn pe_bytes(format: Format, image_base: u64, import_rva: u32) -> Vec<u8> {
+        let (magic, machine, fixed_size) = match format {
+            Format::Pe32 => (0x10bu16, 0x014cu16, 96),
+            Format::Pe32Plus => (0x20b, 0x8664, 112),
+        };
+        let optional_size = fixed_size + 2 * 8;
+        let mut data = vec![0; 0x400];
+        put(&mut data, 0, &DOS_MAGIC.to_le_bytes());
+        put(
+            &mut data,
+            E_LFANEW_OFFSET,
+            &(PE_OFFSET as u32).to_le_bytes(),
+        );
+        put(&mut data, PE_OFFSET, &PE_SIGNATURE.to_le_bytes());
+
+        let coff = PE_OFFSET + 4;
+        put(&mut data, coff, &machine.to_le_bytes());
+        put(&mut data, coff + 2, &1u16.to_le_bytes());
+        put(&mut data, coff + 16, &(optional_size as u16).to_le_bytes());
+        put(
+            &mut data,
+            coff + 18,
+            &IMAGE_FILE_EXECUTABLE_IMAGE.to_le_bytes(),
+        );
+
+        let optional = coff + COFF_HEADER_SIZE;
+        put(&mut data, optional, &magic.to_le_bytes());
+        put(&mut data, optional + 16, &TEXT_RVA.to_le_bytes());
+        match format {
+            Format::Pe32 => put(&mut data, optional + 28, &(image_base as u32).to_le_bytes()),
+            Format::Pe32Plus => put(&mut data, optional + 24, &image_base.to_le_bytes()),
+        }
+        put(&mut data, optional + 68, &3u16.to_le_bytes()); // windows console
+        put(&mut data, optional + fixed_size - 4, &2u32.to_le_bytes());
+        put(
+            &mut data,
+            optional + fixed_size + 8,
+            &import_rva.to_le_bytes(),
+        );
+        put(&mut data, optional + fixed_size + 12, &40u32.to_le_bytes());
+
+        let section = optional + optional_size;
+        put(&mut data, section, b".text");
+        put(&mut data, section + 8, &0x200u32.to_le_bytes());
+        put(&mut data, section + 12, &TEXT_RVA.to_le_bytes());
+        put(&mut data, section + 16, &0x200u32.to_le_bytes());
+        put(&mut data, section + 20, &0x200u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn pe32_header() {
+        let pe = load(&pe_bytes(Format::Pe32, 0x400000, 0)).unwrap();
+        assert_eq!(pe.coff_header.machine, Machine::I386);
+        let optional = pe.optional_header.unwrap();
+        assert_eq!(optional.format, Format::Pe32);
+        assert_eq!(optional.image_base, 0x400000);
+        assert_eq!(optional.entry_point, TEXT_RVA);
+        assert_eq!(optional.subsystem, Subsystem::WindowsCui);
+        assert_eq!(optional.data_directories.len(), 2);
+        assert_eq!(pe.section_headers[0].name, ".text");
+        assert_eq!(
+            rva_to_offset(&pe.section_headers, TEXT_RVA + 4),
+            Some(0x204)
+        );
+        assert!(pe.imports.is_empty());
+        assert!(pe.warnings.is_empty());
+    }
+
+    #[test]
+    fn pe32_plus_header() {
+        let pe = load(&pe_bytes(Format::Pe32Plus, 0x140000000, 0)).unwrap();
+        assert_eq!(pe.coff_header.machine, Machine::AMD64);
+        let optional = pe.optional_header.unwrap();
+        assert_eq!(optional.format, Format::Pe32Plus);
+        // above 4 GiB, only representable in PE32+
+        assert_eq!(optional.image_base, 0x140000000);
+        assert_eq!(optional.entry_point, TEXT_RVA);
+        assert_eq!(optional.subsystem, Subsystem::WindowsCui);
+        assert_eq!(optional.data_directories.len(), 2);
+        assert_eq!(pe.section_headers[0].name, ".text");
+    }
+
+    #[test]
+    fn error_at_the_pe_signature() {
+        let mut data = pe_bytes(Format::Pe32, 0x400000, 0);
+        data[PE_OFFSET] = b'X';
+        let err = load(&data).err().unwrap();
+        assert_eq!(err.offset, PE_OFFSET);
+        assert_eq!(
+            err.to_string(),
+            "parse error at 0x40: expected PE signature"
+        );
+    }
+
+    #[test]
+    fn import_table_outside_of_the_sections() {
+        let pe = load(&pe_bytes(Format::Pe32Plus, 0x140000000, 0x9000)).unwrap();
+        assert!(pe.imports.is_empty());
+        assert_eq!(
+            pe.warnings,
+            ["import table at RVA 0x9000 is not in a section"]
+        );
+    }
+}
@@ -101,7 +101,7 @@ impl GBViewWidget {
                 ui.end_row();
 
                 ui.label("Licensee Code:");
-                ui.label(&format!("{:?}", self.binary.header.licensee_code));
+                ui.label(&self.binary.header.licensee_code.to_string());
                 ui.end_row();
 
                 ui.label("Super Gameboy Flag:");
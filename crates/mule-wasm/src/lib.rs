@@ -0,0 +1,532 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Wasm {
+    pub version: u32,
+    pub sections: Vec<Section>,
+    pub types: Vec<FuncType>,
+    pub imports: Vec<Import>,
+    /// Type index of each function defined in the module
+    pub functions: Vec<u32>,
+    pub exports: Vec<Export>,
+}
+
+/// Error of `load`, with the file offset of the malformed data.
+#[derive(Debug)]
+pub struct ParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error at 0x{:X}: {}", self.offset, self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.to_string()
+    }
+}
+
+// Header
+
+/// `\0asm` read as little-endian u32
+pub const MAGIC_HEADER: u32 = 0x6d736100;
+
+/// The only version of the binary format, the component model uses others.
+pub const VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 8;
+
+/// Form of a function type in the type section.
+const FUNC_TYPE_FORM: u8 = 0x60;
+
+// Sections
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum SectionId {
+    Custom,
+    Type,
+    Import,
+    Function,
+    Table,
+    Memory,
+    Global,
+    Export,
+    Start,
+    Element,
+    Code,
+    Data,
+    DataCount,
+    Tag,
+    Other(u8),
+}
+
+#[derive(Serialize)]
+pub struct Section {
+    pub id: SectionId,
+    /// Name of a custom section, empty for all other sections
+    pub name: String,
+    /// File offset of the section contents
+    pub offset: usize,
+    pub size: usize,
+    /// Number of entries, only for sections that are a vector of entries
+    pub count: Option<u32>,
+}
+
+// Types
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+    V128,
+    FuncRef,
+    ExternRef,
+    Other(u8),
+}
+
+#[derive(Serialize)]
+pub struct FuncType {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+// Imports and Exports
+
+#[derive(Serialize)]
+pub struct Import {
+    pub module: String,
+    pub name: String,
+    pub kind: ImportKind,
+}
+
+#[derive(Serialize)]
+pub enum ImportKind {
+    /// Index of the function type
+    Function(u32),
+    Table(ValType, Limits),
+    Memory(Limits),
+    /// Type of the global and whether it is mutable
+    Global(ValType, bool),
+    /// Index of the function type of the exception tag
+    Tag(u32),
+}
+
+#[derive(Serialize, Copy, Clone)]
+pub struct Limits {
+    pub min: u64,
+    pub max: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct Export {
+    pub name: String,
+    pub kind: ExportKind,
+    /// Index into the function, table, memory, global or tag index space
+    pub index: u32,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+pub enum ExportKind {
+    Function,
+    Table,
+    Memory,
+    Global,
+    Tag,
+    Other(u8),
+}
+
+impl Wasm {
+    /// Number of imported functions, they come first in the function index
+    /// space.
+    pub fn imported_function_count(&self) -> usize {
+        self.imports
+            .iter()
+            .filter(|i| matches!(i.kind, ImportKind::Function(_)))
+            .count()
+    }
+
+    /// The type of the function with the `index` in the function index space
+    /// (imported functions first, then the functions of the module).
+    pub fn function_type(&self, index: u32) -> Option<&FuncType> {
+        let index = index as usize;
+        let mut imported = self.imports.iter().filter_map(|i| match i.kind {
+            ImportKind::Function(type_index) => Some(type_index),
+            _ => None,
+        });
+        let type_index = match imported.nth(index) {
+            Some(type_index) => type_index,
+            None => *self.functions.get(index - self.imported_function_count())?,
+        };
+        self.types.get(type_index as usize)
+    }
+}
+
+impl fmt::Display for ValType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValType::I32 => write!(f, "i32"),
+            ValType::I64 => write!(f, "i64"),
+            ValType::F32 => write!(f, "f32"),
+            ValType::F64 => write!(f, "f64"),
+            ValType::V128 => write!(f, "v128"),
+            ValType::FuncRef => write!(f, "funcref"),
+            ValType::ExternRef => write!(f, "externref"),
+            ValType::Other(v) => write!(f, "0x{:x}", v),
+        }
+    }
+}
+
+/// Formats the signature as `(i32, i32) -> i64`.
+impl fmt::Display for FuncType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |types: &[ValType]| {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(f, "({})", join(&self.params))?;
+        match self.results.len() {
+            0 => Ok(()),
+            1 => write!(f, " -> {}", self.results[0]),
+            _ => write!(f, " -> ({})", join(&self.results)),
+        }
+    }
+}
+
+pub fn load(data: &[u8]) -> Result<Wasm, ParseError> {
+    if data.len() < HEADER_SIZE {
+        return Err(ParseError::new(
+            data.len(),
+            format!("expected wasm header of {} bytes", HEADER_SIZE),
+        ));
+    }
+    let mut reader = DataReader::new(data);
+    if reader.read_u32()? != MAGIC_HEADER {
+        return Err(ParseError::new(0, "expected \\0asm magic"));
+    }
+    let version = reader.read_u32()?;
+    if version != VERSION {
+        return Err(ParseError::new(
+            4,
+            format!("expected wasm version {}, is {}", VERSION, version),
+        ));
+    }
+
+    let mut wasm = Wasm {
+        version,
+        sections: Vec::new(),
+        types: Vec::new(),
+        imports: Vec::new(),
+        functions: Vec::new(),
+        exports: Vec::new(),
+    };
+    while reader.offset() < data.len() {
+        let section_start = reader.offset();
+        let id = parse_section_id(reader.read_u8()?);
+        let size = reader.read_leb_u32()? as usize;
+        let offset = reader.offset();
+        let end = offset.saturating_add(size);
+        if end > data.len() {
+            return Err(ParseError::new(
+                section_start,
+                format!("expected section of {} bytes, file ends", size),
+            ));
+        }
+
+        // the contents are read with absolute offsets, but only up to the end
+        // of the section
+        let mut contents = DataReader::new_with_offset(&data[..end], offset);
+        let (name, count) = match id {
+            SectionId::Custom => (contents.read_name()?, None),
+            SectionId::Start | SectionId::Other(_) => (String::new(), None),
+            _ => (String::new(), Some(contents.read_leb_u32()?)),
+        };
+        match (id, count) {
+            (SectionId::Type, Some(count)) => wasm.types = parse_types(&mut contents, count)?,
+            (SectionId::Import, Some(count)) => wasm.imports = parse_imports(&mut contents, count)?,
+            (SectionId::Function, Some(count)) => {
+                wasm.functions = parse_functions(&mut contents, count)?
+            }
+            (SectionId::Export, Some(count)) => wasm.exports = parse_exports(&mut contents, count)?,
+            _ => { /* only the size and count are shown */ }
+        }
+        wasm.sections.push(Section {
+            id,
+            name,
+            offset,
+            size,
+            count,
+        });
+        reader.skip(size);
+    }
+    Ok(wasm)
+}
+
+fn parse_section_id(v: u8) -> SectionId {
+    match v {
+        0 => SectionId::Custom,
+        1 => SectionId::Type,
+        2 => SectionId::Import,
+        3 => SectionId::Function,
+        4 => SectionId::Table,
+        5 => SectionId::Memory,
+        6 => SectionId::Global,
+        7 => SectionId::Export,
+        8 => SectionId::Start,
+        9 => SectionId::Element,
+        10 => SectionId::Code,
+        11 => SectionId::Data,
+        12 => SectionId::DataCount,
+        13 => SectionId::Tag,
+        _ => SectionId::Other(v),
+    }
+}
+
+fn parse_val_type(v: u8) -> ValType {
+    match v {
+        0x7f => ValType::I32,
+        0x7e => ValType::I64,
+        0x7d => ValType::F32,
+        0x7c => ValType::F64,
+        0x7b => ValType::V128,
+        0x70 => ValType::FuncRef,
+        0x6f => ValType::ExternRef,
+        _ => ValType::Other(v),
+    }
+}
+
+fn parse_types(reader: &mut DataReader, count: u32) -> Result<Vec<FuncType>, ParseError> {
+    let mut result = Vec::new();
+    for _ in 0..count {
+        // the composite types of the gc proposal are not decoded
+        if reader.read_u8()? != FUNC_TYPE_FORM {
+            break;
+        }
+        let params = parse_val_types(reader)?;
+        let results = parse_val_types(reader)?;
+        result.push(FuncType { params, results });
+    }
+    Ok(result)
+}
+
+fn parse_val_types(reader: &mut DataReader) -> Result<Vec<ValType>, ParseError> {
+    let count = reader.read_leb_u32()?;
+    let mut result = Vec::new();
+    for _ in 0..count {
+        result.push(parse_val_type(reader.read_u8()?));
+    }
+    Ok(result)
+}
+
+fn parse_limits(reader: &mut DataReader) -> Result<Limits, ParseError> {
+    let flags = reader.read_u8()?;
+    let min = reader.read_leb_u64()?;
+    let max = if flags & 0x01 != 0 {
+        Some(reader.read_leb_u64()?)
+    } else {
+        None
+    };
+    Ok(Limits { min, max })
+}
+
+fn parse_imports(reader: &mut DataReader, count: u32) -> Result<Vec<Import>, ParseError> {
+    let mut result = Vec::new();
+    for _ in 0..count {
+        let module = reader.read_name()?;
+        let name = reader.read_name()?;
+        let kind_offset = reader.offset();
+        let kind = match reader.read_u8()? {
+            0x00 => ImportKind::Function(reader.read_leb_u32()?),
+            0x01 => {
+                let val_type = parse_val_type(reader.read_u8()?);
+                ImportKind::Table(val_type, parse_limits(reader)?)
+            }
+            0x02 => ImportKind::Memory(parse_limits(reader)?),
+            0x03 => {
+                let val_type = parse_val_type(reader.read_u8()?);
+                ImportKind::Global(val_type, reader.read_u8()? != 0)
+            }
+            0x04 => {
+                reader.skip(1); // attribute, always 0
+                ImportKind::Tag(reader.read_leb_u32()?)
+            }
+            v => {
+                return Err(ParseError::new(
+                    kind_offset,
+                    format!("expected import kind 0x0 to 0x4, is 0x{:x}", v),
+                ));
+            }
+        };
+        result.push(Import { module, name, kind });
+    }
+    Ok(result)
+}
+
+fn parse_functions(reader: &mut DataReader, count: u32) -> Result<Vec<u32>, ParseError> {
+    let mut result = Vec::new();
+    for _ in 0..count {
+        result.push(reader.read_leb_u32()?);
+    }
+    Ok(result)
+}
+
+fn parse_exports(reader: &mut DataReader, count: u32) -> Result<Vec<Export>, ParseError> {
+    let mut result = Vec::new();
+    for _ in 0..count {
+        let name = reader.read_name()?;
+        let kind = match reader.read_u8()? {
+            0x00 => ExportKind::Function,
+            0x01 => ExportKind::Table,
+            0x02 => ExportKind::Memory,
+            0x03 => ExportKind::Global,
+            0x04 => ExportKind::Tag,
+            v => ExportKind::Other(v),
+        };
+        let index = reader.read_leb_u32()?;
+        result.push(Export { name, kind, index });
+    }
+    Ok(result)
+}
+
+// helper
+
+/// Reads little-endian values and LEB128 numbers. Unlike the readers of the
+/// other formats it checks the bounds, the sizes of a wasm file are all
+/// variable length encoded.
+pub struct DataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl DataReader<'_> {
+    pub fn new(data: &[u8]) -> DataReader<'_> {
+        DataReader::new_with_offset(data, 0)
+    }
+
+    pub fn new_with_offset(data: &[u8], offset: usize) -> DataReader<'_> {
+        DataReader { data, offset }
+    }
+}
+
+impl<'a> DataReader<'a> {
+    pub fn read_u32(&mut self) -> Result<u32, ParseError> {
+        let bytes = self.read_bytes(4)?.try_into().unwrap();
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, ParseError> {
+        let u = *self.data.get(self.offset).ok_or_else(|| self.end_error())?;
+        self.offset += 1;
+        Ok(u)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let data = self.data;
+        let end = self.offset.saturating_add(len);
+        let bytes = data.get(self.offset..end).ok_or_else(|| self.end_error())?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 number.
+    pub fn read_leb_u64(&mut self) -> Result<u64, ParseError> {
+        let start = self.offset;
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 {
+                return Err(ParseError::new(
+                    start,
+                    "expected leb128 number of at most 64 bits",
+                ));
+            }
+            result |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    pub fn read_leb_u32(&mut self) -> Result<u32, ParseError> {
+        let start = self.offset;
+        let v = self.read_leb_u64()?;
+        u32::try_from(v).map_err(|_| ParseError::new(start, "expected leb128 number of 32 bits"))
+    }
+
+    /// Reads a name, a LEB128 length followed by UTF-8 bytes.
+    pub fn read_name(&mut self) -> Result<String, ParseError> {
+        let len = self.read_leb_u32()? as usize;
+        let bytes = self.read_bytes(len)?;
+        Ok(String::from_utf8_lossy(bytes).to_string())
+    }
+
+    pub fn skip(&mut self, bytes: usize) {
+        self.offset += bytes;
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn end_error(&self) -> ParseError {
+        ParseError::new(self.offset, "expected more data, the section or file ends")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(version: u32) -> Vec<u8> {
+        [MAGIC_HEADER.to_le_bytes(), version.to_le_bytes()].concat()
+    }
+
+    #[test]
+    fn empty_module() {
+        let wasm = load(&header(VERSION)).unwrap();
+        assert_eq!(wasm.version, VERSION);
+        assert!(wasm.sections.is_empty());
+    }
+
+    #[test]
+    fn error_at_the_version() {
+        let err = load(&header(2)).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "parse error at 0x4: expected wasm version 1, is 2"
+        );
+    }
+
+    #[test]
+    fn error_at_a_truncated_section() {
+        let mut data = header(VERSION);
+        // a type section of 16 bytes with only the count
+        data.extend_from_slice(&[1, 16, 0]);
+        let err = load(&data).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "parse error at 0x8: expected section of 16 bytes, file ends"
+        );
+    }
+}